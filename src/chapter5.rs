@@ -1,87 +1,183 @@
+use crate::chapter5_10::Date;
+use crate::chapter5_9::{Px, Unit};
+use std::fmt;
+use std::marker::PhantomData;
+
 // 需要 derive Debug trait
-#[derive(Debug)]
-struct User {
-    username: String,
-    email: String,
-    age: u32,
-    active: bool,
+#[derive(Debug, Clone)]
+pub struct User {
+    pub username: String,
+    pub email: String,
+    pub birth_date: Date, // 年齡用 age() 計算，不會過期
+    pub active: bool,
 }
 
-fn build_user(email: String, username: String) -> User {
+pub fn build_user(email: String, username: String, birth_date: Date) -> User {
     User {
         username, // 變數名與欄位名相同可簡寫
         email,
-        age: 25,
+        birth_date,
         active: true,
     }
 }
 
 // Method Syntax
-#[derive(Debug)]
-struct Rectangle {
-    width: u32,
-    height: u32,
+// U 是長度單位，預設為像素；不同單位的 Rectangle 是不同型別，混用會編譯錯誤
+#[derive(Clone, PartialEq, Eq)]
+pub struct Rectangle<U: Unit = Px> {
+    pub width: u32,
+    pub height: u32,
+    unit: PhantomData<U>,
 }
 
 impl Rectangle {
     // Associated Functions(關聯函式)
-    fn new(width: u32, height: u32) -> Rectangle {
-        Rectangle { width, height }
+    pub fn new(width: u32, height: u32) -> Rectangle {
+        Rectangle::of(width, height)
+    }
+}
+
+impl<U: Unit> Rectangle<U> {
+    // 指定單位：Rectangle::<Mm>::of(210, 297)
+    pub fn of(width: u32, height: u32) -> Rectangle<U> {
+        Rectangle {
+            width,
+            height,
+            unit: PhantomData,
+        }
     }
 
     //1. 不可變借用-只能讀取
-    fn area(&self) -> u32 {
-        self.width * self.height
+    // u32 * u32 最大約 1.8e19，放得進 u64，所以 area 一定不會溢位
+    pub fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
     }
 
-    fn can_hold(&self, other: &Rectangle) -> bool {
+    // 和 area 相同但用 checked_mul：u32 × u32 一定放得進 u64，目前永遠是 Some，
+    // 保留 Option 是為了之後寬高換成更大的型別時，呼叫端不用改
+    pub fn checked_area(&self) -> Option<u64> {
+        (self.width as u64).checked_mul(self.height as u64)
+    }
+
+    // 需要 u32 結果時使用，超過 u32 回傳 None
+    pub fn area_u32(&self) -> Option<u32> {
+        self.width.checked_mul(self.height)
+    }
+
+    pub fn saturating_area(&self) -> u32 {
+        self.width.saturating_mul(self.height)
+    }
+
+    pub fn can_hold(&self, other: &Rectangle<U>) -> bool {
         self.width > other.width && self.height > other.height
     }
 
+    // can_hold 是嚴格大於，can_fit 允許剛好一樣大
+    pub fn can_fit(&self, other: &Rectangle<U>) -> bool {
+        self.width >= other.width && self.height >= other.height
+    }
+
     // 2. 可變借用 - 需要修改
-    fn scale(&mut self, factor: u32) {
-        self.width *= factor;
-        self.height *= factor;
+    // release 模式下 `*=` 會默默 wrap，所以溢位時直接 panic
+    pub fn scale(&mut self, factor: u32) {
+        self.try_scale(factor).expect("Rectangle::scale 溢位");
+    }
+
+    // 溢位時回傳錯誤，self 保持不變
+    pub fn try_scale(&mut self, factor: u32) -> Result<(), ScaleOverflow> {
+        match (
+            self.width.checked_mul(factor),
+            self.height.checked_mul(factor),
+        ) {
+            (Some(width), Some(height)) => {
+                self.width = width;
+                self.height = height;
+                Ok(())
+            }
+            _ => Err(ScaleOverflow {
+                width: self.width,
+                height: self.height,
+                factor,
+            }),
+        }
+    }
+
+    // 溢位時停在 u32::MAX
+    pub fn saturating_scale(&mut self, factor: u32) {
+        self.width = self.width.saturating_mul(factor);
+        self.height = self.height.saturating_mul(factor);
     }
 
     // 3. 取得所有權 - 消耗 self（少用）
-    fn into_square(self) -> Rectangle {
+    pub fn into_square(self) -> Rectangle<U> {
         let size = self.width.max(self.height);
-        Rectangle {
-            width: size,
-            height: size,
+        Rectangle::of(size, size)
+    }
+}
+
+// 像素單位印成 Rectangle { width: 30, height: 50 }，其他單位多印出 unit
+impl<U: Unit> fmt::Debug for Rectangle<U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = f.debug_struct("Rectangle");
+        s.field("width", &self.width).field("height", &self.height);
+        if U::SUFFIX != Px::SUFFIX {
+            s.field("unit", &U::SUFFIX);
         }
+        s.finish()
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub struct ScaleOverflow {
+    pub width: u32,
+    pub height: u32,
+    pub factor: u32,
+}
+
+impl fmt::Display for ScaleOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}x{} 放大 {} 倍超過 u32 範圍",
+            self.width, self.height, self.factor
+        )
+    }
+}
+
+impl std::error::Error for ScaleOverflow {}
+
 pub fn main() {
     // Structs
     print!("✅Structs \n");
     let user1 = User {
         username: String::from("John"),
         email: String::from("john@example.com"),
-        age: 20,
+        birth_date: Date::new(2006, 5, 17).unwrap(),
         active: true,
     };
     println!("user1: {:?}", user1);
     println!("user1 username: {}", user1.username);
     println!("user1 email: {}", user1.email);
-    println!("user1 age: {}", user1.age);
+    println!("user1 age: {}", user1.age());
     println!("user1 active: {}", user1.active);
 
-    // user1.age = 26; // ❌ 錯誤
+    // user1.birth_date = Date::new(2000, 1, 1).unwrap(); // ❌ 錯誤
 
     let mut user2 = User {
         username: String::from("Jane"),
         email: String::from("jane@example.com"),
-        age: 25,
+        birth_date: Date::new(2001, 3, 2).unwrap(),
         active: false,
     };
-    print!("user2 age: {}", user2.age);
-    user2.age = 26;
-    print!("user2 age after: {}", user2.age);
+    print!("user2 age: {}", user2.age());
+    user2.birth_date = Date::new(2000, 3, 2).unwrap();
+    print!("user2 age after: {}", user2.age());
 
-    let user3 = build_user(String::from("john@example.com"), String::from("John"));
+    let user3 = build_user(
+        String::from("john@example.com"),
+        String::from("John"),
+        Date::new(2001, 1, 1).unwrap(),
+    );
     println!("user3: {:?}", user3);
 
     let user4 = User {
@@ -93,24 +189,15 @@ pub fn main() {
 
     // Method Syntax
     print!("✅Method Syntax \n");
-    let rect1 = Rectangle {
-        width: 30,
-        height: 50,
-    };
+    let rect1 = Rectangle::new(30, 50);
     println!("rect1: {:?}", rect1);
     println!("rect1 area: {}", rect1.area());
-    let rect2 = Rectangle {
-        width: 20,
-        height: 40,
-    };
+    let rect2 = Rectangle::new(20, 40);
     println!("rect2: {:?}", rect2);
     println!("rect2 area: {}", rect2.area());
     println!("rect1 can hold rect2: {}", rect1.can_hold(&rect2));
 
-    let mut rect = Rectangle {
-        width: 30,
-        height: 50,
-    };
+    let mut rect = Rectangle::new(30, 50);
     println!("rect before scale: {:?}", rect);
     rect.scale(2);
     println!("rect after scale: {:?}", rect);
@@ -123,4 +210,88 @@ pub fn main() {
     let rect3 = Rectangle::new(30, 50);
     println!("rect3: {:?}", rect3);
     println!("rect3 area: {}", rect3.area());
+
+    // 溢位安全
+    println!("✅ Overflow-safe");
+    let max = Rectangle::new(u32::MAX, u32::MAX);
+    println!("max area: {}", max.area()); // 18446744065119617025
+    println!("max checked_area: {:?}", max.checked_area()); // Some(18446744065119617025)
+    println!("max area_u32: {:?}", max.area_u32()); // None
+    println!("max saturating_area: {}", max.saturating_area()); // u32::MAX
+    let edge = Rectangle::new(u32::MAX, 1);
+    println!("edge area_u32: {:?}", edge.area_u32()); // Some(u32::MAX)
+
+    let mut rect4 = Rectangle::new(u32::MAX / 2, 10);
+    println!("try_scale(2): {:?}", rect4.try_scale(2)); // Ok，剛好不溢位
+    println!("rect4: {:?}", rect4);
+    let result = rect4.try_scale(2);
+    println!("try_scale(2) again: {:?}", result); // Err
+    if let Err(err) = result {
+        println!("error: {}", err);
+    }
+    println!("rect4 unchanged: {:?}", rect4);
+    rect4.saturating_scale(2);
+    println!("rect4 saturating_scale(2): {:?}", rect4);
+    // rect4.scale(2); // ❌ panic: Rectangle::scale 溢位
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn area_at_u32_max() {
+        let max = Rectangle::new(u32::MAX, u32::MAX);
+        assert_eq!(max.area(), u32::MAX as u64 * u32::MAX as u64);
+        assert_eq!(max.checked_area(), Some(max.area()));
+        assert_eq!(max.area_u32(), None);
+        assert_eq!(max.saturating_area(), u32::MAX);
+
+        let edge = Rectangle::new(u32::MAX, 1);
+        assert_eq!(edge.area(), u32::MAX as u64);
+        assert_eq!(edge.area_u32(), Some(u32::MAX));
+        assert_eq!(edge.saturating_area(), u32::MAX);
+
+        let over = Rectangle::new(u32::MAX, 2);
+        assert_eq!(over.area_u32(), None);
+        assert_eq!(over.saturating_area(), u32::MAX);
+    }
+
+    #[test]
+    fn try_scale_leaves_self_unchanged_on_error() {
+        let mut rect = Rectangle::new(u32::MAX / 2, 10);
+        assert_eq!(rect.try_scale(2), Ok(()));
+        assert_eq!(rect, Rectangle::new(u32::MAX - 1, 20));
+
+        let err = rect.try_scale(2).unwrap_err();
+        assert_eq!(
+            err,
+            ScaleOverflow {
+                width: u32::MAX - 1,
+                height: 20,
+                factor: 2
+            }
+        );
+        assert_eq!(rect, Rectangle::new(u32::MAX - 1, 20));
+
+        // 只有高度溢位也不能改到寬度
+        let mut tall = Rectangle::new(1, u32::MAX);
+        assert!(tall.try_scale(2).is_err());
+        assert_eq!(tall, Rectangle::new(1, u32::MAX));
+    }
+
+    #[test]
+    fn saturating_scale_stops_at_u32_max() {
+        let mut rect = Rectangle::new(u32::MAX - 1, 3);
+        rect.saturating_scale(2);
+        assert_eq!(rect, Rectangle::new(u32::MAX, 6));
+        rect.saturating_scale(u32::MAX);
+        assert_eq!(rect, Rectangle::new(u32::MAX, u32::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "Rectangle::scale 溢位")]
+    fn scale_panics_instead_of_wrapping() {
+        Rectangle::new(u32::MAX, 1).scale(2);
+    }
 }
@@ -0,0 +1,397 @@
+// Birth Date（用生日計算年齡，取代固定的 age 數字）
+use crate::chapter5::{build_user, User};
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 欄位順序是 年、月、日，所以 derive 出來的 Ord 就是時間先後
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+impl Date {
+    // 不存在的日期（例如 2023-02-29）回傳 None
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Date> {
+        if day >= 1 && day <= days_in_month(year, month) {
+            Some(Date { year, month, day })
+        } else {
+            None
+        }
+    }
+
+    // 今天（UTC）
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        Date::from_days_since_epoch(seconds.div_euclid(86_400))
+    }
+
+    // 1970-01-01 之後第幾天轉成日期（Howard Hinnant 的 civil_from_days 演算法）
+    pub fn from_days_since_epoch(days: i64) -> Date {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        Date {
+            year: year as i32,
+            month,
+            day,
+        }
+    }
+
+    // 往前推 years 年；2/29 在非閏年變成 2/28
+    // 年份超出 i32 範圍時回傳 None
+    pub fn years_before(&self, years: u32) -> Option<Date> {
+        let year = self.year.checked_sub(i32::try_from(years).ok()?)?;
+        let day = self.day.min(days_in_month(year, self.month));
+        Some(Date {
+            year,
+            month: self.month,
+            day,
+        })
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidDate(pub String);
+
+impl fmt::Display for InvalidDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "無效的日期: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidDate {}
+
+// 格式：YYYY-MM-DD
+impl FromStr for Date {
+    type Err = InvalidDate;
+
+    fn from_str(s: &str) -> Result<Date, InvalidDate> {
+        let parts: Vec<&str> = s.split('-').collect();
+        let invalid = || InvalidDate(s.to_string());
+        if parts.len() != 3 {
+            return Err(invalid());
+        }
+        let year = parts[0].parse().map_err(|_| invalid())?;
+        let month = parts[1].parse().map_err(|_| invalid())?;
+        let day = parts[2].parse().map_err(|_| invalid())?;
+        Date::new(year, month, day).ok_or_else(invalid)
+    }
+}
+
+// 還沒過今年生日就少一歲；2/29 出生的人在非閏年 3/1 才算滿一歲
+// 年份相減用 i64，差距最多 2^32 - 1，一定放得進 u32
+pub fn age_between(birth_date: Date, today: Date) -> u32 {
+    if today < birth_date {
+        return 0;
+    }
+    let mut age = (today.year as i64 - birth_date.year as i64) as u32;
+    if (today.month, today.day) < (birth_date.month, birth_date.day) {
+        age -= 1;
+    }
+    age
+}
+
+// 舊資料的 age 超過這個值就不轉換
+pub const MAX_LEGACY_AGE: u32 = 150;
+
+impl User {
+    // today 由外部傳入，方便測試與重現結果
+    pub fn age_on(&self, today: Date) -> u32 {
+        age_between(self.birth_date, today)
+    }
+
+    pub fn age(&self) -> u32 {
+        self.age_on(Date::today())
+    }
+
+    // 舊資料只有 age 數字時使用
+    // 近似方式：假設使用者剛好在 loaded_on 當天過生日。
+    // 真正的生日落在 (loaded_on - (age + 1) 年, loaded_on - age 年] 之間，
+    // 這裡取最晚的可能值，所以算出來的年齡不會比實際大，最多少算未滿一年。
+    // age 超過 MAX_LEGACY_AGE 視為壞資料，回傳 None
+    pub fn from_legacy_age(
+        username: String,
+        email: String,
+        age: u32,
+        active: bool,
+        loaded_on: Date,
+    ) -> Option<User> {
+        if age > MAX_LEGACY_AGE {
+            return None;
+        }
+        Some(User {
+            username,
+            email,
+            birth_date: loaded_on.years_before(age)?,
+            active,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AgeBracket {
+    Child,
+    Teen,
+    Adult,
+    Senior,
+}
+
+impl AgeBracket {
+    pub fn range(&self) -> RangeInclusive<u32> {
+        match self {
+            AgeBracket::Child => 0..=12,
+            AgeBracket::Teen => 13..=17,
+            AgeBracket::Adult => 18..=64,
+            AgeBracket::Senior => 65..=u32::MAX,
+        }
+    }
+
+    pub fn of(age: u32) -> AgeBracket {
+        [
+            AgeBracket::Child,
+            AgeBracket::Teen,
+            AgeBracket::Adult,
+            AgeBracket::Senior,
+        ]
+        .into_iter()
+        .find(|bracket| bracket.range().contains(&age))
+        .unwrap()
+    }
+}
+
+pub fn filter_by_bracket(users: &[User], bracket: AgeBracket, today: Date) -> Vec<&User> {
+    users
+        .iter()
+        .filter(|user| bracket.range().contains(&user.age_on(today)))
+        .collect()
+}
+
+pub fn main() {
+    println!("✅ Date");
+    let today = Date::new(2026, 10, 19).unwrap();
+    println!("today (固定): {}", today);
+    println!("today (系統): {}", Date::today());
+    println!("2023-02-29: {:?}", Date::new(2023, 2, 29));
+    println!("parse: {:?}", "2000-02-29".parse::<Date>());
+    println!("parse: {:?}", "2000-13-01".parse::<Date>());
+
+    println!("✅ Age");
+    let users = vec![
+        build_user(
+            String::from("amy@example.com"),
+            String::from("Amy"),
+            Date::new(2016, 3, 1).unwrap(),
+        ),
+        build_user(
+            String::from("ben@example.com"),
+            String::from("Ben"),
+            Date::new(2010, 10, 20).unwrap(), // 明天才滿 16
+        ),
+        build_user(
+            String::from("leap@example.com"),
+            String::from("Leap"),
+            Date::new(2000, 2, 29).unwrap(),
+        ),
+        build_user(
+            String::from("grace@example.com"),
+            String::from("Grace"),
+            Date::new(1950, 1, 1).unwrap(),
+        ),
+    ];
+    for user in &users {
+        println!(
+            "{} born {} age {} ({:?})",
+            user.username,
+            user.birth_date,
+            user.age_on(today),
+            AgeBracket::of(user.age_on(today))
+        );
+    }
+    let leap = &users[2];
+    for day in [
+        Date::new(2023, 2, 28).unwrap(),
+        Date::new(2023, 3, 1).unwrap(),
+    ] {
+        println!("Leap on {}: {}", day, leap.age_on(day));
+    }
+
+    println!("✅ Age brackets");
+    for bracket in [AgeBracket::Child, AgeBracket::Teen, AgeBracket::Adult] {
+        let names: Vec<&str> = filter_by_bracket(&users, bracket, today)
+            .iter()
+            .map(|u| u.username.as_str())
+            .collect();
+        println!("{:?}: {:?}", bracket, names);
+    }
+
+    println!("✅ Legacy age migration");
+    let legacy = User::from_legacy_age(
+        String::from("John"),
+        String::from("john@example.com"),
+        25,
+        true,
+        today,
+    )
+    .unwrap();
+    println!("legacy: {:?}", legacy);
+    println!("age on load day: {}", legacy.age_on(today));
+    println!(
+        "age one year later: {}",
+        legacy.age_on(Date::new(2027, 10, 19).unwrap())
+    );
+    for age in [MAX_LEGACY_AGE + 1, u32::MAX] {
+        let user = User::from_legacy_age(String::new(), String::new(), age, true, today);
+        println!("age {}: {:?}", age, user.map(|u| u.birth_date));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn years_before_does_not_overflow() {
+        let date = Date::new(2026, 10, 19).unwrap();
+        assert_eq!(date.years_before(2147485000), None);
+        assert_eq!(date.years_before(u32::MAX), None);
+        assert_eq!(Date::new(i32::MIN + 5, 1, 1).unwrap().years_before(6), None);
+        assert_eq!(
+            Date::new(2024, 2, 29).unwrap().years_before(1),
+            Date::new(2023, 2, 28)
+        );
+    }
+
+    #[test]
+    fn from_legacy_age_rejects_out_of_range_ages() {
+        let today = Date::new(2026, 10, 19).unwrap();
+        let user = |age| User::from_legacy_age(String::new(), String::new(), age, true, today);
+        assert_eq!(user(MAX_LEGACY_AGE).unwrap().age_on(today), MAX_LEGACY_AGE);
+        assert!(user(MAX_LEGACY_AGE + 1).is_none());
+        assert!(user(u32::MAX).is_none());
+    }
+
+    fn user(birth_date: Date) -> User {
+        build_user(String::new(), String::from("Test"), birth_date)
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn age_changes_on_the_birthday() {
+        let ben = user(date(2010, 10, 20));
+        assert_eq!(ben.age_on(date(2026, 10, 19)), 15);
+        assert_eq!(ben.age_on(date(2026, 10, 20)), 16);
+        assert_eq!(ben.age_on(date(2026, 10, 21)), 16);
+        assert_eq!(ben.age_on(date(2027, 10, 19)), 16);
+        // 出生當天是 0 歲，出生前也算 0 歲
+        assert_eq!(ben.age_on(date(2010, 10, 20)), 0);
+        assert_eq!(ben.age_on(date(2000, 1, 1)), 0);
+        // 跨月份的前一天
+        let new_year = user(date(2000, 1, 1));
+        assert_eq!(new_year.age_on(date(2025, 12, 31)), 25);
+        assert_eq!(new_year.age_on(date(2026, 1, 1)), 26);
+    }
+
+    #[test]
+    fn leap_day_birthday() {
+        let leap = user(date(2000, 2, 29));
+        // 非閏年 3/1 才滿一歲
+        assert_eq!(leap.age_on(date(2023, 2, 28)), 22);
+        assert_eq!(leap.age_on(date(2023, 3, 1)), 23);
+        // 閏年當天就滿
+        assert_eq!(leap.age_on(date(2024, 2, 28)), 23);
+        assert_eq!(leap.age_on(date(2024, 2, 29)), 24);
+        assert_eq!(leap.age_on(date(2100, 2, 28)), 99);
+        assert_eq!(leap.age_on(date(2100, 3, 1)), 100);
+    }
+
+    #[test]
+    fn age_between_extreme_years() {
+        let oldest = date(i32::MIN, 1, 1);
+        let newest = date(i32::MAX, 12, 31);
+        assert_eq!(age_between(oldest, newest), u32::MAX);
+        assert_eq!(age_between(oldest, date(i32::MAX, 1, 1)), u32::MAX);
+        assert_eq!(
+            age_between(date(i32::MIN, 12, 31), date(i32::MAX, 1, 1)),
+            u32::MAX - 1
+        );
+        assert_eq!(
+            age_between(date(i32::MIN + 5, 1, 1), date(2026, 10, 19)),
+            2026 + 2147483643
+        );
+        assert_eq!(age_between(newest, oldest), 0);
+    }
+
+    #[test]
+    fn brackets() {
+        assert_eq!(AgeBracket::of(0), AgeBracket::Child);
+        assert_eq!(AgeBracket::of(12), AgeBracket::Child);
+        assert_eq!(AgeBracket::of(13), AgeBracket::Teen);
+        assert_eq!(AgeBracket::of(17), AgeBracket::Teen);
+        assert_eq!(AgeBracket::of(18), AgeBracket::Adult);
+        assert_eq!(AgeBracket::of(64), AgeBracket::Adult);
+        assert_eq!(AgeBracket::of(65), AgeBracket::Senior);
+        assert_eq!(AgeBracket::of(u32::MAX), AgeBracket::Senior);
+    }
+
+    #[test]
+    fn filter_users_by_bracket() {
+        let today = date(2026, 10, 19);
+        let users = vec![
+            build_user(String::new(), String::from("Amy"), date(2016, 3, 1)),
+            build_user(String::new(), String::from("Ben"), date(2010, 10, 20)),
+            build_user(String::new(), String::from("Cat"), date(2008, 10, 19)),
+            build_user(String::new(), String::from("Leap"), date(2000, 2, 29)),
+            build_user(String::new(), String::from("Grace"), date(1950, 1, 1)),
+            build_user(String::new(), String::from("Unborn"), date(2030, 1, 1)),
+        ];
+        let names = |bracket, today| -> Vec<&str> {
+            filter_by_bracket(&users, bracket, today)
+                .iter()
+                .map(|u| u.username.as_str())
+                .collect()
+        };
+        assert_eq!(names(AgeBracket::Child, today), vec!["Amy", "Unborn"]);
+        // Ben 明天才滿 16，Cat 今天剛滿 18
+        assert_eq!(names(AgeBracket::Teen, today), vec!["Ben"]);
+        assert_eq!(names(AgeBracket::Adult, today), vec!["Cat", "Leap"]);
+        assert_eq!(names(AgeBracket::Senior, today), vec!["Grace"]);
+        assert_eq!(names(AgeBracket::Adult, date(2026, 10, 18)), vec!["Leap"]);
+        assert!(filter_by_bracket(&[], AgeBracket::Adult, today).is_empty());
+    }
+}
@@ -0,0 +1,368 @@
+// Deduplication（從多個來源匯入 User 時合併重複資料）
+use crate::chapter5::User;
+use crate::chapter5_10::Date;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalizeOptions {
+    // Gmail 忽略 local part 的 "." 與 "+" 之後的內容，預設不開啟
+    pub gmail_rules: bool,
+    // username 相同但 email 不同也視為同一人，預設不開啟（同名的人很常見）
+    pub match_username: bool,
+}
+
+pub fn normalize_email(email: &str, options: NormalizeOptions) -> String {
+    let email = email.trim().to_lowercase();
+    if !options.gmail_rules {
+        return email;
+    }
+    match email.split_once('@') {
+        Some((local, "gmail.com" | "googlemail.com")) => {
+            let local = local.split('+').next().unwrap_or("").replace('.', "");
+            format!("{}@gmail.com", local)
+        }
+        _ => email,
+    }
+}
+
+// 去掉前後空白、連續空白壓成一個，並轉小寫
+pub fn normalize_username(username: &str) -> String {
+    username
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+// 值是正規化之後仍然不同的內容，依出現順序
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conflict {
+    Username(Vec<String>),
+    Email(Vec<String>),   // 合併後只留第一個，其他的會被丟掉
+    BirthDate(Vec<Date>), // 年齡由生日計算，所以比較生日
+    Active(Vec<bool>),
+}
+
+#[derive(Debug, Clone)]
+pub struct MergeProposal {
+    pub members: Vec<usize>, // 原始列表中的位置，由小到大
+    pub merged: User,
+    pub conflicts: Vec<Conflict>,
+}
+
+// Union-Find：email（或開啟 match_username 時的 username）相同的就放在同一組
+// 空白的 email 或 username 不代表任何人，不拿來比對
+fn find(parent: &mut Vec<usize>, i: usize) -> usize {
+    if parent[i] != i {
+        let root = find(parent, parent[i]);
+        parent[i] = root;
+    }
+    parent[i]
+}
+
+fn union(parent: &mut Vec<usize>, a: usize, b: usize) {
+    let (ra, rb) = (find(parent, a), find(parent, b));
+    // 讓比較小的 index 當 root，結果才會固定
+    if ra < rb {
+        parent[rb] = ra;
+    } else if rb < ra {
+        parent[ra] = rb;
+    }
+}
+
+pub fn group_duplicates(users: &[User], options: NormalizeOptions) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..users.len()).collect();
+    let mut by_email: HashMap<String, usize> = HashMap::new();
+    let mut by_username: HashMap<String, usize> = HashMap::new();
+
+    for (i, user) in users.iter().enumerate() {
+        let email = normalize_email(&user.email, options);
+        // "+tag@gmail.com" 套用 Gmail 規則後 local part 也會是空的
+        if !email.is_empty() && !email.starts_with('@') {
+            match by_email.get(&email) {
+                Some(&first) => union(&mut parent, first, i),
+                None => {
+                    by_email.insert(email, i);
+                }
+            }
+        }
+        if !options.match_username {
+            continue;
+        }
+        let username = normalize_username(&user.username);
+        if username.is_empty() {
+            continue;
+        }
+        match by_username.get(&username) {
+            Some(&first) => union(&mut parent, first, i),
+            None => {
+                by_username.insert(username, i);
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+    for i in 0..users.len() {
+        let root = find(&mut parent, i);
+        match group_of_root.get(&root) {
+            Some(&g) => groups[g].push(i),
+            None => {
+                group_of_root.insert(root, groups.len());
+                groups.push(vec![i]);
+            }
+        }
+    }
+    groups
+}
+
+// 出現最多次的值，平手時取最先出現的
+fn most_common<T: PartialEq + Copy>(values: &[T]) -> T {
+    let mut best = values[0];
+    let mut best_count = 0;
+    for value in values {
+        let count = values.iter().filter(|v| *v == value).count();
+        if count > best_count {
+            best = *value;
+            best_count = count;
+        }
+    }
+    best
+}
+
+fn distinct<T: PartialEq + Clone>(values: &[T]) -> Vec<T> {
+    let mut result = Vec::new();
+    for value in values {
+        if !result.contains(value) {
+            result.push(value.clone());
+        }
+    }
+    result
+}
+
+// 合併規則（固定、可重現）：
+// - username、email 取組內第一筆，只做 trim（email 另外轉小寫）
+// - birth_date 取出現最多次的，平手取第一筆
+// - active 只要有一筆是 true 就是 true
+pub fn propose_merges(users: &[User], options: NormalizeOptions) -> Vec<MergeProposal> {
+    group_duplicates(users, options)
+        .into_iter()
+        .filter(|members| members.len() > 1)
+        .map(|members| {
+            let first = &users[members[0]];
+            let usernames: Vec<String> = members
+                .iter()
+                .map(|&i| normalize_username(&users[i].username))
+                .collect();
+            let emails: Vec<String> = members
+                .iter()
+                .map(|&i| normalize_email(&users[i].email, options))
+                .collect();
+            let dates: Vec<Date> = members.iter().map(|&i| users[i].birth_date).collect();
+            let actives: Vec<bool> = members.iter().map(|&i| users[i].active).collect();
+
+            let mut conflicts = Vec::new();
+            if distinct(&usernames).len() > 1 {
+                conflicts.push(Conflict::Username(distinct(&usernames)));
+            }
+            if distinct(&emails).len() > 1 {
+                conflicts.push(Conflict::Email(distinct(&emails)));
+            }
+            if distinct(&dates).len() > 1 {
+                conflicts.push(Conflict::BirthDate(distinct(&dates)));
+            }
+            if distinct(&actives).len() > 1 {
+                conflicts.push(Conflict::Active(distinct(&actives)));
+            }
+
+            let merged = User {
+                username: first.username.trim().to_string(),
+                email: first.email.trim().to_lowercase(),
+                birth_date: most_common(&dates),
+                active: actives.contains(&true),
+            };
+            MergeProposal {
+                members,
+                merged,
+                conflicts,
+            }
+        })
+        .collect()
+}
+
+// 套用合併：每組留下一筆（放在該組第一筆的位置），其他使用者保持原順序
+pub fn apply_merges(users: Vec<User>, proposals: &[MergeProposal]) -> Vec<User> {
+    let mut replacement: HashMap<usize, &User> = HashMap::new();
+    let mut removed = vec![false; users.len()];
+    for proposal in proposals {
+        replacement.insert(proposal.members[0], &proposal.merged);
+        for &i in &proposal.members[1..] {
+            removed[i] = true;
+        }
+    }
+    users
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !removed[*i])
+        .map(|(i, user)| match replacement.get(&i) {
+            Some(merged) => (*merged).clone(),
+            None => user,
+        })
+        .collect()
+}
+
+fn user(username: &str, email: &str, birth_date: (i32, u32, u32), active: bool) -> User {
+    User {
+        username: username.to_string(),
+        email: email.to_string(),
+        birth_date: Date::new(birth_date.0, birth_date.1, birth_date.2).unwrap(),
+        active,
+    }
+}
+
+pub fn main() {
+    println!("✅ Normalize");
+    let gmail = NormalizeOptions {
+        gmail_rules: true,
+        ..NormalizeOptions::default()
+    };
+    let by_name = NormalizeOptions {
+        gmail_rules: true,
+        match_username: true,
+    };
+    println!(
+        "{}",
+        normalize_email("  John.Doe+news@Gmail.com ", NormalizeOptions::default())
+    );
+    println!("{}", normalize_email("  John.Doe+news@Gmail.com ", gmail));
+    println!("{:?}", normalize_username("  John   Doe "));
+
+    let users = vec![
+        user("John Doe", "john.doe@gmail.com", (1990, 1, 1), true),
+        user("jane", "jane@example.com", (1992, 2, 2), true),
+        user(" john  doe", "JOHN.DOE@GMAIL.COM", (1990, 1, 1), false),
+        user("JD", "johndoe+shop@gmail.com", (1991, 1, 1), true),
+        user("Jane ", "jane.smith@example.com", (1992, 2, 2), true),
+        user("bob", "bob@example.com", (1980, 3, 3), true),
+    ];
+
+    for (name, options) in [
+        ("預設", NormalizeOptions::default()),
+        ("Gmail 規則", gmail),
+        ("Gmail 規則 + username", by_name),
+    ] {
+        println!("✅ Merge proposals ({})", name);
+        let proposals = propose_merges(&users, options);
+        for proposal in &proposals {
+            println!("members: {:?}", proposal.members);
+            println!("  merged: {:?}", proposal.merged);
+            println!("  conflicts: {:?}", proposal.conflicts);
+        }
+        let merged = apply_merges(users.clone(), &proposals);
+        println!("{} users -> {} users", users.len(), merged.len());
+        for user in &merged {
+            println!("  {}", user);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_username_is_not_merged_by_default() {
+        let users = vec![
+            user("jane", "jane@a.com", (1992, 2, 2), true),
+            user("Jane", "jane.smith@b.com", (1992, 2, 2), true),
+        ];
+        assert_eq!(
+            group_duplicates(&users, NormalizeOptions::default()),
+            vec![vec![0], vec![1]]
+        );
+        assert!(propose_merges(&users, NormalizeOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn username_matching_reports_the_dropped_email() {
+        let users = vec![
+            user("jane", "jane@a.com", (1992, 2, 2), true),
+            user("Jane", "jane.smith@b.com", (1992, 2, 2), true),
+        ];
+        let options = NormalizeOptions {
+            match_username: true,
+            ..NormalizeOptions::default()
+        };
+        let proposals = propose_merges(&users, options);
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(proposals[0].merged.email, "jane@a.com");
+        assert_eq!(
+            proposals[0].conflicts,
+            vec![Conflict::Email(vec![
+                String::from("jane@a.com"),
+                String::from("jane.smith@b.com")
+            ])]
+        );
+    }
+
+    #[test]
+    fn email_match_reports_username_conflict() {
+        let users = vec![
+            user("John Doe", "john.doe@gmail.com", (1990, 1, 1), true),
+            user("JD", "johndoe+shop@gmail.com", (1990, 1, 1), false),
+        ];
+        let options = NormalizeOptions {
+            gmail_rules: true,
+            ..NormalizeOptions::default()
+        };
+        let proposals = propose_merges(&users, options);
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(
+            proposals[0].conflicts,
+            vec![
+                Conflict::Username(vec![String::from("john doe"), String::from("jd")]),
+                Conflict::Active(vec![true, false]),
+            ]
+        );
+        assert!(proposals[0].merged.active);
+    }
+
+    #[test]
+    fn blank_emails_are_not_duplicates() {
+        let users = vec![
+            user("Alice", "", (1990, 1, 1), true),
+            user("Bob", "   ", (1985, 5, 5), true),
+            user("Carol", "", (1970, 7, 7), false),
+            user("Dave", "+shop@gmail.com", (1960, 6, 6), true),
+            user("Erin", "+news@gmail.com", (1950, 5, 5), true),
+        ];
+        let options = NormalizeOptions {
+            gmail_rules: true,
+            ..NormalizeOptions::default()
+        };
+        assert_eq!(
+            group_duplicates(&users, options),
+            vec![vec![0], vec![1], vec![2], vec![3], vec![4]]
+        );
+        assert!(propose_merges(&users, options).is_empty());
+    }
+
+    #[test]
+    fn blank_usernames_are_not_duplicates() {
+        let users = vec![
+            user("", "a@example.com", (1990, 1, 1), true),
+            user("  ", "b@example.com", (1985, 5, 5), true),
+            user("", "", (1970, 7, 7), true),
+            // username 空白但 email 相同的仍然會合併
+            user(" ", "A@example.com", (1990, 1, 1), true),
+        ];
+        let options = NormalizeOptions {
+            match_username: true,
+            ..NormalizeOptions::default()
+        };
+        assert_eq!(
+            group_duplicates(&users, options),
+            vec![vec![0, 3], vec![1], vec![2]]
+        );
+    }
+}
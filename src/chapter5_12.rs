@@ -0,0 +1,473 @@
+// Tiling（把一個矩形切成多個子矩形）
+use crate::chapter5::Rectangle;
+use crate::chapter5_3::Rect;
+
+// 把 total 分成 parts 份，餘數平均分給前面幾份：10 分 3 份 -> [4, 3, 3]
+fn split_evenly(total: u32, parts: u32) -> Vec<u32> {
+    (0..parts)
+        .map(|i| total / parts + if i < total % parts { 1 } else { 0 })
+        .collect()
+}
+
+// 每段的 (起點偏移, 長度)
+fn offsets(lengths: &[u32]) -> Vec<(u32, u32)> {
+    let mut offset = 0;
+    lengths
+        .iter()
+        .map(|&len| {
+            let start = offset;
+            offset += len;
+            (start, len)
+        })
+        .collect()
+}
+
+// parent 的右邊或下邊可以超過 i64::MAX（見 chapter5_3），
+// 起點超出 i64 的 tile 沒有合法座標，直接略過
+fn cross(parent: &Rect, columns: &[u32], rows: &[u32]) -> Vec<Rect> {
+    let mut tiles = Vec::new();
+    for (y, height) in offsets(rows) {
+        let Some(tile_y) = parent.y.checked_add(y as i64) else {
+            break;
+        };
+        for (x, width) in offsets(columns) {
+            let Some(tile_x) = parent.x.checked_add(x as i64) else {
+                break;
+            };
+            tiles.push(Rect::new(tile_x, tile_y, width, height));
+        }
+    }
+    tiles
+}
+
+// N×M 網格，無法整除時前面的欄/列多 1，依列優先（row-major）排列
+// 欄數超過寬度（或列數超過高度）時只切到每格 1，不會產生寬或高為 0 的 tile
+pub fn grid(parent: &Rect, columns: u32, rows: u32) -> Vec<Rect> {
+    let columns = columns.min(parent.size.width);
+    let rows = rows.min(parent.size.height);
+    if columns == 0 || rows == 0 {
+        return Vec::new();
+    }
+    cross(
+        parent,
+        &split_evenly(parent.size.width, columns),
+        &split_evenly(parent.size.height, rows),
+    )
+}
+
+// 邊緣放不下完整 tile 時的處理方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Remainder {
+    Partial, // 邊緣保留較小的 tile
+    Drop,    // 捨棄邊緣（結果不會蓋滿 parent）
+    Stretch, // 併入最後一個完整的 tile
+}
+
+fn tile_lengths(total: u32, size: u32, remainder: Remainder) -> Vec<u32> {
+    let mut lengths = vec![size; (total / size) as usize];
+    let rest = total % size;
+    if rest > 0 {
+        match (remainder, lengths.last_mut()) {
+            (Remainder::Partial, _) | (Remainder::Stretch, None) => lengths.push(rest),
+            (Remainder::Stretch, Some(last)) => *last += rest,
+            (Remainder::Drop, _) => {}
+        }
+    }
+    lengths
+}
+
+pub fn tiles(parent: &Rect, tile: &Rectangle, remainder: Remainder) -> Vec<Rect> {
+    if tile.width == 0 || tile.height == 0 {
+        return Vec::new();
+    }
+    cross(
+        parent,
+        &tile_lengths(parent.size.width, tile.width, remainder),
+        &tile_lengths(parent.size.height, tile.height, remainder),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cut {
+    Vertical(u32),   // 距離左邊 offset 的位置垂直切開
+    Horizontal(u32), // 距離上邊 offset 的位置水平切開
+}
+
+// 斷頭台切割（Guillotine）：每一刀都從一邊切到另一邊，形成一棵二元樹
+#[derive(Debug, Clone, PartialEq)]
+pub enum Guillotine {
+    Leaf,
+    Split(Cut, Box<Guillotine>, Box<Guillotine>),
+}
+
+impl Guillotine {
+    pub fn split(cut: Cut, first: Guillotine, second: Guillotine) -> Guillotine {
+        Guillotine::Split(cut, Box::new(first), Box::new(second))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CutOutOfRange {
+    pub cut: Cut,
+    pub rect: Rect,
+}
+
+// 切在邊上（offset 為 0 或等於邊長）會產生空矩形，視為錯誤
+// 第二塊的起點超過 i64::MAX 時也沒有合法座標，同樣回傳錯誤
+pub fn guillotine(parent: &Rect, plan: &Guillotine) -> Result<Vec<Rect>, CutOutOfRange> {
+    match plan {
+        Guillotine::Leaf => Ok(vec![parent.clone()]),
+        Guillotine::Split(cut, first, second) => {
+            let (w, h) = (parent.size.width, parent.size.height);
+            let halves = match *cut {
+                Cut::Vertical(x) if x > 0 && x < w => {
+                    parent.x.checked_add(x as i64).map(|second_x| {
+                        (
+                            Rect::new(parent.x, parent.y, x, h),
+                            Rect::new(second_x, parent.y, w - x, h),
+                        )
+                    })
+                }
+                Cut::Horizontal(y) if y > 0 && y < h => {
+                    parent.y.checked_add(y as i64).map(|second_y| {
+                        (
+                            Rect::new(parent.x, parent.y, w, y),
+                            Rect::new(parent.x, second_y, w, h - y),
+                        )
+                    })
+                }
+                _ => None,
+            };
+            let Some((a, b)) = halves else {
+                return Err(CutOutOfRange {
+                    cut: *cut,
+                    rect: parent.clone(),
+                });
+            };
+            let mut result = guillotine(&a, first)?;
+            result.extend(guillotine(&b, second)?);
+            Ok(result)
+        }
+    }
+}
+
+// 檢查 tiles 剛好蓋滿 parent：都不是空的、都在 parent 內、兩兩不重疊、面積總和相等
+pub fn covers_exactly(parent: &Rect, tiles: &[Rect]) -> bool {
+    let inside = tiles
+        .iter()
+        .all(|t| t.size.area() > 0 && parent.contains_rect(t));
+    let disjoint = tiles
+        .iter()
+        .enumerate()
+        .all(|(i, a)| tiles[i + 1..].iter().all(|b| !a.intersects(b)));
+    let area: u64 = tiles.iter().map(|t| t.size.area()).sum();
+    inside && disjoint && area == parent.size.area()
+}
+
+fn show(name: &str, parent: &Rect, tiles: &[Rect]) {
+    println!(
+        "{}: {} tiles, covers exactly: {}",
+        name,
+        tiles.len(),
+        covers_exactly(parent, tiles)
+    );
+    for t in tiles {
+        println!("  ({}, {}) {}x{}", t.x, t.y, t.size.width, t.size.height);
+    }
+}
+
+pub fn main() {
+    println!("✅ Grid");
+    let parent = Rect::new(10, 20, 10, 7);
+    show("grid 3x2", &parent, &grid(&parent, 3, 2));
+
+    println!("✅ Tiles");
+    let tile = Rectangle::new(4, 3);
+    show(
+        "partial",
+        &parent,
+        &tiles(&parent, &tile, Remainder::Partial),
+    );
+    show("drop", &parent, &tiles(&parent, &tile, Remainder::Drop));
+    show(
+        "stretch",
+        &parent,
+        &tiles(&parent, &tile, Remainder::Stretch),
+    );
+
+    println!("✅ Guillotine");
+    let page = Rect::new(0, 0, 100, 60);
+    // 左邊欄 30 寬；右邊再切成上 20 的標題列與下方內容，內容再左右對半
+    let plan = Guillotine::split(
+        Cut::Vertical(30),
+        Guillotine::Leaf,
+        Guillotine::split(
+            Cut::Horizontal(20),
+            Guillotine::Leaf,
+            Guillotine::split(Cut::Vertical(35), Guillotine::Leaf, Guillotine::Leaf),
+        ),
+    );
+    show("layout", &page, &guillotine(&page, &plan).unwrap());
+    let bad = Guillotine::split(Cut::Horizontal(60), Guillotine::Leaf, Guillotine::Leaf);
+    println!("bad cut: {:?}", guillotine(&page, &bad));
+
+    println!("✅ covers_exactly 檢查");
+    let mut broken = grid(&parent, 2, 2);
+    broken[0].size.width += 1; // 和右邊重疊
+    println!("overlap: {}", covers_exactly(&parent, &broken));
+    println!(
+        "missing tile: {}",
+        covers_exactly(&parent, &grid(&parent, 2, 2)[1..])
+    );
+    println!(
+        "grid 20x2 on width 10: {} tiles",
+        grid(&parent, 20, 2).len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARENTS: [(i64, i64, u32, u32); 5] = [
+        (0, 0, 10, 7),
+        (10, 20, 10, 7),
+        (-5, -3, 1, 1),
+        (0, 0, 17, 13),
+        (i64::MAX - 100, i64::MIN, 64, 48),
+    ];
+
+    fn parents() -> impl Iterator<Item = Rect> {
+        PARENTS.iter().map(|&(x, y, w, h)| Rect::new(x, y, w, h))
+    }
+
+    #[test]
+    fn grid_covers_parent_exactly() {
+        for parent in parents() {
+            for columns in 1..=parent.size.width.min(12) {
+                for rows in 1..=parent.size.height.min(12) {
+                    let tiles = grid(&parent, columns, rows);
+                    assert_eq!(tiles.len(), (columns * rows) as usize);
+                    assert!(
+                        covers_exactly(&parent, &tiles),
+                        "{:?} {}x{}",
+                        parent,
+                        columns,
+                        rows
+                    );
+                    // 無法整除時每欄/列最多差 1
+                    let widths: Vec<u32> = tiles.iter().map(|t| t.size.width).collect();
+                    let (min, max) = (widths.iter().min().unwrap(), widths.iter().max().unwrap());
+                    assert!(max - min <= 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn grid_with_more_columns_than_width_has_no_empty_tiles() {
+        let parent = Rect::new(0, 0, 3, 2);
+        let tiles = grid(&parent, 10, 5);
+        assert_eq!(tiles.len(), 6);
+        assert!(tiles
+            .iter()
+            .all(|t| t.size.width == 1 && t.size.height == 1));
+        assert!(covers_exactly(&parent, &tiles));
+        assert!(grid(&parent, 0, 2).is_empty());
+        assert!(grid(&Rect::new(0, 0, 0, 5), 3, 3).is_empty());
+    }
+
+    #[test]
+    fn tiles_partial_and_stretch_cover_parent_exactly() {
+        for parent in parents() {
+            for w in 1..=9 {
+                for h in 1..=9 {
+                    let tile = Rectangle::new(w, h);
+                    for remainder in [Remainder::Partial, Remainder::Stretch] {
+                        let result = tiles(&parent, &tile, remainder);
+                        assert!(
+                            covers_exactly(&parent, &result),
+                            "{:?} {:?} {:?}",
+                            parent,
+                            tile,
+                            remainder
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tiles_drop_covers_only_whole_tiles() {
+        for parent in parents() {
+            for w in 1..=9 {
+                for h in 1..=9 {
+                    let result = tiles(&parent, &Rectangle::new(w, h), Remainder::Drop);
+                    assert!(result
+                        .iter()
+                        .all(|t| t.size.width == w && t.size.height == h));
+                    let (columns, rows) = (parent.size.width / w, parent.size.height / h);
+                    assert_eq!(result.len(), (columns * rows) as usize);
+                    if !result.is_empty() {
+                        let covered = Rect::new(parent.x, parent.y, columns * w, rows * h);
+                        assert!(covers_exactly(&covered, &result));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tiles_remainder_modes() {
+        let parent = Rect::new(0, 0, 10, 7);
+        let tile = Rectangle::new(4, 3);
+        let widths = |mode| {
+            tiles(&parent, &tile, mode)
+                .iter()
+                .filter(|t| t.y == 0)
+                .map(|t| t.size.width)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(widths(Remainder::Partial), vec![4, 4, 2]);
+        assert_eq!(widths(Remainder::Drop), vec![4, 4]);
+        assert_eq!(widths(Remainder::Stretch), vec![4, 6]);
+        // tile 比 parent 大：Stretch 沒有完整的 tile 可以併入，保留一個較小的
+        let big = Rectangle::new(20, 20);
+        assert_eq!(
+            tiles(&parent, &big, Remainder::Stretch),
+            vec![parent.clone()]
+        );
+        assert!(tiles(&parent, &big, Remainder::Drop).is_empty());
+        assert!(tiles(&parent, &Rectangle::new(0, 3), Remainder::Partial).is_empty());
+    }
+
+    #[test]
+    fn guillotine_covers_parent_exactly() {
+        let page = Rect::new(0, 0, 100, 60);
+        let plan = Guillotine::split(
+            Cut::Vertical(30),
+            Guillotine::Leaf,
+            Guillotine::split(
+                Cut::Horizontal(20),
+                Guillotine::Leaf,
+                Guillotine::split(Cut::Vertical(35), Guillotine::Leaf, Guillotine::Leaf),
+            ),
+        );
+        let result = guillotine(&page, &plan).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                Rect::new(0, 0, 30, 60),
+                Rect::new(30, 0, 70, 20),
+                Rect::new(30, 20, 35, 40),
+                Rect::new(65, 20, 35, 40),
+            ]
+        );
+        assert!(covers_exactly(&page, &result));
+        assert_eq!(guillotine(&page, &Guillotine::Leaf), Ok(vec![page]));
+    }
+
+    #[test]
+    fn guillotine_rejects_cuts_on_or_outside_the_edge() {
+        let page = Rect::new(0, 0, 100, 60);
+        for cut in [
+            Cut::Vertical(0),
+            Cut::Vertical(100),
+            Cut::Vertical(u32::MAX),
+            Cut::Horizontal(0),
+            Cut::Horizontal(60),
+        ] {
+            let plan = Guillotine::split(cut, Guillotine::Leaf, Guillotine::Leaf);
+            assert_eq!(
+                guillotine(&page, &plan),
+                Err(CutOutOfRange {
+                    cut,
+                    rect: page.clone()
+                })
+            );
+        }
+        // 錯誤回報的是實際被切的子矩形
+        let nested = Guillotine::split(
+            Cut::Vertical(30),
+            Guillotine::split(Cut::Vertical(30), Guillotine::Leaf, Guillotine::Leaf),
+            Guillotine::Leaf,
+        );
+        assert_eq!(
+            guillotine(&page, &nested),
+            Err(CutOutOfRange {
+                cut: Cut::Vertical(30),
+                rect: Rect::new(0, 0, 30, 60)
+            })
+        );
+    }
+
+    #[test]
+    fn covers_exactly_detects_overlap_gaps_and_empty_tiles() {
+        let parent = Rect::new(0, 0, 10, 7);
+        let mut overlap = grid(&parent, 2, 2);
+        overlap[0].size.width += 1;
+        assert!(!covers_exactly(&parent, &overlap));
+        assert!(!covers_exactly(&parent, &grid(&parent, 2, 2)[1..]));
+        let mut with_empty = grid(&parent, 2, 2);
+        with_empty.push(Rect::new(0, 0, 0, 7));
+        assert!(!covers_exactly(&parent, &with_empty));
+    }
+
+    // 右邊或下邊超過 i64::MAX 的 parent：只留下起點還在 i64 範圍內的 tile
+    #[test]
+    fn parents_past_i64_max_skip_unrepresentable_tiles() {
+        let wide = Rect::new(i64::MAX - 5, 0, 100, 10);
+        assert!(wide.right() > i64::MAX as i128);
+        let result = grid(&wide, 4, 2);
+        assert_eq!(
+            result,
+            vec![
+                Rect::new(i64::MAX - 5, 0, 25, 5),
+                Rect::new(i64::MAX - 5, 5, 25, 5),
+            ]
+        );
+        let result = tiles(&wide, &Rectangle::new(3, 10), Remainder::Partial);
+        assert_eq!(
+            result.iter().map(|t| t.x).collect::<Vec<_>>(),
+            vec![i64::MAX - 5, i64::MAX - 2]
+        );
+
+        let tall = Rect::new(i64::MIN, i64::MAX, 10, u32::MAX);
+        let result = grid(&tall, 2, 3);
+        assert_eq!(result.len(), 2);
+        assert!(result.iter().all(|t| t.y == i64::MAX));
+
+        let corner = Rect::new(i64::MAX, i64::MAX, u32::MAX, u32::MAX);
+        for remainder in [Remainder::Partial, Remainder::Drop, Remainder::Stretch] {
+            let result = tiles(&corner, &Rectangle::new(1000, 1000), remainder);
+            assert_eq!(result.len(), 1);
+            assert_eq!((result[0].x, result[0].y), (i64::MAX, i64::MAX));
+            assert!(corner.contains_rect(&result[0]));
+        }
+    }
+
+    #[test]
+    fn guillotine_rejects_halves_past_i64_max() {
+        let wide = Rect::new(i64::MAX - 5, i64::MAX - 5, 100, 100);
+        // 起點剛好是 i64::MAX 還可以
+        let ok = Guillotine::split(Cut::Vertical(5), Guillotine::Leaf, Guillotine::Leaf);
+        assert_eq!(
+            guillotine(&wide, &ok),
+            Ok(vec![
+                Rect::new(i64::MAX - 5, i64::MAX - 5, 5, 100),
+                Rect::new(i64::MAX, i64::MAX - 5, 95, 100),
+            ])
+        );
+        for cut in [Cut::Vertical(6), Cut::Horizontal(50)] {
+            let plan = Guillotine::split(cut, Guillotine::Leaf, Guillotine::Leaf);
+            assert_eq!(
+                guillotine(&wide, &plan),
+                Err(CutOutOfRange {
+                    cut,
+                    rect: wide.clone()
+                })
+            );
+        }
+    }
+}
@@ -0,0 +1,433 @@
+// Roles and Permissions（角色與權限）
+use crate::chapter5::{build_user, User};
+use crate::chapter5_10::Date;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+    Admin,
+    Editor,
+    Viewer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Read,
+    Create,
+    Update,
+    Delete,
+}
+
+// 細粒度權限：動作 + 資源，資源 "*" 代表全部
+#[derive(Debug, Clone, PartialEq)]
+pub struct Permission {
+    pub action: Action,
+    pub resource: String,
+}
+
+impl Permission {
+    pub fn new(action: Action, resource: &str) -> Permission {
+        Permission {
+            action,
+            resource: resource.to_string(),
+        }
+    }
+
+    // "docs/*" 可以匹配 "docs/readme"
+    fn matches(&self, action: Action, resource: &str) -> bool {
+        if self.action != action {
+            return false;
+        }
+        match self.resource.strip_suffix('*') {
+            Some(prefix) => resource.starts_with(prefix),
+            None => self.resource == resource,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct RoleDef {
+    parents: Vec<Role>, // 繼承的角色
+    permissions: Vec<Permission>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    Allowed(Grant),
+    Denied(DenyReason),
+}
+
+impl Decision {
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Decision::Allowed(_))
+    }
+}
+
+// 允許的來源：角色（可能是繼承來的）或直接授予使用者
+#[derive(Debug, Clone, PartialEq)]
+pub enum Grant {
+    Role(Role),
+    Direct,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DenyReason {
+    Inactive,
+    NoPermission,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    pub username: String,
+    pub action: Action,
+    pub resource: String,
+    pub decision: Decision,
+}
+
+#[derive(Debug, Default)]
+pub struct Policy {
+    roles: HashMap<Role, RoleDef>,
+    assignments: HashMap<String, Vec<Role>>, // username -> roles
+    direct: HashMap<String, Vec<Permission>>, // username -> 直接授予的權限
+    audit: RefCell<Vec<AuditEntry>>,         // can() 只需要 &self，所以用 RefCell 記錄
+}
+
+impl Policy {
+    pub fn new() -> Policy {
+        Policy::default()
+    }
+
+    // 預設：Admin 繼承 Editor，Editor 繼承 Viewer
+    pub fn with_default_roles() -> Policy {
+        let mut policy = Policy::new();
+        policy.grant_role(Role::Viewer, Permission::new(Action::Read, "*"));
+        policy.grant_role(Role::Editor, Permission::new(Action::Create, "*"));
+        policy.grant_role(Role::Editor, Permission::new(Action::Update, "*"));
+        policy.grant_role(Role::Admin, Permission::new(Action::Delete, "*"));
+        policy.inherit(Role::Editor, Role::Viewer);
+        policy.inherit(Role::Admin, Role::Editor);
+        policy
+    }
+
+    pub fn grant_role(&mut self, role: Role, permission: Permission) {
+        self.roles
+            .entry(role)
+            .or_default()
+            .permissions
+            .push(permission);
+    }
+
+    pub fn inherit(&mut self, role: Role, parent: Role) {
+        let def = self.roles.entry(role).or_default();
+        if !def.parents.contains(&parent) {
+            def.parents.push(parent);
+        }
+    }
+
+    pub fn assign(&mut self, user: &User, role: Role) {
+        let roles = self.assignments.entry(user.username.clone()).or_default();
+        if !roles.contains(&role) {
+            roles.push(role);
+        }
+    }
+
+    pub fn grant_user(&mut self, user: &User, permission: Permission) {
+        self.direct
+            .entry(user.username.clone())
+            .or_default()
+            .push(permission);
+    }
+
+    pub fn can(&self, user: &User, action: Action, resource: &str) -> bool {
+        self.check(user, action, resource).is_allowed()
+    }
+
+    // 回傳完整的判斷結果，並寫入 audit log
+    pub fn check(&self, user: &User, action: Action, resource: &str) -> Decision {
+        let decision = self.decide(user, action, resource);
+        self.audit.borrow_mut().push(AuditEntry {
+            username: user.username.clone(),
+            action,
+            resource: resource.to_string(),
+            decision: decision.clone(),
+        });
+        decision
+    }
+
+    fn decide(&self, user: &User, action: Action, resource: &str) -> Decision {
+        // 停用的使用者一律拒絕
+        if !user.active {
+            return Decision::Denied(DenyReason::Inactive);
+        }
+
+        if let Some(permissions) = self.direct.get(&user.username) {
+            if permissions.iter().any(|p| p.matches(action, resource)) {
+                return Decision::Allowed(Grant::Direct);
+            }
+        }
+
+        let assigned = self
+            .assignments
+            .get(&user.username)
+            .map(|roles| roles.as_slice())
+            .unwrap_or(&[]);
+        for role in assigned {
+            if let Some(via) = self.find_in_role(*role, action, resource) {
+                return Decision::Allowed(Grant::Role(via));
+            }
+        }
+
+        Decision::Denied(DenyReason::NoPermission)
+    }
+
+    // 沿著繼承關係往上找，visited 避免循環繼承造成無限迴圈
+    fn find_in_role(&self, role: Role, action: Action, resource: &str) -> Option<Role> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![role];
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current) {
+                continue;
+            }
+            if let Some(def) = self.roles.get(&current) {
+                if def.permissions.iter().any(|p| p.matches(action, resource)) {
+                    return Some(current);
+                }
+                stack.extend(def.parents.iter().copied());
+            }
+        }
+        None
+    }
+
+    pub fn audit_log(&self) -> Vec<AuditEntry> {
+        self.audit.borrow().clone()
+    }
+}
+
+pub fn main() {
+    println!("✅ Roles and Permissions");
+    let mut policy = Policy::with_default_roles();
+    let birthday = Date::new(2000, 1, 1).unwrap();
+
+    let admin = build_user(
+        String::from("admin@example.com"),
+        String::from("Admin"),
+        birthday,
+    );
+    let editor = build_user(
+        String::from("editor@example.com"),
+        String::from("Eddie"),
+        birthday,
+    );
+    let viewer = build_user(
+        String::from("viewer@example.com"),
+        String::from("Vivian"),
+        birthday,
+    );
+    let mut inactive = build_user(
+        String::from("old@example.com"),
+        String::from("Old"),
+        birthday,
+    );
+    inactive.active = false;
+
+    policy.assign(&admin, Role::Admin);
+    policy.assign(&editor, Role::Editor);
+    policy.assign(&viewer, Role::Viewer);
+    policy.assign(&inactive, Role::Admin);
+    policy.grant_user(&viewer, Permission::new(Action::Update, "docs/*"));
+
+    println!(
+        "admin delete posts: {}",
+        policy.can(&admin, Action::Delete, "posts/1")
+    );
+    println!(
+        "admin read posts(繼承): {:?}",
+        policy.check(&admin, Action::Read, "posts/1")
+    );
+    println!(
+        "editor delete posts: {}",
+        policy.can(&editor, Action::Delete, "posts/1")
+    );
+    println!(
+        "viewer update docs(直接授予): {:?}",
+        policy.check(&viewer, Action::Update, "docs/readme")
+    );
+    println!(
+        "viewer update posts: {}",
+        policy.can(&viewer, Action::Update, "posts/1")
+    );
+    println!(
+        "inactive admin read: {:?}",
+        policy.check(&inactive, Action::Read, "posts/1")
+    );
+
+    println!("✅ Audit Log");
+    for entry in policy.audit_log() {
+        println!(
+            "{} {:?} {} -> {:?}",
+            entry.username, entry.action, entry.resource, entry.decision
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(name: &str) -> User {
+        build_user(
+            format!("{}@example.com", name.to_lowercase()),
+            name.to_string(),
+            Date::new(2000, 1, 1).unwrap(),
+        )
+    }
+
+    #[test]
+    fn roles_inherit_permissions() {
+        let mut policy = Policy::with_default_roles();
+        let (admin, editor, viewer) = (user("Admin"), user("Eddie"), user("Vivian"));
+        policy.assign(&admin, Role::Admin);
+        policy.assign(&editor, Role::Editor);
+        policy.assign(&viewer, Role::Viewer);
+
+        // 回傳實際提供權限的角色
+        assert_eq!(
+            policy.check(&admin, Action::Read, "posts/1"),
+            Decision::Allowed(Grant::Role(Role::Viewer))
+        );
+        assert_eq!(
+            policy.check(&admin, Action::Delete, "posts/1"),
+            Decision::Allowed(Grant::Role(Role::Admin))
+        );
+        assert_eq!(
+            policy.check(&editor, Action::Update, "posts/1"),
+            Decision::Allowed(Grant::Role(Role::Editor))
+        );
+        assert!(!policy.can(&editor, Action::Delete, "posts/1"));
+        assert!(policy.can(&viewer, Action::Read, "anything"));
+        assert!(!policy.can(&viewer, Action::Create, "posts/1"));
+        // 沒有指派角色的使用者什麼都不能做
+        assert_eq!(
+            policy.check(&user("Nobody"), Action::Read, "posts/1"),
+            Decision::Denied(DenyReason::NoPermission)
+        );
+    }
+
+    #[test]
+    fn cyclic_inheritance_terminates() {
+        let mut policy = Policy::new();
+        policy.inherit(Role::Editor, Role::Viewer);
+        policy.inherit(Role::Viewer, Role::Editor);
+        policy.grant_role(Role::Viewer, Permission::new(Action::Read, "docs"));
+        let eddie = user("Eddie");
+        policy.assign(&eddie, Role::Editor);
+        assert!(policy.can(&eddie, Action::Read, "docs"));
+        assert!(!policy.can(&eddie, Action::Delete, "docs"));
+    }
+
+    #[test]
+    fn direct_grants_come_before_roles() {
+        let mut policy = Policy::with_default_roles();
+        let viewer = user("Vivian");
+        policy.assign(&viewer, Role::Viewer);
+        policy.grant_user(&viewer, Permission::new(Action::Update, "docs/*"));
+        policy.grant_user(&viewer, Permission::new(Action::Read, "secret"));
+        assert_eq!(
+            policy.check(&viewer, Action::Update, "docs/readme"),
+            Decision::Allowed(Grant::Direct)
+        );
+        assert_eq!(
+            policy.check(&viewer, Action::Read, "secret"),
+            Decision::Allowed(Grant::Direct)
+        );
+        assert!(!policy.can(&viewer, Action::Update, "posts/1"));
+        // 直接授予只影響那位使用者
+        let other = user("Other");
+        policy.assign(&other, Role::Viewer);
+        assert!(!policy.can(&other, Action::Update, "docs/readme"));
+    }
+
+    #[test]
+    fn wildcard_matching() {
+        let docs = Permission::new(Action::Read, "docs/*");
+        assert!(docs.matches(Action::Read, "docs/readme"));
+        assert!(docs.matches(Action::Read, "docs/"));
+        assert!(docs.matches(Action::Read, "docs/a/b"));
+        assert!(!docs.matches(Action::Read, "docs"));
+        assert!(!docs.matches(Action::Read, "posts/docs/readme"));
+        assert!(!docs.matches(Action::Update, "docs/readme"));
+
+        let all = Permission::new(Action::Delete, "*");
+        assert!(all.matches(Action::Delete, ""));
+        assert!(all.matches(Action::Delete, "posts/1"));
+
+        let exact = Permission::new(Action::Read, "posts/1");
+        assert!(exact.matches(Action::Read, "posts/1"));
+        assert!(!exact.matches(Action::Read, "posts/10"));
+    }
+
+    #[test]
+    fn inactive_users_are_denied() {
+        let mut policy = Policy::with_default_roles();
+        let mut old = user("Old");
+        policy.assign(&old, Role::Admin);
+        policy.grant_user(&old, Permission::new(Action::Read, "*"));
+        assert!(policy.can(&old, Action::Delete, "posts/1"));
+        old.active = false;
+        assert_eq!(
+            policy.check(&old, Action::Read, "posts/1"),
+            Decision::Denied(DenyReason::Inactive)
+        );
+        assert!(!policy.can(&old, Action::Delete, "posts/1"));
+    }
+
+    #[test]
+    fn every_check_is_audited() {
+        let mut policy = Policy::with_default_roles();
+        let (editor, mut old) = (user("Eddie"), user("Old"));
+        old.active = false;
+        policy.assign(&editor, Role::Editor);
+        assert!(policy.audit_log().is_empty());
+
+        policy.can(&editor, Action::Create, "posts/2");
+        policy.check(&editor, Action::Delete, "posts/2");
+        policy.can(&old, Action::Read, "posts/2");
+
+        let log = policy.audit_log();
+        let summary: Vec<(&str, Action, &str, &Decision)> = log
+            .iter()
+            .map(|e| {
+                (
+                    e.username.as_str(),
+                    e.action,
+                    e.resource.as_str(),
+                    &e.decision,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "Eddie",
+                    Action::Create,
+                    "posts/2",
+                    &Decision::Allowed(Grant::Role(Role::Editor))
+                ),
+                (
+                    "Eddie",
+                    Action::Delete,
+                    "posts/2",
+                    &Decision::Denied(DenyReason::NoPermission)
+                ),
+                (
+                    "Old",
+                    Action::Read,
+                    "posts/2",
+                    &Decision::Denied(DenyReason::Inactive)
+                ),
+            ]
+        );
+    }
+}
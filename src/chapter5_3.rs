@@ -0,0 +1,479 @@
+// Geometry（2D 幾何，將 Rectangle 一般化）
+use crate::chapter5::Rectangle;
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Point {
+        Point { x, y }
+    }
+
+    pub fn distance(&self, other: &Point) -> f64 {
+        ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+// 所有形狀共用的 trait
+pub trait Shape {
+    fn area(&self) -> f64;
+    fn perimeter(&self) -> f64;
+    fn bounding_box(&self) -> BoundingBox;
+    fn contains(&self, point: Point) -> bool; // 邊界上的點也算在內
+}
+
+// 有位置的矩形：原點（左上角）+ 尺寸
+// 座標用 i64；右邊界與下邊界用 i128 計算，x 接近 i64::MAX 時也不會溢位
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i64,
+    pub y: i64,
+    pub size: Rectangle,
+}
+
+impl Rect {
+    pub fn new(x: i64, y: i64, width: u32, height: u32) -> Rect {
+        Rect {
+            x,
+            y,
+            size: Rectangle::new(width, height),
+        }
+    }
+
+    pub fn right(&self) -> i128 {
+        self.x as i128 + self.size.width as i128
+    }
+
+    pub fn bottom(&self) -> i128 {
+        self.y as i128 + self.size.height as i128
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    // 重疊的部分，只碰到邊不算重疊
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        if right > left as i128 && bottom > top as i128 {
+            // 重疊的寬高不會超過任一個矩形，一定放得進 u32
+            Some(Rect::new(
+                left,
+                top,
+                u32::try_from(right - left as i128).ok()?,
+                u32::try_from(bottom - top as i128).ok()?,
+            ))
+        } else {
+            None
+        }
+    }
+
+    // 兩個矩形的聯集取外接矩形（矩形聯集不一定是矩形）
+    // 兩個矩形離太遠、外接矩形的寬或高超過 u32 時回傳 None
+    pub fn union(&self, other: &Rect) -> Option<Rect> {
+        let left = self.x.min(other.x);
+        let top = self.y.min(other.y);
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        Some(Rect::new(
+            left,
+            top,
+            u32::try_from(right - left as i128).ok()?,
+            u32::try_from(bottom - top as i128).ok()?,
+        ))
+    }
+
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+}
+
+impl Shape for Rect {
+    fn area(&self) -> f64 {
+        self.size.width as f64 * self.size.height as f64
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * (self.size.width as f64 + self.size.height as f64)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox {
+            min: Point::new(self.x as f64, self.y as f64),
+            max: Point::new(self.right() as f64, self.bottom() as f64),
+        }
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        point.x >= self.x as f64
+            && point.x <= self.right() as f64
+            && point.y >= self.y as f64
+            && point.y <= self.bottom() as f64
+    }
+}
+
+// 半徑必須是有限且不小於 0 的數，只能透過 Circle::new 建立
+#[derive(Debug, Clone, PartialEq)]
+pub struct Circle {
+    center: Point,
+    radius: f64,
+}
+
+impl Circle {
+    pub fn new(center: Point, radius: f64) -> Option<Circle> {
+        let finite = center.x.is_finite() && center.y.is_finite() && radius.is_finite();
+        if finite && radius >= 0.0 {
+            Some(Circle { center, radius })
+        } else {
+            None
+        }
+    }
+
+    pub fn center(&self) -> Point {
+        self.center
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        PI * self.radius * self.radius
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * PI * self.radius
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox {
+            min: Point::new(self.center.x - self.radius, self.center.y - self.radius),
+            max: Point::new(self.center.x + self.radius, self.center.y + self.radius),
+        }
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.center.distance(&point) <= self.radius
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triangle {
+    pub a: Point,
+    pub b: Point,
+    pub c: Point,
+}
+
+// 外積：> 0 表示 p 在 a->b 的左側
+fn cross(a: Point, b: Point, p: Point) -> f64 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+fn bounding_box_of(points: &[Point]) -> BoundingBox {
+    let mut min = points[0];
+    let mut max = points[0];
+    for p in points {
+        min.x = min.x.min(p.x);
+        min.y = min.y.min(p.y);
+        max.x = max.x.max(p.x);
+        max.y = max.y.max(p.y);
+    }
+    BoundingBox { min, max }
+}
+
+impl Shape for Triangle {
+    fn area(&self) -> f64 {
+        cross(self.a, self.b, self.c).abs() / 2.0
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.a.distance(&self.b) + self.b.distance(&self.c) + self.c.distance(&self.a)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        bounding_box_of(&[self.a, self.b, self.c])
+    }
+
+    // 三個外積同號（或為 0）代表點在三角形內
+    fn contains(&self, point: Point) -> bool {
+        let d1 = cross(self.a, self.b, point);
+        let d2 = cross(self.b, self.c, point);
+        let d3 = cross(self.c, self.a, point);
+        let has_neg = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+        let has_pos = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+        !(has_neg && has_pos)
+    }
+}
+
+// 簡單多邊形（頂點依序排列，不自交）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    pub points: Vec<Point>,
+}
+
+impl Polygon {
+    // 少於三個頂點不是多邊形
+    pub fn new(points: Vec<Point>) -> Option<Polygon> {
+        if points.len() < 3 {
+            None
+        } else {
+            Some(Polygon { points })
+        }
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        let n = self.points.len();
+        (0..n).map(move |i| (self.points[i], self.points[(i + 1) % n]))
+    }
+}
+
+impl Shape for Polygon {
+    // 鞋帶公式(Shoelace formula)
+    fn area(&self) -> f64 {
+        let sum: f64 = self.edges().map(|(p, q)| p.x * q.y - q.x * p.y).sum();
+        sum.abs() / 2.0
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.edges().map(|(p, q)| p.distance(&q)).sum()
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        bounding_box_of(&self.points)
+    }
+
+    // 射線法(Ray casting)，邊上的點另外判斷
+    fn contains(&self, point: Point) -> bool {
+        let mut inside = false;
+        for (p, q) in self.edges() {
+            let on_segment = cross(p, q, point) == 0.0
+                && point.x >= p.x.min(q.x)
+                && point.x <= p.x.max(q.x)
+                && point.y >= p.y.min(q.y)
+                && point.y <= p.y.max(q.y);
+            if on_segment {
+                return true;
+            }
+            if (p.y > point.y) != (q.y > point.y) {
+                let x = p.x + (point.y - p.y) * (q.x - p.x) / (q.y - p.y);
+                if point.x < x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+}
+
+pub fn main() {
+    println!("✅ Shape trait");
+    let shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(Rect::new(0, 0, 30, 50)),
+        Box::new(Circle::new(Point::new(0.0, 0.0), 10.0).unwrap()),
+        Box::new(Triangle {
+            a: Point::new(0.0, 0.0),
+            b: Point::new(4.0, 0.0),
+            c: Point::new(0.0, 3.0),
+        }),
+        Box::new(
+            Polygon::new(vec![
+                Point::new(0.0, 0.0),
+                Point::new(4.0, 0.0),
+                Point::new(4.0, 4.0),
+                Point::new(2.0, 2.0),
+                Point::new(0.0, 4.0),
+            ])
+            .unwrap(),
+        ),
+    ];
+    let probe = Point::new(1.0, 1.0);
+    for shape in shapes.iter() {
+        println!(
+            "area: {:.2}, perimeter: {:.2}, bbox: {:?}, contains {:?}: {}",
+            shape.area(),
+            shape.perimeter(),
+            shape.bounding_box(),
+            probe,
+            shape.contains(probe)
+        );
+    }
+
+    println!("✅ Rect intersection / union");
+    let a = Rect::new(0, 0, 30, 50);
+    let b = Rect::new(20, 40, 30, 50);
+    let c = Rect::new(30, 0, 10, 10); // 只碰到 a 的右邊
+    println!("a ∩ b: {:?}", a.intersection(&b));
+    println!("a ∩ c: {:?}", a.intersection(&c));
+    println!("a ∪ b: {:?}", a.union(&b));
+    println!(
+        "a ∪ b contains a: {}",
+        a.union(&b).is_some_and(|u| u.contains_rect(&a))
+    );
+    let far = Rect::new(i64::MAX - 5, 0, 10, 10);
+    println!("far right: {}", far.right());
+    println!("a ∪ far: {:?}", a.union(&far));
+
+    println!("✅ Circle");
+    for radius in [10.0, 0.0, -1.0, f64::NAN, f64::INFINITY] {
+        println!(
+            "radius {}: {:?}",
+            radius,
+            Circle::new(Point::new(0.0, 0.0), radius)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn circle_rejects_invalid_radius() {
+        let origin = Point::new(0.0, 0.0);
+        assert!(Circle::new(origin, 1.0).is_some());
+        assert!(Circle::new(origin, 0.0).is_some());
+        assert_eq!(Circle::new(origin, -1.0), None);
+        assert_eq!(Circle::new(origin, f64::NAN), None);
+        assert_eq!(Circle::new(origin, f64::INFINITY), None);
+        assert_eq!(Circle::new(Point::new(f64::NAN, 0.0), 1.0), None);
+    }
+
+    #[test]
+    fn circle_measurements() {
+        let c = Circle::new(Point::new(1.0, 2.0), 3.0).unwrap();
+        assert!(approx(c.area(), PI * 9.0));
+        assert!(approx(c.perimeter(), PI * 6.0));
+        assert_eq!(
+            c.bounding_box(),
+            BoundingBox {
+                min: Point::new(-2.0, -1.0),
+                max: Point::new(4.0, 5.0),
+            }
+        );
+        // 圓周上的點也算在內
+        assert!(c.contains(Point::new(4.0, 2.0)));
+        assert!(c.contains(Point::new(1.0, 2.0)));
+        assert!(!c.contains(Point::new(4.0, 5.0)));
+    }
+
+    #[test]
+    fn triangle_measurements() {
+        let t = Triangle {
+            a: Point::new(0.0, 0.0),
+            b: Point::new(4.0, 0.0),
+            c: Point::new(0.0, 3.0),
+        };
+        assert!(approx(t.area(), 6.0));
+        assert!(approx(t.perimeter(), 12.0));
+        assert!(t.contains(Point::new(1.0, 1.0)));
+        assert!(t.contains(Point::new(2.0, 0.0))); // 邊上
+        assert!(t.contains(Point::new(4.0, 0.0))); // 頂點
+        assert!(!t.contains(Point::new(3.0, 3.0)));
+        assert!(!t.contains(Point::new(-0.1, 1.0)));
+        // 頂點順序反過來結果一樣
+        let reversed = Triangle {
+            a: t.c,
+            b: t.b,
+            c: t.a,
+        };
+        assert!(approx(reversed.area(), 6.0));
+        assert!(reversed.contains(Point::new(1.0, 1.0)));
+        assert!(!reversed.contains(Point::new(3.0, 3.0)));
+    }
+
+    #[test]
+    fn polygon_measurements() {
+        assert_eq!(
+            Polygon::new(vec![Point::new(0.0, 0.0), Point::new(1.0, 1.0)]),
+            None
+        );
+        // 凹多邊形：4x4 的正方形上緣挖掉一個三角形
+        let p = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(4.0, 0.0),
+            Point::new(4.0, 4.0),
+            Point::new(2.0, 2.0),
+            Point::new(0.0, 4.0),
+        ])
+        .unwrap();
+        assert!(approx(p.area(), 12.0));
+        assert!(approx(p.perimeter(), 12.0 + 2.0 * 8f64.sqrt()));
+        assert!(p.contains(Point::new(1.0, 1.0)));
+        assert!(p.contains(Point::new(3.0, 3.0))); // 右邊的尖角
+        assert!(!p.contains(Point::new(2.0, 3.0))); // 凹進去的部分
+        assert!(p.contains(Point::new(2.0, 2.0))); // 凹點本身
+        assert!(p.contains(Point::new(4.0, 2.0))); // 邊上
+        assert!(!p.contains(Point::new(5.0, 1.0)));
+        assert_eq!(
+            p.bounding_box(),
+            BoundingBox {
+                min: Point::new(0.0, 0.0),
+                max: Point::new(4.0, 4.0),
+            }
+        );
+    }
+
+    #[test]
+    fn rect_measurements() {
+        let r = Rect::new(-10, 5, 30, 50);
+        assert!(approx(r.area(), 1500.0));
+        assert!(approx(r.perimeter(), 160.0));
+        assert!(r.contains(Point::new(-10.0, 5.0)));
+        assert!(r.contains(Point::new(20.0, 55.0)));
+        assert!(!r.contains(Point::new(20.1, 55.0)));
+    }
+
+    #[test]
+    fn right_and_bottom_do_not_overflow_near_i64_max() {
+        let r = Rect::new(i64::MAX, i64::MAX, u32::MAX, u32::MAX);
+        assert_eq!(r.right(), i64::MAX as i128 + u32::MAX as i128);
+        assert_eq!(r.bottom(), i64::MAX as i128 + u32::MAX as i128);
+        assert!(r.contains_rect(&Rect::new(i64::MAX, i64::MAX, 1, 1)));
+    }
+
+    #[test]
+    fn union_rejects_spans_wider_than_u32() {
+        let a = Rect::new(0, 0, 10, 10);
+        let far = Rect::new(u32::MAX as i64, 0, 10, 10);
+        assert_eq!(a.union(&far), None);
+        let edge = Rect::new(u32::MAX as i64 - 10, 0, 10, 10);
+        assert_eq!(a.union(&edge), Some(Rect::new(0, 0, u32::MAX, 10)));
+        let extremes = Rect::new(i64::MIN, i64::MIN, 1, 1);
+        assert_eq!(extremes.union(&Rect::new(i64::MAX, i64::MAX, 1, 1)), None);
+    }
+
+    #[test]
+    fn intersection_near_i64_max() {
+        let a = Rect::new(i64::MAX - 5, 0, u32::MAX, 10);
+        let b = Rect::new(i64::MAX, 0, u32::MAX, 10);
+        assert_eq!(
+            a.intersection(&b),
+            Some(Rect::new(i64::MAX, 0, u32::MAX - 5, 10))
+        );
+        // 只碰到邊不算重疊
+        let c = Rect::new(0, 0, 10, 10);
+        assert_eq!(c.intersection(&Rect::new(10, 0, 10, 10)), None);
+    }
+}
@@ -0,0 +1,446 @@
+// Bin Packing（把多個 Rectangle 放進固定大小的箱子）
+use crate::chapter5::Rectangle;
+use crate::chapter5_3::Rect;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Heuristic {
+    Shelf,    // 一層一層往上疊，快但浪費較多
+    MaxRects, // 維護所有最大空白矩形，較省空間
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement {
+    pub index: usize, // 在輸入列表中的位置
+    pub bin: usize,
+    pub rect: Rect, // 放置後的位置與尺寸（旋轉後的尺寸）
+    pub rotated: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Packing {
+    pub bin_size: Rectangle,
+    pub bins: usize,
+    pub placements: Vec<Placement>,
+    pub unplaced: Vec<usize>, // 比箱子還大、放不進去的項目
+}
+
+impl Packing {
+    pub fn used_area(&self, bin: usize) -> u64 {
+        self.placements
+            .iter()
+            .filter(|p| p.bin == bin)
+            .map(|p| p.rect.size.area())
+            .sum()
+    }
+
+    pub fn wasted_area(&self, bin: usize) -> u64 {
+        self.bin_size.area() - self.used_area(bin)
+    }
+
+    pub fn total_wasted_area(&self) -> u64 {
+        (0..self.bins).map(|bin| self.wasted_area(bin)).sum()
+    }
+}
+
+pub struct Packer {
+    pub bin_size: Rectangle,
+    pub heuristic: Heuristic,
+    pub allow_rotation: bool, // 允許旋轉 90°
+}
+
+impl Packer {
+    pub fn new(bin_size: Rectangle, heuristic: Heuristic) -> Packer {
+        Packer {
+            bin_size,
+            heuristic,
+            allow_rotation: false,
+        }
+    }
+
+    pub fn with_rotation(mut self) -> Packer {
+        self.allow_rotation = true;
+        self
+    }
+
+    pub fn pack(&self, items: &[Rectangle]) -> Packing {
+        // 先放大的，效果比較好
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        match self.heuristic {
+            Heuristic::Shelf => order.sort_by_key(|&i| std::cmp::Reverse(items[i].height)),
+            Heuristic::MaxRects => order.sort_by_key(|&i| std::cmp::Reverse(items[i].area())),
+        }
+
+        let mut packing = Packing {
+            bin_size: self.bin_size.clone(),
+            bins: 0,
+            placements: Vec::new(),
+            unplaced: Vec::new(),
+        };
+        match self.heuristic {
+            Heuristic::Shelf => self.pack_shelf(items, &order, &mut packing),
+            Heuristic::MaxRects => self.pack_max_rects(items, &order, &mut packing),
+        }
+        packing.placements.sort_by_key(|p| p.index);
+        packing.unplaced.sort();
+        packing
+    }
+
+    // 可以嘗試的方向：(寬, 高, 是否旋轉)
+    fn orientations(&self, item: &Rectangle) -> Vec<(u32, u32, bool)> {
+        let mut result = vec![(item.width, item.height, false)];
+        if self.allow_rotation && item.width != item.height {
+            result.push((item.height, item.width, true));
+        }
+        result
+            .into_iter()
+            .filter(|&(w, h, _)| self.bin_size.can_fit(&Rectangle::new(w, h)))
+            .collect()
+    }
+
+    fn pack_shelf(&self, items: &[Rectangle], order: &[usize], packing: &mut Packing) {
+        // 每個箱子的 shelves：(y, 高度, 已用寬度)
+        let mut bins: Vec<Vec<(u32, u32, u32)>> = Vec::new();
+
+        for &index in order {
+            let mut orientations = self.orientations(&items[index]);
+            if orientations.is_empty() {
+                packing.unplaced.push(index);
+                continue;
+            }
+            // 躺平（高度較小）優先，減少 shelf 的高度
+            orientations.sort_by_key(|&(_, h, _)| h);
+
+            let mut placed = None;
+            'search: for (bin, shelves) in bins.iter_mut().enumerate() {
+                // 1. 放進已有的 shelf
+                for shelf in shelves.iter_mut() {
+                    for &(w, h, rotated) in &orientations {
+                        if h <= shelf.1 && shelf.2 + w <= self.bin_size.width {
+                            placed = Some((bin, shelf.2, shelf.0, w, h, rotated));
+                            shelf.2 += w;
+                            break 'search;
+                        }
+                    }
+                }
+                // 2. 在這個箱子開新的 shelf
+                let top = shelves.last().map(|s| s.0 + s.1).unwrap_or(0);
+                let (w, h, rotated) = orientations[0];
+                if top + h <= self.bin_size.height {
+                    shelves.push((top, h, w));
+                    placed = Some((bin, 0, top, w, h, rotated));
+                    break 'search;
+                }
+            }
+
+            // 3. 都放不下，開新箱子
+            let (bin, x, y, w, h, rotated) = placed.unwrap_or_else(|| {
+                let (w, h, rotated) = orientations[0];
+                bins.push(vec![(0, h, w)]);
+                (bins.len() - 1, 0, 0, w, h, rotated)
+            });
+            packing.placements.push(Placement {
+                index,
+                bin,
+                rect: Rect::new(x as i64, y as i64, w, h),
+                rotated,
+            });
+        }
+        packing.bins = bins.len();
+    }
+
+    fn pack_max_rects(&self, items: &[Rectangle], order: &[usize], packing: &mut Packing) {
+        let full = Rect {
+            x: 0,
+            y: 0,
+            size: self.bin_size.clone(),
+        };
+        let mut bins: Vec<Vec<Rect>> = Vec::new(); // 每個箱子的空白矩形
+
+        for &index in order {
+            let orientations = self.orientations(&items[index]);
+            if orientations.is_empty() {
+                packing.unplaced.push(index);
+                continue;
+            }
+
+            // Best Short Side Fit：剩餘短邊最小的位置最好
+            let mut best: Option<(u32, usize, Rect, bool)> = None;
+            for (bin, free) in bins.iter().enumerate() {
+                for slot in free {
+                    for &(w, h, rotated) in &orientations {
+                        if slot.size.can_fit(&Rectangle::new(w, h)) {
+                            let short = (slot.size.width - w).min(slot.size.height - h);
+                            if best.as_ref().map(|b| short < b.0).unwrap_or(true) {
+                                best = Some((short, bin, Rect::new(slot.x, slot.y, w, h), rotated));
+                            }
+                        }
+                    }
+                }
+            }
+
+            let (bin, rect, rotated) = match best {
+                Some((_, bin, rect, rotated)) => (bin, rect, rotated),
+                None => {
+                    let (w, h, rotated) = orientations[0];
+                    bins.push(vec![full.clone()]);
+                    (bins.len() - 1, Rect::new(0, 0, w, h), rotated)
+                }
+            };
+            split_free_rects(&mut bins[bin], &rect);
+            packing.placements.push(Placement {
+                index,
+                bin,
+                rect,
+                rotated,
+            });
+        }
+        packing.bins = bins.len();
+    }
+}
+
+// 把和 used 重疊的空白矩形切成最多四塊，再移除被其他空白矩形包含的
+fn split_free_rects(free: &mut Vec<Rect>, used: &Rect) {
+    let mut next = Vec::new();
+    for slot in free.drain(..) {
+        if !slot.intersects(used) {
+            next.push(slot);
+            continue;
+        }
+        if used.x > slot.x {
+            next.push(Rect::new(
+                slot.x,
+                slot.y,
+                (used.x - slot.x) as u32,
+                slot.size.height,
+            ));
+        }
+        // 右邊界超過 i64::MAX 時，右邊剩下的空間沒有合法座標，直接捨棄
+        if let (true, Ok(x)) = (used.right() < slot.right(), i64::try_from(used.right())) {
+            next.push(Rect::new(
+                x,
+                slot.y,
+                (slot.right() - used.right()) as u32,
+                slot.size.height,
+            ));
+        }
+        if used.y > slot.y {
+            next.push(Rect::new(
+                slot.x,
+                slot.y,
+                slot.size.width,
+                (used.y - slot.y) as u32,
+            ));
+        }
+        if let (true, Ok(y)) = (used.bottom() < slot.bottom(), i64::try_from(used.bottom())) {
+            next.push(Rect::new(
+                slot.x,
+                y,
+                slot.size.width,
+                (slot.bottom() - used.bottom()) as u32,
+            ));
+        }
+    }
+
+    let mut pruned: Vec<Rect> = Vec::new();
+    for (i, slot) in next.iter().enumerate() {
+        let redundant = next.iter().enumerate().any(|(j, other)| {
+            // 完全相同的只保留第一個
+            i != j && other.contains_rect(slot) && (other != slot || j < i)
+        });
+        if !redundant {
+            pruned.push(slot.clone());
+        }
+    }
+    *free = pruned;
+}
+
+fn report(name: &str, packing: &Packing) {
+    println!("{}: bins = {}", name, packing.bins);
+    for p in &packing.placements {
+        println!(
+            "  #{} -> bin {} at ({}, {}) {}x{}{}",
+            p.index,
+            p.bin,
+            p.rect.x,
+            p.rect.y,
+            p.rect.size.width,
+            p.rect.size.height,
+            if p.rotated { " (rotated)" } else { "" }
+        );
+    }
+    for bin in 0..packing.bins {
+        println!("  bin {} wasted area: {}", bin, packing.wasted_area(bin));
+    }
+    println!("  unplaced: {:?}", packing.unplaced);
+}
+
+pub fn main() {
+    println!("✅ Bin Packing");
+    let items = vec![
+        Rectangle::new(40, 20),
+        Rectangle::new(20, 40),
+        Rectangle::new(30, 30),
+        Rectangle::new(60, 10),
+        Rectangle::new(10, 60),
+        Rectangle::new(50, 50),
+        Rectangle::new(200, 10), // 比箱子大
+    ];
+    let bin = Rectangle::new(64, 64);
+
+    report(
+        "shelf",
+        &Packer::new(bin.clone(), Heuristic::Shelf).pack(&items),
+    );
+    report(
+        "shelf + rotation",
+        &Packer::new(bin.clone(), Heuristic::Shelf)
+            .with_rotation()
+            .pack(&items),
+    );
+    report(
+        "maxrects",
+        &Packer::new(bin.clone(), Heuristic::MaxRects).pack(&items),
+    );
+    report(
+        "maxrects + rotation",
+        &Packer::new(bin, Heuristic::MaxRects)
+            .with_rotation()
+            .pack(&items),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // 檢查所有不變量：在箱子內、同一箱不重疊、只在允許時旋轉、unplaced 剛好是放不下的
+    fn check(packer: &Packer, items: &[Rectangle]) -> Packing {
+        let packing = packer.pack(items);
+        let bin_rect = Rect {
+            x: 0,
+            y: 0,
+            size: packer.bin_size.clone(),
+        };
+        let fits = |item: &Rectangle| {
+            packer.bin_size.can_fit(item)
+                || (packer.allow_rotation
+                    && packer
+                        .bin_size
+                        .can_fit(&Rectangle::new(item.height, item.width)))
+        };
+        let expected_unplaced: Vec<usize> =
+            (0..items.len()).filter(|&i| !fits(&items[i])).collect();
+        assert_eq!(packing.unplaced, expected_unplaced);
+
+        let placed: Vec<usize> = packing.placements.iter().map(|p| p.index).collect();
+        let expected_placed: Vec<usize> = (0..items.len()).filter(|&i| fits(&items[i])).collect();
+        assert_eq!(placed, expected_placed);
+
+        for p in &packing.placements {
+            let item = &items[p.index];
+            assert!(p.bin < packing.bins);
+            assert!(bin_rect.contains_rect(&p.rect), "{:?} 超出箱子", p);
+            if p.rotated {
+                assert!(packer.allow_rotation, "{:?} 不允許旋轉", p);
+                assert_ne!(item.width, item.height);
+                assert_eq!(
+                    (p.rect.size.width, p.rect.size.height),
+                    (item.height, item.width)
+                );
+            } else {
+                assert_eq!(p.rect.size, *item);
+            }
+        }
+        for (i, a) in packing.placements.iter().enumerate() {
+            for b in &packing.placements[i + 1..] {
+                if a.bin == b.bin {
+                    assert!(!a.rect.intersects(&b.rect), "{:?} 和 {:?} 重疊", a, b);
+                }
+            }
+        }
+        // 每個箱子都至少有一個項目
+        for bin in 0..packing.bins {
+            assert!(packing.placements.iter().any(|p| p.bin == bin));
+            assert_eq!(
+                packing.used_area(bin) + packing.wasted_area(bin),
+                packer.bin_size.area()
+            );
+        }
+        packing
+    }
+
+    fn packers(bin: Rectangle) -> Vec<Packer> {
+        [Heuristic::Shelf, Heuristic::MaxRects]
+            .into_iter()
+            .flat_map(|h| {
+                [
+                    Packer::new(bin.clone(), h),
+                    Packer::new(bin.clone(), h).with_rotation(),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn demo_items() {
+        let items = vec![
+            Rectangle::new(40, 20),
+            Rectangle::new(20, 40),
+            Rectangle::new(30, 30),
+            Rectangle::new(60, 10),
+            Rectangle::new(10, 60),
+            Rectangle::new(50, 50),
+            Rectangle::new(200, 10),
+        ];
+        for packer in packers(Rectangle::new(64, 64)) {
+            let packing = check(&packer, &items);
+            assert_eq!(packing.unplaced, vec![6]);
+        }
+    }
+
+    #[test]
+    fn rotation_is_needed_for_tall_items() {
+        let items = vec![Rectangle::new(10, 100), Rectangle::new(100, 10)];
+        let bin = Rectangle::new(100, 20);
+        for packer in packers(bin) {
+            let packing = check(&packer, &items);
+            if packer.allow_rotation {
+                assert!(packing.unplaced.is_empty());
+                assert!(packing.placements[0].rotated);
+                assert_eq!(packing.bins, 1);
+            } else {
+                assert_eq!(packing.unplaced, vec![0]);
+            }
+        }
+    }
+
+    #[test]
+    fn empty_and_exact_fits() {
+        for packer in packers(Rectangle::new(10, 10)) {
+            let packing = check(&packer, &[]);
+            assert_eq!(packing.bins, 0);
+            let packing = check(&packer, &vec![Rectangle::new(10, 10); 3]);
+            assert_eq!(packing.bins, 3);
+            assert_eq!(packing.total_wasted_area(), 0);
+            let packing = check(&packer, &vec![Rectangle::new(5, 5); 4]);
+            assert_eq!(packing.bins, 1);
+        }
+    }
+
+    #[test]
+    fn random_items_keep_invariants() {
+        let mut rng = StdRng::seed_from_u64(29);
+        for round in 0..200 {
+            let bin = Rectangle::new(rng.random_range(1..80), rng.random_range(1..80));
+            let items: Vec<Rectangle> = (0..rng.random_range(0..30))
+                .map(|_| Rectangle::new(rng.random_range(1..100), rng.random_range(1..100)))
+                .collect();
+            for packer in packers(bin.clone()) {
+                let packing = check(&packer, &items);
+                assert!(packing.bins <= items.len(), "round {}", round);
+            }
+        }
+    }
+}
@@ -0,0 +1,514 @@
+// Spatial Index（四元樹 Quadtree）
+use crate::chapter5_3::{Point, Rect, Shape};
+use rand::Rng;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::time::Instant;
+
+const NODE_CAPACITY: usize = 8;
+const MAX_DEPTH: usize = 12;
+
+// 點到矩形的距離，點在矩形內為 0
+fn distance_to(rect: &Rect, point: Point) -> f64 {
+    let dx = (rect.x as f64 - point.x)
+        .max(point.x - rect.right() as f64)
+        .max(0.0);
+    let dy = (rect.y as f64 - point.y)
+        .max(point.y - rect.bottom() as f64)
+        .max(0.0);
+    (dx * dx + dy * dy).sqrt()
+}
+
+struct Node {
+    bounds: Rect,
+    depth: usize,
+    items: Vec<(usize, Rect)>, // 跨越子節點邊界的矩形留在這一層
+    children: Option<Box<[Node; 4]>>,
+}
+
+impl Node {
+    fn new(bounds: Rect, depth: usize) -> Node {
+        Node {
+            bounds,
+            depth,
+            items: Vec::new(),
+            children: None,
+        }
+    }
+
+    // 回傳完全包含 rect 的子節點位置
+    fn child_index(&self, rect: &Rect) -> Option<usize> {
+        self.children
+            .as_ref()?
+            .iter()
+            .position(|child| child.bounds.contains_rect(rect))
+    }
+
+    fn insert(&mut self, id: usize, rect: Rect) {
+        if let Some(i) = self.child_index(&rect) {
+            self.children.as_mut().unwrap()[i].insert(id, rect);
+            return;
+        }
+        self.items.push((id, rect));
+        if self.children.is_none() && self.items.len() > NODE_CAPACITY && self.depth < MAX_DEPTH {
+            self.split();
+        }
+    }
+
+    fn split(&mut self) {
+        let b = &self.bounds;
+        let half_w = b.size.width / 2;
+        let half_h = b.size.height / 2;
+        if half_w == 0 || half_h == 0 {
+            return;
+        }
+        let (mx, my) = (b.x + half_w as i64, b.y + half_h as i64);
+        let (rest_w, rest_h) = (b.size.width - half_w, b.size.height - half_h);
+        let depth = self.depth + 1;
+        self.children = Some(Box::new([
+            Node::new(Rect::new(b.x, b.y, half_w, half_h), depth),
+            Node::new(Rect::new(mx, b.y, rest_w, half_h), depth),
+            Node::new(Rect::new(b.x, my, half_w, rest_h), depth),
+            Node::new(Rect::new(mx, my, rest_w, rest_h), depth),
+        ]));
+        for (id, rect) in std::mem::take(&mut self.items) {
+            self.insert(id, rect);
+        }
+    }
+
+    // 依照插入時的路徑找回去
+    fn remove(&mut self, id: usize, rect: &Rect) -> bool {
+        if let Some(pos) = self.items.iter().position(|(item, _)| *item == id) {
+            self.items.swap_remove(pos);
+            return true;
+        }
+        match self.child_index(rect) {
+            Some(i) => self.children.as_mut().unwrap()[i].remove(id, rect),
+            None => false,
+        }
+    }
+
+    fn children(&self) -> &[Node] {
+        match &self.children {
+            Some(children) => &children[..],
+            None => &[],
+        }
+    }
+
+    fn query_point(&self, point: Point, result: &mut Vec<usize>) {
+        for (id, rect) in &self.items {
+            if rect.contains(point) {
+                result.push(*id);
+            }
+        }
+        for child in self.children() {
+            if child.bounds.contains(point) {
+                child.query_point(point, result);
+            }
+        }
+    }
+
+    fn query_range(&self, range: &Rect, result: &mut Vec<usize>) {
+        for (id, rect) in &self.items {
+            if rect.intersects(range) {
+                result.push(*id);
+            }
+        }
+        for child in self.children() {
+            if child.bounds.intersects(range) {
+                child.query_range(range, result);
+            }
+        }
+    }
+}
+
+// BinaryHeap 是 max-heap，這裡反過來讓距離小的先出來
+struct Candidate<'a> {
+    distance: f64,
+    node: &'a Node,
+}
+
+impl PartialEq for Candidate<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance == other.distance
+    }
+}
+
+impl Eq for Candidate<'_> {}
+
+impl PartialOrd for Candidate<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.distance.total_cmp(&self.distance)
+    }
+}
+
+pub struct QuadTree {
+    root: Node,
+    rects: HashMap<usize, Rect>,
+    next_id: usize,
+}
+
+impl QuadTree {
+    // bounds 外的矩形也能插入，只是會留在根節點
+    pub fn new(bounds: Rect) -> QuadTree {
+        QuadTree {
+            root: Node::new(bounds, 0),
+            rects: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Rect> {
+        self.rects.get(&id)
+    }
+
+    pub fn insert(&mut self, rect: Rect) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.rects.insert(id, rect.clone());
+        self.root.insert(id, rect);
+        id
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<Rect> {
+        let rect = self.rects.remove(&id)?;
+        self.root.remove(id, &rect);
+        Some(rect)
+    }
+
+    // 包含這個點的矩形（邊界也算）
+    pub fn query_point(&self, point: Point) -> Vec<usize> {
+        let mut result = Vec::new();
+        self.root.query_point(point, &mut result);
+        result
+    }
+
+    // 和 range 有重疊面積的矩形
+    pub fn query_range(&self, range: &Rect) -> Vec<usize> {
+        let mut result = Vec::new();
+        self.root.query_range(range, &mut result);
+        result
+    }
+
+    // Best-first search：先看離點最近的節點，比目前最佳還遠就停止
+    pub fn nearest(&self, point: Point) -> Option<(usize, f64)> {
+        let mut best: Option<(usize, f64)> = None;
+        let mut heap = BinaryHeap::new();
+        heap.push(Candidate {
+            distance: 0.0,
+            node: &self.root,
+        });
+        while let Some(Candidate { distance, node }) = heap.pop() {
+            if let Some((_, best_distance)) = best {
+                if distance > best_distance {
+                    break;
+                }
+            }
+            for (id, rect) in &node.items {
+                let d = distance_to(rect, point);
+                if best.map(|(_, b)| d < b).unwrap_or(true) {
+                    best = Some((*id, d));
+                }
+            }
+            for child in node.children() {
+                heap.push(Candidate {
+                    distance: distance_to(&child.bounds, point),
+                    node: child,
+                });
+            }
+        }
+        best
+    }
+}
+
+// 暴力搜尋，用來比較結果與速度
+fn brute_point(rects: &[(usize, Rect)], point: Point) -> Vec<usize> {
+    rects
+        .iter()
+        .filter(|(_, r)| r.contains(point))
+        .map(|(id, _)| *id)
+        .collect()
+}
+
+fn brute_range(rects: &[(usize, Rect)], range: &Rect) -> Vec<usize> {
+    rects
+        .iter()
+        .filter(|(_, r)| r.intersects(range))
+        .map(|(id, _)| *id)
+        .collect()
+}
+
+fn brute_nearest(rects: &[(usize, Rect)], point: Point) -> Option<f64> {
+    rects
+        .iter()
+        .map(|(_, r)| distance_to(r, point))
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+fn sorted(mut ids: Vec<usize>) -> Vec<usize> {
+    ids.sort();
+    ids
+}
+
+pub fn main() {
+    println!("✅ QuadTree");
+    let mut tree = QuadTree::new(Rect::new(0, 0, 100, 100));
+    let a = tree.insert(Rect::new(10, 10, 20, 20));
+    let b = tree.insert(Rect::new(25, 25, 10, 10));
+    let c = tree.insert(Rect::new(70, 70, 5, 5));
+    println!("a = {}, b = {}, c = {}", a, b, c);
+    println!(
+        "point (27, 27): {:?}",
+        sorted(tree.query_point(Point::new(27.0, 27.0)))
+    );
+    println!(
+        "range (0,0,50,50): {:?}",
+        sorted(tree.query_range(&Rect::new(0, 0, 50, 50)))
+    );
+    println!(
+        "nearest (90, 90): {:?}",
+        tree.nearest(Point::new(90.0, 90.0))
+    );
+    println!("remove b: {:?}", tree.remove(b));
+    println!(
+        "point (27, 27) after remove: {:?}",
+        tree.query_point(Point::new(27.0, 27.0))
+    );
+
+    println!("✅ Benchmark 100k rectangles");
+    let world = 100_000;
+    let mut rng = rand::rng();
+    let mut tree = QuadTree::new(Rect::new(0, 0, world as u32, world as u32));
+    let mut rects = Vec::new();
+    for _ in 0..100_000 {
+        let rect = Rect::new(
+            rng.random_range(0..world),
+            rng.random_range(0..world),
+            rng.random_range(1..200),
+            rng.random_range(1..200),
+        );
+        let id = tree.insert(rect.clone());
+        rects.push((id, rect));
+    }
+
+    let queries = 200;
+    let points: Vec<Point> = (0..queries)
+        .map(|_| {
+            Point::new(
+                rng.random_range(0.0..world as f64),
+                rng.random_range(0.0..world as f64),
+            )
+        })
+        .collect();
+    let ranges: Vec<Rect> = (0..queries)
+        .map(|_| {
+            Rect::new(
+                rng.random_range(0..world),
+                rng.random_range(0..world),
+                1000,
+                1000,
+            )
+        })
+        .collect();
+
+    let start = Instant::now();
+    let tree_points: Vec<Vec<usize>> = points
+        .iter()
+        .map(|&p| sorted(tree.query_point(p)))
+        .collect();
+    let tree_point_time = start.elapsed();
+    let start = Instant::now();
+    let brute_points: Vec<Vec<usize>> = points
+        .iter()
+        .map(|&p| sorted(brute_point(&rects, p)))
+        .collect();
+    let brute_point_time = start.elapsed();
+    println!(
+        "point query x{}: tree {:?} vs brute {:?}, same result: {}",
+        queries,
+        tree_point_time,
+        brute_point_time,
+        tree_points == brute_points
+    );
+
+    let start = Instant::now();
+    let tree_ranges: Vec<Vec<usize>> = ranges.iter().map(|r| sorted(tree.query_range(r))).collect();
+    let tree_range_time = start.elapsed();
+    let start = Instant::now();
+    let brute_ranges: Vec<Vec<usize>> = ranges
+        .iter()
+        .map(|r| sorted(brute_range(&rects, r)))
+        .collect();
+    let brute_range_time = start.elapsed();
+    println!(
+        "range query x{}: tree {:?} vs brute {:?}, same result: {}",
+        queries,
+        tree_range_time,
+        brute_range_time,
+        tree_ranges == brute_ranges
+    );
+
+    let start = Instant::now();
+    let tree_nearest: Vec<Option<f64>> = points
+        .iter()
+        .map(|&p| tree.nearest(p).map(|(_, d)| d))
+        .collect();
+    let tree_nearest_time = start.elapsed();
+    let start = Instant::now();
+    let brute_nearest: Vec<Option<f64>> =
+        points.iter().map(|&p| brute_nearest(&rects, p)).collect();
+    let brute_nearest_time = start.elapsed();
+    // 距離相同的矩形可能不只一個，所以比較距離
+    println!(
+        "nearest x{}: tree {:?} vs brute {:?}, same result: {}",
+        queries,
+        tree_nearest_time,
+        brute_nearest_time,
+        tree_nearest == brute_nearest
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // 和暴力搜尋比較所有查詢；nearest 距離相同的可能不只一個，所以比較距離
+    fn assert_matches_brute(tree: &QuadTree, rects: &[(usize, Rect)], rng: &mut StdRng) {
+        assert_eq!(tree.len(), rects.len());
+        for _ in 0..300 {
+            // 整數座標常常剛好落在邊界上
+            let point = if rng.random_bool(0.5) {
+                Point::new(
+                    rng.random_range(-600..1600) as f64,
+                    rng.random_range(-600..1600) as f64,
+                )
+            } else {
+                Point::new(
+                    rng.random_range(-600.0..1600.0),
+                    rng.random_range(-600.0..1600.0),
+                )
+            };
+            assert_eq!(
+                sorted(tree.query_point(point)),
+                sorted(brute_point(rects, point)),
+                "{:?}",
+                point
+            );
+            let nearest = tree.nearest(point);
+            assert_eq!(nearest.map(|(_, d)| d), brute_nearest(rects, point));
+            if let Some((id, d)) = nearest {
+                assert_eq!(distance_to(tree.get(id).unwrap(), point), d);
+            }
+
+            let range = Rect::new(
+                rng.random_range(-600..1600),
+                rng.random_range(-600..1600),
+                rng.random_range(0..400),
+                rng.random_range(0..400),
+            );
+            assert_eq!(
+                sorted(tree.query_range(&range)),
+                sorted(brute_range(rects, &range)),
+                "{:?}",
+                range
+            );
+        }
+    }
+
+    fn random_rect(rng: &mut StdRng) -> Rect {
+        // 大約一成放在 bounds 外面或跨出邊界
+        let (min, max) = if rng.random_bool(0.1) {
+            (-500, 1500)
+        } else {
+            (0, 1000)
+        };
+        Rect::new(
+            rng.random_range(min..max),
+            rng.random_range(min..max),
+            rng.random_range(0..60),
+            rng.random_range(0..60),
+        )
+    }
+
+    #[test]
+    fn queries_match_brute_force() {
+        let mut rng = StdRng::seed_from_u64(30);
+        let mut tree = QuadTree::new(Rect::new(0, 0, 1000, 1000));
+        let mut rects = Vec::new();
+        assert_matches_brute(&tree, &rects, &mut rng);
+        for _ in 0..3000 {
+            let rect = random_rect(&mut rng);
+            let id = tree.insert(rect.clone());
+            rects.push((id, rect));
+        }
+        assert_matches_brute(&tree, &rects, &mut rng);
+    }
+
+    #[test]
+    fn queries_match_brute_force_after_removes() {
+        let mut rng = StdRng::seed_from_u64(300);
+        let mut tree = QuadTree::new(Rect::new(0, 0, 1000, 1000));
+        let mut rects: Vec<(usize, Rect)> = Vec::new();
+        for round in 0..6 {
+            for _ in 0..500 {
+                let rect = random_rect(&mut rng);
+                let id = tree.insert(rect.clone());
+                rects.push((id, rect));
+            }
+            // 隨機移除一半
+            let mut kept = Vec::new();
+            for (id, rect) in rects {
+                if rng.random_bool(0.5) {
+                    assert_eq!(tree.remove(id), Some(rect));
+                    assert_eq!(tree.remove(id), None);
+                    assert_eq!(tree.get(id), None);
+                } else {
+                    kept.push((id, rect));
+                }
+            }
+            rects = kept;
+            assert_matches_brute(&tree, &rects, &mut rng);
+            assert!(!tree.is_empty(), "round {}", round);
+        }
+        for (id, _) in std::mem::take(&mut rects) {
+            tree.remove(id);
+        }
+        assert!(tree.is_empty());
+        assert_eq!(tree.nearest(Point::new(1.0, 1.0)), None);
+        assert_matches_brute(&tree, &rects, &mut rng);
+    }
+
+    #[test]
+    fn out_of_bounds_rects_are_found() {
+        let mut tree = QuadTree::new(Rect::new(0, 0, 100, 100));
+        for i in 0..50 {
+            tree.insert(Rect::new(i, i, 1, 1));
+        }
+        let far = tree.insert(Rect::new(-1000, 5000, 10, 10));
+        let edge = tree.insert(Rect::new(95, 95, 10, 10));
+        assert_eq!(tree.query_point(Point::new(-995.0, 5005.0)), vec![far]);
+        assert_eq!(tree.query_point(Point::new(103.0, 103.0)), vec![edge]);
+        assert_eq!(
+            tree.nearest(Point::new(-2000.0, 5000.0)),
+            Some((far, 1000.0))
+        );
+        assert_eq!(tree.remove(far), Some(Rect::new(-1000, 5000, 10, 10)));
+        assert!(tree.query_point(Point::new(-995.0, 5005.0)).is_empty());
+    }
+}
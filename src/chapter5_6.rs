@@ -0,0 +1,396 @@
+// Renderer（用方框字元畫出形狀，並輸出 SVG）
+use crate::chapter5_3::{BoundingBox, Circle, Point, Polygon, Rect, Shape, Triangle};
+use std::fmt;
+use std::fs;
+use std::io;
+
+// 每個格子連接的方向
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+// 依照連接方向選方框字元，重疊的線會自動接成 ┼ ├ ┬ 等
+fn box_char(mask: u8) -> char {
+    match mask {
+        0 => ' ',
+        m if m == LEFT | RIGHT || m == LEFT || m == RIGHT => '─',
+        m if m == UP | DOWN || m == UP || m == DOWN => '│',
+        m if m == DOWN | RIGHT => '┌',
+        m if m == DOWN | LEFT => '┐',
+        m if m == UP | RIGHT => '└',
+        m if m == UP | LEFT => '┘',
+        m if m == UP | DOWN | RIGHT => '├',
+        m if m == UP | DOWN | LEFT => '┤',
+        m if m == LEFT | RIGHT | DOWN => '┬',
+        m if m == LEFT | RIGHT | UP => '┴',
+        _ => '┼',
+    }
+}
+
+// 可以被畫出來的形狀
+#[derive(Debug, Clone)]
+pub enum Figure {
+    Rect(Rect),
+    Circle(Circle),
+    Triangle(Triangle),
+    Polygon(Polygon),
+}
+
+impl Figure {
+    fn shape(&self) -> &dyn Shape {
+        match self {
+            Figure::Rect(r) => r,
+            Figure::Circle(c) => c,
+            Figure::Triangle(t) => t,
+            Figure::Polygon(p) => p,
+        }
+    }
+
+    fn to_svg(&self) -> String {
+        let style = r#"fill="none" stroke="black""#;
+        match self {
+            Figure::Rect(r) => format!(
+                r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+                r.x, r.y, r.size.width, r.size.height, style
+            ),
+            Figure::Circle(c) => format!(
+                r#"<circle cx="{}" cy="{}" r="{}" {}/>"#,
+                c.center().x,
+                c.center().y,
+                c.radius(),
+                style
+            ),
+            Figure::Triangle(t) => svg_polygon(&[t.a, t.b, t.c], style),
+            Figure::Polygon(p) => svg_polygon(&p.points, style),
+        }
+    }
+}
+
+fn svg_polygon(points: &[Point], style: &str) -> String {
+    let points: Vec<String> = points.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+    format!(r#"<polygon points="{}" {}/>"#, points.join(" "), style)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenderError {
+    InvalidScale(f64), // scale 必須是正的有限數
+    TooLarge,          // 畫布超過 MAX_CELLS 格
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenderError::InvalidScale(scale) => write!(f, "無效的 scale: {}", scale),
+            RenderError::TooLarge => write!(f, "畫布超過 {} 格", MAX_CELLS),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+#[derive(Debug, Default)]
+pub struct Scene {
+    items: Vec<(Figure, Option<String>)>,
+}
+
+impl Scene {
+    pub fn new() -> Scene {
+        Scene::default()
+    }
+
+    pub fn add(&mut self, figure: Figure) -> &mut Scene {
+        self.items.push((figure, None));
+        self
+    }
+
+    pub fn add_labeled(&mut self, figure: Figure, label: &str) -> &mut Scene {
+        self.items.push((figure, Some(label.to_string())));
+        self
+    }
+
+    pub fn bounds(&self) -> Option<BoundingBox> {
+        let mut boxes = self.items.iter().map(|(f, _)| f.shape().bounding_box());
+        let first = boxes.next()?;
+        Some(boxes.fold(first, |acc, b| BoundingBox {
+            min: Point::new(acc.min.x.min(b.min.x), acc.min.y.min(b.min.y)),
+            max: Point::new(acc.max.x.max(b.max.x), acc.max.y.max(b.max.y)),
+        }))
+    }
+
+    // scale：每個字元格代表幾個單位；空的 Scene 畫出空字串
+    pub fn render(&self, scale: f64) -> Result<String, RenderError> {
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err(RenderError::InvalidScale(scale));
+        }
+        let bounds = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return Ok(String::new()),
+        };
+        let cells = |span: f64| {
+            let n = (span / scale).round();
+            (n.is_finite() && n < MAX_CELLS as f64).then(|| n as usize + 1)
+        };
+        // 限制的是總格數，不是每一邊
+        let (width, height) = match (
+            cells(bounds.max.x - bounds.min.x),
+            cells(bounds.max.y - bounds.min.y),
+        ) {
+            (Some(w), Some(h)) if w.checked_mul(h).is_some_and(|n| n <= MAX_CELLS) => (w, h),
+            _ => return Err(RenderError::TooLarge),
+        };
+        let mut canvas = Canvas::new(width, height);
+        let to_cell = |x: f64, y: f64| {
+            (
+                ((x - bounds.min.x) / scale).round() as usize,
+                ((y - bounds.min.y) / scale).round() as usize,
+            )
+        };
+
+        // 先畫線，最後才寫 label，避免被線蓋掉
+        for (figure, _) in &self.items {
+            match figure {
+                Figure::Rect(r) => {
+                    let (left, top) = to_cell(r.x as f64, r.y as f64);
+                    let (right, bottom) = to_cell(r.right() as f64, r.bottom() as f64);
+                    canvas.draw_box(left, top, right, bottom);
+                }
+                other => {
+                    let shape = other.shape();
+                    canvas.draw_outline(|cx, cy| {
+                        let x = bounds.min.x + cx as f64 * scale;
+                        let y = bounds.min.y + cy as f64 * scale;
+                        shape.contains(Point::new(x, y))
+                    });
+                }
+            }
+        }
+        for (figure, label) in &self.items {
+            if let Some(label) = label {
+                let b = figure.shape().bounding_box();
+                let (x, y) = to_cell(b.min.x, b.min.y);
+                canvas.write(x + 1, y, label);
+            }
+        }
+        Ok(canvas.to_string())
+    }
+
+    pub fn to_svg(&self) -> String {
+        let bounds = self.bounds().unwrap_or(BoundingBox {
+            min: Point::new(0.0, 0.0),
+            max: Point::new(0.0, 0.0),
+        });
+        let margin = 10.0;
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+            bounds.min.x - margin,
+            bounds.min.y - margin,
+            bounds.max.x - bounds.min.x + margin * 2.0,
+            bounds.max.y - bounds.min.y + margin * 2.0
+        );
+        svg.push('\n');
+        for (figure, label) in &self.items {
+            svg.push_str(&format!("  {}\n", figure.to_svg()));
+            if let Some(label) = label {
+                let b = figure.shape().bounding_box();
+                svg.push_str(&format!(
+                    "  <text x=\"{}\" y=\"{}\" font-size=\"10\">{}</text>\n",
+                    b.min.x + 2.0,
+                    b.min.y + 12.0,
+                    escape_xml(label)
+                ));
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn save_svg(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_svg())
+    }
+}
+
+// 終端機畫布最多幾格（寬 × 高），避免太小的 scale 配置出巨大的記憶體
+const MAX_CELLS: usize = 1_000_000;
+
+struct Canvas {
+    width: usize,
+    height: usize,
+    lines: Vec<u8>,           // 方框線的連接方向
+    marks: Vec<Option<char>>, // label 或曲線形狀的點，優先顯示
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Canvas {
+        Canvas {
+            width,
+            height,
+            lines: vec![0; width * height],
+            marks: vec![None; width * height],
+        }
+    }
+
+    fn connect(&mut self, x: usize, y: usize, mask: u8) {
+        if x < self.width && y < self.height {
+            self.lines[y * self.width + x] |= mask;
+        }
+    }
+
+    fn draw_box(&mut self, left: usize, top: usize, right: usize, bottom: usize) {
+        for x in left..right {
+            self.connect(x, top, RIGHT);
+            self.connect(x + 1, top, LEFT);
+            self.connect(x, bottom, RIGHT);
+            self.connect(x + 1, bottom, LEFT);
+        }
+        for y in top..bottom {
+            self.connect(left, y, DOWN);
+            self.connect(left, y + 1, UP);
+            self.connect(right, y, DOWN);
+            self.connect(right, y + 1, UP);
+        }
+    }
+
+    // 在形狀內、但旁邊有格子在形狀外的，就是邊緣
+    fn draw_outline(&mut self, inside: impl Fn(usize, usize) -> bool) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !inside(x, y) {
+                    continue;
+                }
+                let edge = x == 0
+                    || y == 0
+                    || x + 1 == self.width
+                    || y + 1 == self.height
+                    || !inside(x - 1, y)
+                    || !inside(x + 1, y)
+                    || !inside(x, y - 1)
+                    || !inside(x, y + 1);
+                if edge {
+                    self.marks[y * self.width + x] = Some('•');
+                }
+            }
+        }
+    }
+
+    fn write(&mut self, x: usize, y: usize, text: &str) {
+        for (i, c) in text.chars().enumerate() {
+            if x + i < self.width && y < self.height {
+                self.marks[y * self.width + x + i] = Some(c);
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Canvas {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for y in 0..self.height {
+            let row: String = (0..self.width)
+                .map(|x| {
+                    let i = y * self.width + x;
+                    self.marks[i].unwrap_or_else(|| box_char(self.lines[i]))
+                })
+                .collect();
+            writeln!(f, "{}", row.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+pub fn main() {
+    println!("✅ Renderer");
+    let mut scene = Scene::new();
+    scene
+        .add_labeled(Figure::Rect(Rect::new(0, 0, 30, 50)), "rect1")
+        .add_labeled(Figure::Rect(Rect::new(20, 10, 40, 20)), "rect2")
+        .add(Figure::Circle(
+            Circle::new(Point::new(45.0, 45.0), 10.0).unwrap(),
+        ))
+        .add_labeled(
+            Figure::Triangle(Triangle {
+                a: Point::new(60.0, 0.0),
+                b: Point::new(80.0, 0.0),
+                c: Point::new(70.0, 20.0),
+            }),
+            "tri",
+        );
+
+    for scale in [2.0, 5.0] {
+        println!("scale {}:", scale);
+        match scene.render(scale) {
+            Ok(text) => print!("{}", text),
+            Err(err) => println!("{}", err),
+        }
+    }
+    println!("scale 0: {:?}", scene.render(0.0));
+    println!("scale 0.0001: {:?}", scene.render(0.0001));
+
+    println!("✅ SVG");
+    print!("{}", scene.to_svg());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene() -> Scene {
+        let mut scene = Scene::new();
+        scene.add(Figure::Rect(Rect::new(0, 0, 10, 6)));
+        scene
+    }
+
+    #[test]
+    fn render_rejects_invalid_scales() {
+        let scene = scene();
+        for scale in [0.0, -0.0, -1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(
+                matches!(scene.render(scale), Err(RenderError::InvalidScale(_))),
+                "scale {}",
+                scale
+            );
+        }
+        assert!(!scene.render(1.0).unwrap().is_empty());
+        // 空的 Scene 和錯誤分得出來
+        assert_eq!(Scene::new().render(1.0), Ok(String::new()));
+        assert!(Scene::new().render(0.0).is_err());
+    }
+
+    #[test]
+    fn render_rejects_huge_canvas() {
+        assert_eq!(
+            scene().render(f64::MIN_POSITIVE),
+            Err(RenderError::TooLarge)
+        );
+        let mut far = Scene::new();
+        far.add(Figure::Rect(Rect::new(i64::MIN, 0, 1, 1)));
+        far.add(Figure::Rect(Rect::new(i64::MAX, 0, 1, 1)));
+        assert_eq!(far.render(1.0), Err(RenderError::TooLarge));
+    }
+
+    #[test]
+    fn render_limits_total_cells() {
+        // 每邊都在限制內，但總格數太多
+        let mut square = Scene::new();
+        square.add(Figure::Rect(Rect::new(0, 0, 9_990, 9_990)));
+        assert_eq!(square.render(1.0), Err(RenderError::TooLarge));
+        assert!(square.render(10.0).is_ok()); // 1000 x 1000 格剛好
+                                              // 很長但很窄的畫布可以
+        let mut line = Scene::new();
+        line.add(Figure::Rect(Rect::new(0, 0, 50_000, 1)));
+        let text = line.render(1.0).unwrap();
+        assert_eq!(text.lines().count(), 2);
+    }
+
+    #[test]
+    fn render_draws_boxes() {
+        let text = scene().render(2.0).unwrap();
+        assert_eq!(text, "┌────┐\n│    │\n│    │\n└────┘\n");
+    }
+}
@@ -0,0 +1,594 @@
+// Parsing（從文字建立 Rectangle 與 User）
+use crate::chapter5::{Rectangle, User};
+use crate::chapter5_10::{Date, MAX_LEGACY_AGE};
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    Empty,
+    ExpectedNumber,
+    NumberTooLarge,
+    Expected(&'static str),
+    EmptyName,
+    InvalidEmail,
+    InvalidDate,
+    AgeOutOfRange,
+    TrailingInput,
+}
+
+// span 是出錯位置在輸入字串中的 byte 範圍
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub span: Range<usize>,
+}
+
+impl ParseError {
+    fn new(kind: ParseErrorKind, span: Range<usize>) -> ParseError {
+        ParseError { kind, span }
+    }
+
+    // 把輸入印出來，並用 ^^^ 標出錯誤位置
+    pub fn render(&self, input: &str) -> String {
+        let column = input[..self.span.start].chars().count();
+        let width = input[self.span.clone()].chars().count().max(1);
+        format!(
+            "{}\n{}{} {}",
+            input,
+            " ".repeat(column),
+            "^".repeat(width),
+            self
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::Empty => write!(f, "輸入是空的"),
+            ParseErrorKind::ExpectedNumber => write!(f, "需要數字"),
+            ParseErrorKind::NumberTooLarge => write!(f, "數字超過 u32 範圍"),
+            ParseErrorKind::Expected(what) => write!(f, "需要 {}", what),
+            ParseErrorKind::EmptyName => write!(f, "名稱不能是空的"),
+            ParseErrorKind::InvalidEmail => write!(f, "email 格式錯誤"),
+            ParseErrorKind::InvalidDate => write!(f, "日期格式錯誤，需要 YYYY-MM-DD"),
+            ParseErrorKind::AgeOutOfRange => write!(f, "年齡需要在 0-{} 之間", MAX_LEGACY_AGE),
+            ParseErrorKind::TrailingInput => write!(f, "多出無法解析的內容"),
+        }?;
+        write!(f, " (位置 {}..{})", self.span.start, self.span.end)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// 小型的手寫 parser，記錄目前讀到的位置
+struct Cursor<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(input: &'a str) -> Cursor<'a> {
+        Cursor { input, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    // 目前位置下一個字元的範圍，用於錯誤訊息
+    fn next_span(&self) -> Range<usize> {
+        let len = self
+            .rest()
+            .chars()
+            .next()
+            .map(|c| c.len_utf8())
+            .unwrap_or(0);
+        self.pos..self.pos + len
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &'static str) -> Result<(), ParseError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(ParseError::new(
+                ParseErrorKind::Expected(token),
+                self.next_span(),
+            ))
+        }
+    }
+
+    fn number(&mut self) -> Result<u32, ParseError> {
+        self.skip_whitespace();
+        let digits = self.rest().len()
+            - self
+                .rest()
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        if digits == 0 {
+            return Err(ParseError::new(
+                ParseErrorKind::ExpectedNumber,
+                self.next_span(),
+            ));
+        }
+        let span = self.pos..self.pos + digits;
+        self.pos += digits;
+        self.input[span.clone()]
+            .parse()
+            .map_err(|_| ParseError::new(ParseErrorKind::NumberTooLarge, span))
+    }
+
+    // 讀取一段連續符合條件的字元
+    fn take_while(&mut self, accept: impl Fn(char) -> bool) -> (&'a str, Range<usize>) {
+        self.skip_whitespace();
+        let start = self.pos;
+        let len = self.rest().len() - self.rest().trim_start_matches(accept).len();
+        self.pos += len;
+        (&self.input[start..self.pos], start..self.pos)
+    }
+
+    // 讀取 "..." 內的文字，\" 和 \\ 是跳脫字元；範圍包含引號
+    fn quoted(&mut self) -> Result<(String, Range<usize>), ParseError> {
+        let start = self.pos;
+        self.expect("\"")?;
+        let mut text = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok((text, start..self.pos));
+                }
+                '\\' => match chars.next() {
+                    Some((_, escaped)) => text.push(escaped),
+                    None => break,
+                },
+                c => text.push(c),
+            }
+        }
+        self.pos = self.input.len();
+        Err(ParseError::new(
+            ParseErrorKind::Expected("\""),
+            self.pos..self.pos,
+        ))
+    }
+
+    // 讀到 stop 為止（不包含 stop），回傳內容與範圍
+    fn until(&mut self, stop: char) -> (&'a str, Range<usize>) {
+        let start = self.pos;
+        let len = self.rest().find(stop).unwrap_or(self.rest().len());
+        self.pos += len;
+        (&self.input[start..self.pos], start..self.pos)
+    }
+
+    fn end(&mut self) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.rest().is_empty() {
+            Ok(())
+        } else {
+            Err(ParseError::new(
+                ParseErrorKind::TrailingInput,
+                self.pos..self.input.len(),
+            ))
+        }
+    }
+}
+
+fn check_empty(s: &str) -> Result<(), ParseError> {
+    if s.trim().is_empty() {
+        Err(ParseError::new(ParseErrorKind::Empty, 0..s.len()))
+    } else {
+        Ok(())
+    }
+}
+
+// 支援 "30x50"、"30 × 50" 與 "Rectangle(30, 50)"
+impl FromStr for Rectangle {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Rectangle, ParseError> {
+        check_empty(s)?;
+        let mut cursor = Cursor::new(s);
+        let rect = if cursor.eat("Rectangle") {
+            cursor.expect("(")?;
+            let width = cursor.number()?;
+            cursor.expect(",")?;
+            let height = cursor.number()?;
+            cursor.expect(")")?;
+            Rectangle::new(width, height)
+        } else {
+            let width = cursor.number()?;
+            if !(cursor.eat("x") || cursor.eat("X") || cursor.eat("×")) {
+                return Err(ParseError::new(
+                    ParseErrorKind::Expected("x"),
+                    cursor.next_span(),
+                ));
+            }
+            let height = cursor.number()?;
+            Rectangle::new(width, height)
+        };
+        cursor.end()?;
+        Ok(rect)
+    }
+}
+
+impl fmt::Display for Rectangle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+// < 和 > 是 email 的分隔符號，不能出現在 email 裡，否則 Display 後無法 parse 回來
+fn valid_email(email: &str) -> bool {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.is_empty()
+                && !domain.contains('@')
+                && !email.contains(|c: char| c.is_whitespace() || c == '<' || c == '>')
+        }
+        None => false,
+    }
+}
+
+// 名稱有前後空白、包含 <，或以 " 開頭時要加上引號才能 parse 回來
+fn needs_quotes(name: &str) -> bool {
+    name.trim() != name || name.contains('<') || name.starts_with('"')
+}
+
+fn quote(name: &str) -> String {
+    let escaped = name.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{}\"", escaped)
+}
+
+enum Birth {
+    Date(Date),
+    LegacyAge(u32),
+}
+
+// 格式："name <email>, YYYY-MM-DD"，後面可以接 ", inactive"
+// name 可以用引號包起來："  Bob <3 " <bob@example.com>, 2000-01-01
+// 舊格式 "name <email>, age" 仍然接受，用 User::from_legacy_age 以 loaded_on 換算生日
+impl User {
+    pub fn parse_with(s: &str, loaded_on: Date) -> Result<User, ParseError> {
+        check_empty(s)?;
+        let mut cursor = Cursor::new(s);
+
+        cursor.skip_whitespace();
+        let (username, name_span) = if cursor.rest().starts_with('"') {
+            cursor.quoted()?
+        } else {
+            let (name, span) = cursor.until('<');
+            (name.trim().to_string(), span)
+        };
+        if username.trim().is_empty() {
+            return Err(ParseError::new(ParseErrorKind::EmptyName, name_span));
+        }
+
+        cursor.expect("<")?;
+        let (email, email_span) = cursor.until('>');
+        if !valid_email(email) {
+            return Err(ParseError::new(ParseErrorKind::InvalidEmail, email_span));
+        }
+        cursor.expect(">")?;
+        cursor.expect(",")?;
+        let (token, token_span) = cursor.take_while(|c| c.is_ascii_digit() || c == '-');
+        let birth = if token.contains('-') {
+            let date = token
+                .parse::<Date>()
+                .map_err(|_| ParseError::new(ParseErrorKind::InvalidDate, token_span.clone()))?;
+            Birth::Date(date)
+        } else {
+            cursor.pos = token_span.start;
+            Birth::LegacyAge(cursor.number()?)
+        };
+
+        let mut active = true;
+        if cursor.eat(",") {
+            if cursor.eat("inactive") {
+                active = false;
+            } else if !cursor.eat("active") {
+                return Err(ParseError::new(
+                    ParseErrorKind::Expected("active 或 inactive"),
+                    cursor.next_span(),
+                ));
+            }
+        }
+        cursor.end()?;
+
+        let email = email.to_string();
+        match birth {
+            Birth::Date(birth_date) => Ok(User {
+                username,
+                email,
+                birth_date,
+                active,
+            }),
+            Birth::LegacyAge(age) => User::from_legacy_age(username, email, age, active, loaded_on)
+                .ok_or_else(|| ParseError::new(ParseErrorKind::AgeOutOfRange, token_span)),
+        }
+    }
+}
+
+// 舊格式的年齡以今天為載入日期換算；需要固定結果時改用 User::parse_with
+impl FromStr for User {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<User, ParseError> {
+        User::parse_with(s, Date::today())
+    }
+}
+
+impl fmt::Display for User {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if needs_quotes(&self.username) {
+            write!(f, "{}", quote(&self.username))?;
+        } else {
+            write!(f, "{}", self.username)?;
+        }
+        write!(f, " <{}>, {}", self.email, self.birth_date)?;
+        if !self.active {
+            write!(f, ", inactive")?;
+        }
+        Ok(())
+    }
+}
+
+pub fn main() {
+    println!("✅ Parse Rectangle");
+    for input in ["30x50", " 30 × 50 ", "Rectangle(30, 50)"] {
+        let rect: Rectangle = input.parse().unwrap();
+        println!("{:?} -> {:?} -> {}", input, rect, rect);
+        // Display 輸出可以再 parse 回來
+        println!(
+            "round trip: {}",
+            rect.to_string().parse::<Rectangle>().unwrap() == rect
+        );
+    }
+    for input in [
+        "",
+        "30x",
+        "30*50",
+        "Rectangle(30 50)",
+        "99999999999x1",
+        "30x50 extra",
+    ] {
+        match input.parse::<Rectangle>() {
+            Ok(rect) => println!("{:?} -> {:?}", input, rect),
+            Err(err) => println!("{}", err.render(input)),
+        }
+    }
+
+    println!("✅ Parse User");
+    // 舊格式的年齡用固定的載入日期換算，結果不會隨執行日期改變
+    let loaded_on = Date::new(2026, 10, 19).unwrap();
+    for input in [
+        "John <john@example.com>, 2006-05-17",
+        "陳小明 <ming@example.com>, 1996-08-01, inactive",
+        "Old <old@example.com>, 20", // 舊格式
+    ] {
+        let user = User::parse_with(input, loaded_on).unwrap();
+        println!("{:?} -> {:?}", input, user);
+        let again: User = user.to_string().parse().unwrap();
+        println!("round trip: {}", again.to_string() == user.to_string());
+    }
+    for input in [
+        "<john@example.com>, 20",
+        "John <john.example.com>, 20",
+        "John <john@example.com> 20",
+        "John <john@example.com>, twenty",
+        "John <john@example.com>, 2006-02-30",
+        "John <john@example.com>, 20, maybe",
+        "John <john@example.com>, 151",
+        "x <a@b>, 2147485000",
+    ] {
+        match User::parse_with(input, loaded_on) {
+            Ok(user) => println!("{:?} -> {:?}", input, user),
+            Err(err) => println!("{}", err.render(input)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loaded_on() -> Date {
+        Date::new(2026, 10, 19).unwrap()
+    }
+
+    #[test]
+    fn legacy_age_uses_the_load_date() {
+        let user = User::parse_with("Old <old@example.com>, 20", loaded_on()).unwrap();
+        assert_eq!(user.birth_date, Date::new(2006, 10, 19).unwrap());
+        let earlier =
+            User::parse_with("Old <old@example.com>, 20", Date::new(2000, 2, 29).unwrap());
+        assert_eq!(earlier.unwrap().birth_date, Date::new(1980, 2, 29).unwrap());
+    }
+
+    #[test]
+    fn legacy_age_out_of_range_is_a_parse_error() {
+        for (input, span) in [
+            ("John <john@example.com>, 151", 25..28),
+            ("x <a@b>, 2147485000", 9..19),
+            ("x <a@b>, 4294967295", 9..19),
+        ] {
+            let err = User::parse_with(input, loaded_on()).unwrap_err();
+            assert_eq!(err.kind, ParseErrorKind::AgeOutOfRange, "{}", input);
+            assert_eq!(err.span, span, "{}", input);
+        }
+        let max = format!("x <a@b>, {}", MAX_LEGACY_AGE);
+        assert!(User::parse_with(&max, loaded_on()).is_ok());
+        let err = User::parse_with("x <a@b>, 99999999999", loaded_on()).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::NumberTooLarge);
+    }
+
+    #[test]
+    fn birth_date_form_ignores_the_load_date() {
+        let input = "陳小明 <ming@example.com>, 1996-08-01, inactive";
+        let a = User::parse_with(input, loaded_on()).unwrap();
+        let b = User::parse_with(input, Date::new(1900, 1, 1).unwrap()).unwrap();
+        assert_eq!(a.birth_date, b.birth_date);
+        assert!(!a.active);
+        assert_eq!(a.to_string(), input);
+    }
+
+    #[test]
+    fn rectangle_forms() {
+        for input in [
+            "30x50",
+            "30X50",
+            "30×50",
+            " 30 × 50 ",
+            "Rectangle(30, 50)",
+            "Rectangle( 30 ,50 )",
+        ] {
+            assert_eq!(
+                input.parse::<Rectangle>(),
+                Ok(Rectangle::new(30, 50)),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            "4294967295x0".parse::<Rectangle>(),
+            Ok(Rectangle::new(u32::MAX, 0))
+        );
+    }
+
+    #[test]
+    fn rectangle_error_spans() {
+        use ParseErrorKind::*;
+        for (input, kind, span) in [
+            ("", Empty, 0..0),
+            ("   ", Empty, 0..3),
+            ("30x", ExpectedNumber, 3..3),
+            ("30*50", Expected("x"), 2..3),
+            ("30×", ExpectedNumber, 4..4),
+            ("x50", ExpectedNumber, 0..1),
+            ("Rectangle(30 50)", Expected(","), 13..14),
+            ("Rectangle 30, 50)", Expected("("), 10..11),
+            ("Rectangle(30, 50", Expected(")"), 16..16),
+            ("99999999999x1", NumberTooLarge, 0..11),
+            ("30x50 extra", TrailingInput, 6..11),
+        ] {
+            let err = input.parse::<Rectangle>().unwrap_err();
+            assert_eq!((err.kind, err.span), (kind, span), "{:?}", input);
+        }
+        // 多 byte 字元的 span 仍然落在字元邊界上
+        let err = "30×÷".parse::<Rectangle>().unwrap_err();
+        assert_eq!(err.span, 4..6);
+        assert_eq!(err.render("30×÷"), "30×÷\n   ^ 需要數字 (位置 4..6)");
+    }
+
+    #[test]
+    fn rectangle_display_round_trip() {
+        for (w, h) in [(0, 0), (30, 50), (u32::MAX, 1)] {
+            let rect = Rectangle::new(w, h);
+            assert_eq!(rect.to_string().parse::<Rectangle>(), Ok(rect));
+        }
+    }
+
+    fn assert_round_trip(user: &User) {
+        let text = user.to_string();
+        let again = User::parse_with(&text, loaded_on())
+            .unwrap_or_else(|err| panic!("{}", err.render(&text)));
+        assert_eq!(again.username, user.username, "{}", text);
+        assert_eq!(again.email, user.email);
+        assert_eq!(again.birth_date, user.birth_date);
+        assert_eq!(again.active, user.active);
+        assert_eq!(again.to_string(), text);
+    }
+
+    #[test]
+    fn user_display_round_trip() {
+        let birth_date = Date::new(1996, 8, 1).unwrap();
+        for (username, active) in [
+            ("John", true),
+            ("陳小明", false),
+            ("Mary Jane", true),
+            ("Bob <3", true),
+            ("  padded  ", true),
+            (" ", false),
+            ("\"quoted\"", true),
+            ("back\\slash \" <", true),
+            ("trailing\\", true),
+            ("a, b", true),
+        ] {
+            let user = User {
+                username: username.to_string(),
+                email: String::from("user@example.com"),
+                birth_date,
+                active,
+            };
+            // 只有空白的名稱無法建立
+            if username.trim().is_empty() {
+                let err = User::parse_with(&user.to_string(), loaded_on()).unwrap_err();
+                assert_eq!(err.kind, ParseErrorKind::EmptyName);
+                continue;
+            }
+            assert_round_trip(&user);
+        }
+        assert_eq!(
+            User {
+                username: String::from("Bob <3"),
+                email: String::from("bob@example.com"),
+                birth_date,
+                active: true,
+            }
+            .to_string(),
+            "\"Bob <3\" <bob@example.com>, 1996-08-01"
+        );
+    }
+
+    #[test]
+    fn quoted_names() {
+        let user =
+            User::parse_with("  \" Ann \"  <ann@example.com>, 2000-01-01", loaded_on()).unwrap();
+        assert_eq!(user.username, " Ann ");
+        let err = User::parse_with("\"Ann <ann@example.com>, 2000-01-01", loaded_on()).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::Expected("\""));
+        let err =
+            User::parse_with("\"Ann\" x <ann@example.com>, 2000-01-01", loaded_on()).unwrap_err();
+        assert_eq!((err.kind, err.span), (ParseErrorKind::Expected("<"), 6..7));
+    }
+
+    #[test]
+    fn user_error_spans() {
+        use ParseErrorKind::*;
+        for (input, kind, span) in [
+            ("<john@example.com>, 20", EmptyName, 0..0),
+            ("John <john.example.com>, 20", InvalidEmail, 6..22),
+            ("John <a<b@example.com>, 20", InvalidEmail, 6..21),
+            ("John <john@example.com> 20", Expected(","), 24..25),
+            ("John <john@example.com>, twenty", ExpectedNumber, 25..26),
+            ("John <john@example.com>, 2006-02-30", InvalidDate, 25..35),
+            (
+                "John <john@example.com>, 20, maybe",
+                Expected("active 或 inactive"),
+                29..30,
+            ),
+            ("John <john@example.com", Expected(">"), 22..22),
+        ] {
+            let err = User::parse_with(input, loaded_on()).unwrap_err();
+            assert_eq!((err.kind, err.span), (kind, span), "{:?}", input);
+        }
+    }
+}
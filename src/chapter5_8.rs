@@ -0,0 +1,300 @@
+// Transforms（不消耗 self，回傳新的 Rectangle）
+use crate::chapter5::Rectangle;
+use crate::chapter5_9::Unit;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rounding {
+    Nearest,
+    Down,
+    Up,
+}
+
+impl Rounding {
+    fn apply(self, value: f64) -> f64 {
+        match self {
+            Rounding::Nearest => value.round(),
+            Rounding::Down => value.floor(),
+            Rounding::Up => value.ceil(),
+        }
+    }
+}
+
+impl<U: Unit> Rectangle<U> {
+    // 和 into_square 一樣，但只借用 self
+    pub fn to_square(&self) -> Rectangle<U> {
+        let size = self.width.max(self.height);
+        Rectangle::of(size, size)
+    }
+
+    // 旋轉 90°
+    pub fn rotated(&self) -> Rectangle<U> {
+        Rectangle::of(self.height, self.width)
+    }
+
+    pub fn aspect_ratio(&self) -> f64 {
+        self.width as f64 / self.height as f64
+    }
+
+    // 保持長寬比，縮放到剛好放進 bounds 的最大尺寸（可放大也可縮小）
+    // 用整數交叉相乘比較，避免浮點誤差；結果無條件捨去，所以一定放得進去
+    pub fn fit_within(&self, bounds: &Rectangle<U>) -> Rectangle<U> {
+        if self.width == 0 || self.height == 0 {
+            return Rectangle::of(self.width.min(bounds.width), self.height.min(bounds.height));
+        }
+        let (w, h) = (self.width as u64, self.height as u64);
+        let (bw, bh) = (bounds.width as u64, bounds.height as u64);
+        if w * bh <= h * bw {
+            // 高度先碰到邊界
+            Rectangle::of((w * bh / h) as u32, bounds.height)
+        } else {
+            Rectangle::of(bounds.width, (h * bw / w) as u32)
+        }
+    }
+
+    // factor 是負數、NaN 或結果超過 u32 時回傳 None
+    pub fn scaled_by(&self, factor: f64, rounding: Rounding) -> Option<Rectangle<U>> {
+        if !factor.is_finite() || factor < 0.0 {
+            return None;
+        }
+        let scale = |value: u32| {
+            let scaled = rounding.apply(value as f64 * factor);
+            if scaled > u32::MAX as f64 {
+                None
+            } else {
+                Some(scaled as u32)
+            }
+        };
+        Some(Rectangle::of(scale(self.width)?, scale(self.height)?))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transform {
+    Square,
+    Rotate,
+    FitWithin(Rectangle),
+    Scale(f64, Rounding),
+}
+
+impl Transform {
+    pub fn apply(&self, rect: &Rectangle) -> Option<Rectangle> {
+        match self {
+            Transform::Square => Some(rect.to_square()),
+            Transform::Rotate => Some(rect.rotated()),
+            Transform::FitWithin(bounds) => Some(rect.fit_within(bounds)),
+            Transform::Scale(factor, rounding) => rect.scaled_by(*factor, *rounding),
+        }
+    }
+}
+
+// 可以串接的轉換流程，依序套用
+#[derive(Debug, Clone, Default)]
+pub struct Pipeline {
+    steps: Vec<Transform>,
+}
+
+impl Pipeline {
+    pub fn new() -> Pipeline {
+        Pipeline::default()
+    }
+
+    pub fn square(mut self) -> Pipeline {
+        self.steps.push(Transform::Square);
+        self
+    }
+
+    pub fn rotate(mut self) -> Pipeline {
+        self.steps.push(Transform::Rotate);
+        self
+    }
+
+    pub fn fit_within(mut self, bounds: Rectangle) -> Pipeline {
+        self.steps.push(Transform::FitWithin(bounds));
+        self
+    }
+
+    pub fn scale(mut self, factor: f64, rounding: Rounding) -> Pipeline {
+        self.steps.push(Transform::Scale(factor, rounding));
+        self
+    }
+
+    // 任何一步失敗（例如溢位）整個回傳 None
+    pub fn apply(&self, rect: &Rectangle) -> Option<Rectangle> {
+        self.steps
+            .iter()
+            .try_fold(rect.clone(), |current, step| step.apply(&current))
+    }
+}
+
+pub fn main() {
+    println!("✅ Transforms");
+    let rect = Rectangle::new(30, 50);
+    println!("rect: {:?}", rect);
+    println!("to_square: {:?}", rect.to_square());
+    println!("rotated: {:?}", rect.rotated());
+    println!("rect 還可以用: {:?}", rect); // 沒有被消耗
+
+    println!("✅ fit_within 保持長寬比");
+    let screens = [
+        Rectangle::new(1920, 1080),
+        Rectangle::new(100, 100),
+        Rectangle::new(7, 13),
+    ];
+    for screen in screens.iter() {
+        let fitted = rect.fit_within(screen);
+        println!(
+            "{:?} in {:?} -> {:?}, fits: {}, ratio {:.4} vs {:.4}",
+            rect,
+            screen,
+            fitted,
+            screen.can_fit(&fitted),
+            rect.aspect_ratio(),
+            fitted.aspect_ratio()
+        );
+    }
+
+    println!("✅ scaled_by");
+    let odd = Rectangle::new(3, 5);
+    for rounding in [Rounding::Nearest, Rounding::Down, Rounding::Up] {
+        println!(
+            "{:?} x1.5 {:?}: {:?}",
+            odd,
+            rounding,
+            odd.scaled_by(1.5, rounding)
+        );
+    }
+    println!("x-1: {:?}", odd.scaled_by(-1.0, Rounding::Nearest));
+    println!(
+        "u32::MAX x2: {:?}",
+        Rectangle::new(u32::MAX, 1).scaled_by(2.0, Rounding::Nearest)
+    );
+
+    println!("✅ Pipeline");
+    let pipeline = Pipeline::new()
+        .rotate()
+        .fit_within(Rectangle::new(200, 100))
+        .scale(0.5, Rounding::Down);
+    let result = pipeline.apply(&rect).unwrap();
+    println!("{:?} -> {:?}", rect, result);
+    println!(
+        "rotated ratio {:.4} vs result ratio {:.4}",
+        rect.rotated().aspect_ratio(),
+        result.aspect_ratio()
+    );
+    println!(
+        "square pipeline: {:?}",
+        Pipeline::new()
+            .square()
+            .scale(2.0, Rounding::Up)
+            .apply(&rect)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZES: [u32; 9] = [1, 2, 3, 7, 13, 30, 50, 1080, u32::MAX];
+
+    #[test]
+    fn fit_within_fits_and_keeps_aspect_ratio() {
+        for w in SIZES {
+            for h in SIZES {
+                for bw in SIZES {
+                    for bh in SIZES {
+                        let rect = Rectangle::new(w, h);
+                        let bounds = Rectangle::new(bw, bh);
+                        let fitted = rect.fit_within(&bounds);
+                        assert!(bounds.can_fit(&fitted), "{:?} in {:?}", rect, bounds);
+                        // 至少一邊碰到邊界
+                        assert!(fitted.width == bw || fitted.height == bh);
+                        // 另一邊是無條件捨去的結果，交叉相乘的誤差小於一格
+                        let (w, h) = (w as u128, h as u128);
+                        let (fw, fh) = (fitted.width as u128, fitted.height as u128);
+                        assert!(
+                            (w * fh).abs_diff(fw * h) < w.max(h),
+                            "{:?} in {:?}",
+                            rect,
+                            bounds
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fit_within_exact_ratios() {
+        let rect = Rectangle::new(30, 50);
+        assert_eq!(
+            rect.fit_within(&Rectangle::new(1920, 1080)),
+            Rectangle::new(648, 1080)
+        );
+        assert_eq!(
+            rect.fit_within(&Rectangle::new(100, 100)),
+            Rectangle::new(60, 100)
+        );
+        assert_eq!(
+            rect.fit_within(&Rectangle::new(7, 13)),
+            Rectangle::new(7, 11)
+        );
+        assert_eq!(
+            Rectangle::new(0, 5).fit_within(&Rectangle::new(3, 3)),
+            Rectangle::new(0, 3)
+        );
+    }
+
+    #[test]
+    fn scaled_by_rejects_invalid_factors() {
+        let rect = Rectangle::new(30, 50);
+        for factor in [
+            f64::NAN,
+            -1.0,
+            -f64::MIN_POSITIVE,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ] {
+            assert_eq!(
+                rect.scaled_by(factor, Rounding::Nearest),
+                None,
+                "{}",
+                factor
+            );
+        }
+        assert_eq!(
+            rect.scaled_by(0.0, Rounding::Up),
+            Some(Rectangle::new(0, 0))
+        );
+    }
+
+    #[test]
+    fn scaled_by_rejects_overflow() {
+        let max = Rectangle::new(u32::MAX, 1);
+        assert_eq!(max.scaled_by(1.0, Rounding::Nearest), Some(max.clone()));
+        assert_eq!(max.scaled_by(2.0, Rounding::Nearest), None);
+        assert_eq!(max.scaled_by(1.0 + 1e-9, Rounding::Up), None);
+        assert_eq!(max.scaled_by(1e300, Rounding::Down), None);
+    }
+
+    #[test]
+    fn scaled_by_rounding_modes() {
+        let rect = Rectangle::new(3, 5);
+        assert_eq!(
+            rect.scaled_by(0.5, Rounding::Down),
+            Some(Rectangle::new(1, 2))
+        );
+        assert_eq!(
+            rect.scaled_by(0.5, Rounding::Up),
+            Some(Rectangle::new(2, 3))
+        );
+        assert_eq!(
+            rect.scaled_by(0.5, Rounding::Nearest),
+            Some(Rectangle::new(2, 3))
+        );
+        // 放大再縮小，長寬比不變
+        let round_trip = rect
+            .scaled_by(4.0, Rounding::Nearest)
+            .and_then(|r| r.scaled_by(0.25, Rounding::Nearest));
+        assert_eq!(round_trip, Some(rect));
+    }
+}
@@ -0,0 +1,316 @@
+// Units（型別安全的長度單位）
+use crate::chapter5::Rectangle;
+use std::fmt;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Sub};
+
+// 每英吋幾個像素，只有 px 換算時需要
+// 必須是正的有限數，否則換算會得到 inf 或 NaN，所以只能透過 Dpi::new 建立
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dpi(f64);
+
+impl Dpi {
+    pub fn new(value: f64) -> Option<Dpi> {
+        if value.is_finite() && value > 0.0 {
+            Some(Dpi(value))
+        } else {
+            None
+        }
+    }
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl Default for Dpi {
+    fn default() -> Dpi {
+        Dpi(96.0) // CSS 的標準像素
+    }
+}
+
+// 單位只存在於型別上（zero-sized），執行時不佔空間
+pub trait Unit: Debug + Clone + Copy + PartialEq + Eq + Default {
+    const SUFFIX: &'static str;
+
+    // 1 英吋等於多少這個單位
+    fn per_inch(dpi: Dpi) -> f64;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Px;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Mm;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Cm;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct In;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Pt;
+
+impl Unit for Px {
+    const SUFFIX: &'static str = "px";
+    fn per_inch(dpi: Dpi) -> f64 {
+        dpi.0
+    }
+}
+
+impl Unit for Mm {
+    const SUFFIX: &'static str = "mm";
+    fn per_inch(_: Dpi) -> f64 {
+        25.4
+    }
+}
+
+impl Unit for Cm {
+    const SUFFIX: &'static str = "cm";
+    fn per_inch(_: Dpi) -> f64 {
+        2.54
+    }
+}
+
+impl Unit for In {
+    const SUFFIX: &'static str = "in";
+    fn per_inch(_: Dpi) -> f64 {
+        1.0
+    }
+}
+
+impl Unit for Pt {
+    const SUFFIX: &'static str = "pt";
+    fn per_inch(_: Dpi) -> f64 {
+        72.0
+    }
+}
+
+fn ratio<From: Unit, To: Unit>(dpi: Dpi) -> f64 {
+    To::per_inch(dpi) / From::per_inch(dpi)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Length<U: Unit> {
+    pub value: f64,
+    unit: PhantomData<U>,
+}
+
+impl<U: Unit> Length<U> {
+    pub fn new(value: f64) -> Length<U> {
+        Length {
+            value,
+            unit: PhantomData,
+        }
+    }
+
+    pub fn convert<V: Unit>(self, dpi: Dpi) -> Length<V> {
+        Length::new(self.value * ratio::<U, V>(dpi))
+    }
+}
+
+// 只有相同單位可以相加減
+impl<U: Unit> Add for Length<U> {
+    type Output = Length<U>;
+    fn add(self, other: Length<U>) -> Length<U> {
+        Length::new(self.value + other.value)
+    }
+}
+
+impl<U: Unit> Sub for Length<U> {
+    type Output = Length<U>;
+    fn sub(self, other: Length<U>) -> Length<U> {
+        Length::new(self.value - other.value)
+    }
+}
+
+impl<U: Unit> Mul<f64> for Length<U> {
+    type Output = Length<U>;
+    fn mul(self, factor: f64) -> Length<U> {
+        Length::new(self.value * factor)
+    }
+}
+
+impl<U: Unit> fmt::Display for Length<U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.value, U::SUFFIX)
+    }
+}
+
+impl<U: Unit> Rectangle<U> {
+    pub fn width_length(&self) -> Length<U> {
+        Length::new(self.width as f64)
+    }
+
+    pub fn height_length(&self) -> Length<U> {
+        Length::new(self.height as f64)
+    }
+
+    // 換算到另一個單位並四捨五入，超過 u32 時回傳 None
+    pub fn convert<V: Unit>(&self, dpi: Dpi) -> Option<Rectangle<V>> {
+        let to_u32 = |length: Length<U>| {
+            let value = length.convert::<V>(dpi).value.round();
+            if (0.0..=u32::MAX as f64).contains(&value) {
+                Some(value as u32)
+            } else {
+                None
+            }
+        };
+        Some(Rectangle::of(
+            to_u32(self.width_length())?,
+            to_u32(self.height_length())?,
+        ))
+    }
+}
+
+pub fn main() {
+    println!("✅ Length");
+    let dpi = Dpi::default();
+    let margin = Length::<Mm>::new(10.0) + Length::<Mm>::new(5.0);
+    println!("margin: {}", margin);
+    println!("margin in cm: {}", margin.convert::<Cm>(dpi));
+    println!("margin in px @96dpi: {}", margin.convert::<Px>(dpi));
+    println!("1in in pt: {}", Length::<In>::new(1.0).convert::<Pt>(dpi));
+    // let wrong = Length::<Mm>::new(1.0) + Length::<Px>::new(1.0); // ❌ 編譯錯誤：單位不同
+
+    println!("✅ Rectangle<Unit>");
+    let a4 = Rectangle::<Mm>::of(210, 297);
+    let card = Rectangle::<Mm>::of(85, 54);
+    println!("a4: {:?}, area: {} mm²", a4, a4.area());
+    println!("a4 can_hold card: {}", a4.can_hold(&card));
+
+    let screen = Rectangle::new(1920, 1080); // 預設是 Px
+    println!("screen: {:?}", screen);
+    // a4.can_hold(&screen); // ❌ 編譯錯誤：Rectangle<Mm> 和 Rectangle<Px> 是不同型別
+
+    for dpi in [Dpi::default(), Dpi::new(300.0).unwrap()] {
+        let a4_px = a4.convert::<Px>(dpi).unwrap();
+        println!(
+            "a4 @{}dpi: {:?}, screen can_hold: {}",
+            dpi.value(),
+            a4_px,
+            screen.can_hold(&a4_px)
+        );
+    }
+
+    let mut poster = Rectangle::<In>::of(24, 36);
+    poster.scale(2);
+    println!("poster x2: {:?}", poster);
+    println!("poster in cm: {:?}", poster.convert::<Cm>(dpi));
+    println!(
+        "Dpi::new(0.0): {:?}, Dpi::new(-96.0): {:?}, Dpi::new(NaN): {:?}",
+        Dpi::new(0.0),
+        Dpi::new(-96.0),
+        Dpi::new(f64::NAN)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9 * b.abs().max(1.0)
+    }
+
+    #[test]
+    fn dpi_must_be_positive_and_finite() {
+        for value in [0.0, -0.0, -96.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(Dpi::new(value), None, "{}", value);
+        }
+        assert_eq!(Dpi::new(300.0).map(Dpi::value), Some(300.0));
+        assert_eq!(Dpi::default().value(), 96.0);
+        assert!(Dpi::new(f64::MIN_POSITIVE).is_some());
+    }
+
+    #[test]
+    fn px_pt_mm_conversions() {
+        let dpi = Dpi::default();
+        // 96 px = 1 in = 72 pt = 25.4 mm
+        assert!(approx(
+            Length::<Px>::new(96.0).convert::<Pt>(dpi).value,
+            72.0
+        ));
+        assert!(approx(
+            Length::<Pt>::new(72.0).convert::<Px>(dpi).value,
+            96.0
+        ));
+        assert!(approx(
+            Length::<Px>::new(96.0).convert::<Mm>(dpi).value,
+            25.4
+        ));
+        assert!(approx(
+            Length::<Mm>::new(25.4).convert::<Px>(dpi).value,
+            96.0
+        ));
+        assert!(approx(
+            Length::<Pt>::new(72.0).convert::<Mm>(dpi).value,
+            25.4
+        ));
+        assert!(approx(
+            Length::<Mm>::new(10.0).convert::<Pt>(dpi).value,
+            720.0 / 25.4
+        ));
+        assert!(approx(
+            Length::<Mm>::new(15.0).convert::<Cm>(dpi).value,
+            1.5
+        ));
+        assert!(approx(
+            Length::<In>::new(2.0).convert::<Px>(dpi).value,
+            192.0
+        ));
+
+        // 只有 px 會受 dpi 影響
+        let print = Dpi::new(300.0).unwrap();
+        assert!(approx(
+            Length::<In>::new(1.0).convert::<Px>(print).value,
+            300.0
+        ));
+        assert!(approx(
+            Length::<Pt>::new(72.0).convert::<Px>(print).value,
+            300.0
+        ));
+        assert!(approx(
+            Length::<Mm>::new(1.0).convert::<Pt>(print).value,
+            72.0 / 25.4
+        ));
+    }
+
+    #[test]
+    fn conversions_round_trip() {
+        for value in [0.0, 1.0, 12.5, 1e6] {
+            for dpi in [
+                Dpi::default(),
+                Dpi::new(72.0).unwrap(),
+                Dpi::new(0.5).unwrap(),
+            ] {
+                let px = Length::<Px>::new(value);
+                let back = px.convert::<Mm>(dpi).convert::<Pt>(dpi).convert::<Px>(dpi);
+                assert!(approx(back.value, value), "{} @{:?}", value, dpi);
+                assert!(px.convert::<Pt>(dpi).value.is_finite());
+            }
+        }
+    }
+
+    #[test]
+    fn rectangle_convert() {
+        let a4 = Rectangle::<Mm>::of(210, 297);
+        assert_eq!(
+            a4.convert::<Px>(Dpi::default()),
+            Some(Rectangle::of(794, 1123))
+        );
+        assert_eq!(
+            a4.convert::<Px>(Dpi::new(300.0).unwrap()),
+            Some(Rectangle::of(2480, 3508))
+        );
+        // 超過 u32 時回傳 None
+        let huge = Rectangle::<In>::of(u32::MAX, 1);
+        assert_eq!(huge.convert::<Px>(Dpi::default()), None);
+        assert_eq!(
+            Rectangle::<Pt>::of(72, 144).convert::<In>(Dpi::default()),
+            Some(Rectangle::of(1, 2))
+        );
+    }
+}
@@ -1,22 +1,24 @@
-#[derive(Debug)]
-enum Status {
+use crate::chapter6_10::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
     Pending,
     Success,
     Error,
 }
 
-#[derive(Debug)]
-enum IpAddr {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IpAddr {
     V4(u8, u8, u8, u8), // 攜帶四個 u8
-    V6(String),         // 攜帶一個 String
+    V6([u16; 8]),       // 攜帶八組 16 位元，文字格式見 chapter6_3
 }
 
-#[derive(Debug)]
-enum Message {
-    Quit,                       // 無資料
-    Move { x: i32, y: i32 },    // 具名欄位（像 struct）
-    Write(String),              // 單一字串
-    ChangeColor(i32, i32, i32), // 三個整數
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    Quit,                    // 無資料
+    Move { x: i32, y: i32 }, // 具名欄位（像 struct）
+    Write(String),           // 單一字串
+    ChangeColor(Color),      // 顏色，見 chapter6_10
 }
 
 impl Message {
@@ -25,9 +27,7 @@ impl Message {
             Message::Quit => println!("退出訊息"),
             Message::Move { x, y } => println!("移動到 ({}, {})", x, y),
             Message::Write(text) => println!("寫入: {}", text),
-            Message::ChangeColor(r, g, b) => {
-                println!("變更顏色: RGB({}, {}, {})", r, g, b)
-            }
+            Message::ChangeColor(color) => println!("變更顏色: {}", color),
         }
     }
 }
@@ -39,21 +39,10 @@ pub fn main() {
     println!("status: {:?}", status);
 
     let ip1 = IpAddr::V4(127, 0, 0, 1);
-    let ip2 = IpAddr::V6(String::from("::1"));
-    println!(
-        "ip1: {:?}",
-        match ip1 {
-            IpAddr::V4(a, b, c, d) => format!("{}.{}.{}.{}", a, b, c, d),
-            IpAddr::V6(ip) => ip.to_string(),
-        }
-    );
-    println!(
-        "ip2: {:?}",
-        match ip2 {
-            IpAddr::V4(a, b, c, d) => format!("{}.{}.{}.{}", a, b, c, d),
-            IpAddr::V6(ip) => ip.to_string(),
-        }
-    );
+    let ip2 = IpAddr::V6([0, 0, 0, 0, 0, 0, 0, 1]);
+    // Display 實作在 chapter6_3
+    println!("ip1: {}", ip1);
+    println!("ip2: {}", ip2);
 
     // enum function(enum 可以有方法)
     print!("✅enum function(enum 可以有方法) \n");
@@ -61,7 +50,7 @@ pub fn main() {
     message.call();
     let message = Message::Move { x: 10, y: 20 };
     message.call();
-    let message = Message::ChangeColor(255, 255, 255);
+    let message = Message::ChangeColor(Color::rgb(255, 255, 255));
     message.call();
     let message = Message::Quit;
     message.call();
@@ -199,22 +188,76 @@ pub fn main() {
 
 // Match
 // Copy 和 Clone 是為了讓 UsState 可以被複製
-// #[derive(Debug, Copy, Clone)]
-#[derive(Debug)]
-enum UsState {
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum UsState {
     Alabama,
     Alaska,
+    Arizona,
+    Arkansas,
+    California,
+    Colorado,
+    Connecticut,
+    Delaware,
+    Florida,
+    Georgia,
+    Hawaii,
+    Idaho,
+    Illinois,
+    Indiana,
+    Iowa,
+    Kansas,
+    Kentucky,
+    Louisiana,
+    Maine,
+    Maryland,
+    Massachusetts,
+    Michigan,
+    Minnesota,
+    Mississippi,
+    Missouri,
+    Montana,
+    Nebraska,
+    Nevada,
+    NewHampshire,
+    NewJersey,
+    NewMexico,
+    NewYork,
+    NorthCarolina,
+    NorthDakota,
+    Ohio,
+    Oklahoma,
+    Oregon,
+    Pennsylvania,
+    RhodeIsland,
+    SouthCarolina,
+    SouthDakota,
+    Tennessee,
+    Texas,
+    Utah,
+    Vermont,
+    Virginia,
+    Washington,
+    WestVirginia,
+    Wisconsin,
+    Wyoming,
+    // 特區與屬地（2009 年發行的 Quarter），名稱、縮寫等見 chapter6_12
+    DistrictOfColumbia,
+    PuertoRico,
+    Guam,
+    AmericanSamoa,
+    UsVirginIslands,
+    NorthernMarianaIslands,
 }
 
 #[derive(Debug)]
-enum Coin {
+pub enum Coin {
     Penny,
     Nickel,
     Dime,
     Quarter(UsState), // Quarter 攜帶州的資訊
 }
 
-fn value_in_cents(coin: Coin) -> u8 {
+pub fn value_in_cents(coin: Coin) -> u8 {
     match coin {
         Coin::Penny => 1,
         Coin::Nickel => 5,
@@ -0,0 +1,529 @@
+// Color（取代 ChangeColor 的三個 i32）
+use std::fmt;
+use std::str::FromStr;
+
+// 每個通道都是 u8，所以不可能出現超出範圍的顏色；a = 255 代表不透明
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorError {
+    OutOfRange { channel: char, value: i32 }, // 整數不在 0-255
+    InvalidHex(String),
+    UnknownName(String),
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColorError::OutOfRange { channel, value } => {
+                write!(f, "{} 的值 {} 不在 0-255", channel, value)
+            }
+            ColorError::InvalidHex(s) => write!(f, "無效的十六進位顏色 {:?}", s),
+            ColorError::UnknownName(s) => write!(f, "未知的顏色名稱 {:?}", s),
+        }
+    }
+}
+
+impl std::error::Error for ColorError {}
+
+// CSS 的 16 個基本顏色再加上幾個常用的
+const NAMED: [(&str, Color); 20] = [
+    ("black", Color::rgb(0, 0, 0)),
+    ("silver", Color::rgb(192, 192, 192)),
+    ("gray", Color::rgb(128, 128, 128)),
+    ("white", Color::rgb(255, 255, 255)),
+    ("maroon", Color::rgb(128, 0, 0)),
+    ("red", Color::rgb(255, 0, 0)),
+    ("purple", Color::rgb(128, 0, 128)),
+    ("fuchsia", Color::rgb(255, 0, 255)),
+    ("green", Color::rgb(0, 128, 0)),
+    ("lime", Color::rgb(0, 255, 0)),
+    ("olive", Color::rgb(128, 128, 0)),
+    ("yellow", Color::rgb(255, 255, 0)),
+    ("navy", Color::rgb(0, 0, 128)),
+    ("blue", Color::rgb(0, 0, 255)),
+    ("teal", Color::rgb(0, 128, 128)),
+    ("aqua", Color::rgb(0, 255, 255)),
+    ("orange", Color::rgb(255, 165, 0)),
+    ("pink", Color::rgb(255, 192, 203)),
+    ("brown", Color::rgb(165, 42, 42)),
+    ("transparent", Color::rgba(0, 0, 0, 0)),
+];
+
+// HSL：h 是 0-360 度，s、l 是 0.0-1.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub h: f64,
+    pub s: f64,
+    pub l: f64,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b, a: 255 }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Color {
+        Color { r, g, b, a }
+    }
+
+    // 給原本傳 i32 的呼叫者：超出範圍就回傳錯誤
+    pub fn try_from_ints(r: i32, g: i32, b: i32) -> Result<Color, ColorError> {
+        let channel = |channel: char, value: i32| {
+            u8::try_from(value).map_err(|_| ColorError::OutOfRange { channel, value })
+        };
+        Ok(Color::rgb(
+            channel('r', r)?,
+            channel('g', g)?,
+            channel('b', b)?,
+        ))
+    }
+
+    // 給原本傳 i32 的呼叫者：超出範圍就截斷到 0-255
+    pub fn clamped(r: i32, g: i32, b: i32) -> Color {
+        let [r, g, b] = [r, g, b].map(|c| c.clamp(0, 255) as u8);
+        Color::rgb(r, g, b)
+    }
+
+    pub fn named(name: &str) -> Option<Color> {
+        NAMED
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, c)| *c)
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        NAMED.iter().find(|(_, c)| c == self).map(|(n, _)| *n)
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        self.a == 255
+    }
+
+    pub fn with_alpha(self, a: u8) -> Color {
+        Color { a, ..self }
+    }
+
+    pub fn to_hsl(self) -> Hsl {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| c as f64 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+        let d = max - min;
+        if d == 0.0 {
+            return Hsl { h: 0.0, s: 0.0, l };
+        }
+        let s = d / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == r {
+            ((g - b) / d).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / d + 2.0
+        } else {
+            (r - g) / d + 4.0
+        };
+        Hsl { h: h * 60.0, s, l }
+    }
+
+    // h 會換算到 0-360，s、l 會截斷到 0.0-1.0
+    pub fn from_hsl(hsl: Hsl) -> Color {
+        let h = hsl.h.rem_euclid(360.0) / 60.0;
+        let s = hsl.s.clamp(0.0, 1.0);
+        let l = hsl.l.clamp(0.0, 1.0);
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = l - c / 2.0;
+        let [r, g, b] = [r, g, b].map(|v| ((v + m) * 255.0).round() as u8);
+        Color::rgb(r, g, b)
+    }
+
+    // 線性內插：t = 0 是 self，t = 1 是 other
+    pub fn mix(self, other: Color, t: f64) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Color::rgba(
+            lerp(self.r, other.r),
+            lerp(self.g, other.g),
+            lerp(self.b, other.b),
+            lerp(self.a, other.a),
+        )
+    }
+
+    // alpha 合成（source-over）：把 self 疊在 background 上
+    pub fn over(self, background: Color) -> Color {
+        let sa = self.a as f64 / 255.0;
+        let ba = background.a as f64 / 255.0;
+        let a = sa + ba * (1.0 - sa);
+        if a == 0.0 {
+            return Color::rgba(0, 0, 0, 0);
+        }
+        let channel =
+            |s: u8, b: u8| ((s as f64 * sa + b as f64 * ba * (1.0 - sa)) / a).round() as u8;
+        Color::rgba(
+            channel(self.r, background.r),
+            channel(self.g, background.g),
+            channel(self.b, background.b),
+            (a * 255.0).round() as u8,
+        )
+    }
+
+    // WCAG 2 的相對亮度，忽略 alpha
+    pub fn relative_luminance(&self) -> f64 {
+        let linear = |c: u8| {
+            let c = c as f64 / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    // 1.0（相同）到 21.0（黑白）；一般文字建議至少 4.5
+    pub fn contrast_ratio(&self, other: &Color) -> f64 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+}
+
+impl Default for Color {
+    fn default() -> Color {
+        Color::BLACK
+    }
+}
+
+// 不透明時 #rrggbb，否則 #rrggbbaa
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+        if !self.is_opaque() {
+            write!(f, "{:02x}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+// 接受 #rgb、#rgba、#rrggbb、#rrggbbaa 與顏色名稱
+impl FromStr for Color {
+    type Err = ColorError;
+
+    fn from_str(s: &str) -> Result<Color, ColorError> {
+        let s = s.trim();
+        let Some(hex) = s.strip_prefix('#') else {
+            return Color::named(s).ok_or_else(|| ColorError::UnknownName(s.to_string()));
+        };
+        let invalid = || ColorError::InvalidHex(s.to_string());
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).unwrap();
+        let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+        match hex.len() {
+            // 短格式每一位重複一次：#f80 = #ff8800
+            3 | 4 => {
+                let mut channels = [255u8; 4];
+                for (i, c) in channels.iter_mut().take(hex.len()).enumerate() {
+                    *c = digit(i) * 17;
+                }
+                Ok(Color::rgba(
+                    channels[0],
+                    channels[1],
+                    channels[2],
+                    channels[3],
+                ))
+            }
+            6 => Ok(Color::rgb(pair(0), pair(2), pair(4))),
+            8 => Ok(Color::rgba(pair(0), pair(2), pair(4), pair(6))),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+pub fn main() {
+    println!("✅ Parse and Display");
+    for input in [
+        "#ff8800",
+        "#F80",
+        "#ff880080",
+        "#f808",
+        "Orange",
+        "transparent",
+        "#12345",
+        "#gg0000",
+        "teal-ish",
+    ] {
+        match input.parse::<Color>() {
+            Ok(color) => println!("{:<12} -> {} {:?}", input, color, color.name()),
+            Err(err) => println!("{:<12} -> {}", input, err),
+        }
+    }
+
+    println!("✅ 原本的整數呼叫者");
+    println!("{:?}", Color::try_from_ints(255, 128, 0));
+    println!("{:?}", Color::try_from_ints(999, -1, 0));
+    println!("{}", Color::clamped(999, -1, 0));
+
+    println!("✅ HSL");
+    for name in ["red", "lime", "navy", "orange", "gray", "pink"] {
+        let color = Color::named(name).unwrap();
+        let hsl = color.to_hsl();
+        println!(
+            "{:<7} {} -> hsl({:.1}, {:.3}, {:.3}) -> {}",
+            name,
+            color,
+            hsl.h,
+            hsl.s,
+            hsl.l,
+            Color::from_hsl(hsl)
+        );
+    }
+
+    println!("✅ Blending");
+    let red = Color::named("red").unwrap();
+    let blue = Color::named("blue").unwrap();
+    println!("mix(red, blue, 0.5) = {}", red.mix(blue, 0.5));
+    println!(
+        "red 50% over white = {}",
+        red.with_alpha(128).over(Color::WHITE)
+    );
+    println!(
+        "red 50% over transparent = {}",
+        red.with_alpha(128)
+            .over(Color::named("transparent").unwrap())
+    );
+
+    println!("✅ Contrast ratio");
+    for (fg, bg) in [
+        ("black", "white"),
+        ("gray", "white"),
+        ("orange", "white"),
+        ("navy", "yellow"),
+    ] {
+        let ratio = Color::named(fg)
+            .unwrap()
+            .contrast_ratio(&Color::named(bg).unwrap());
+        println!(
+            "{} on {}: {:.2} (AA: {})",
+            fg,
+            bg,
+            ratio,
+            if ratio >= 4.5 { "pass" } else { "fail" }
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn hsl_round_trip() {
+        // 來回轉換都要得到同一個顏色（b 每隔 3 取一個，測試才不會太慢）
+        for r in 0..=255 {
+            for g in 0..=255 {
+                for b in (0..=255).step_by(3) {
+                    let color = Color::rgb(r, g, b);
+                    assert_eq!(Color::from_hsl(color.to_hsl()), color);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hsl_values() {
+        let hsl = Color::rgb(255, 0, 0).to_hsl();
+        assert_eq!(
+            hsl,
+            Hsl {
+                h: 0.0,
+                s: 1.0,
+                l: 0.5
+            }
+        );
+        let hsl = Color::rgb(0, 0, 128).to_hsl();
+        assert!(approx(hsl.h, 240.0) && approx(hsl.s, 1.0), "{:?}", hsl);
+        let hsl = Color::rgb(128, 128, 128).to_hsl();
+        assert_eq!((hsl.h, hsl.s), (0.0, 0.0));
+        // h 換算到 0-360，s、l 截斷
+        let red = Hsl {
+            h: 0.0,
+            s: 1.0,
+            l: 0.5,
+        };
+        assert_eq!(
+            Color::from_hsl(Hsl { h: 720.0, ..red }),
+            Color::rgb(255, 0, 0)
+        );
+        assert_eq!(
+            Color::from_hsl(Hsl { h: -240.0, ..red }),
+            Color::rgb(0, 255, 0)
+        );
+        assert_eq!(
+            Color::from_hsl(Hsl {
+                s: 7.0,
+                l: 2.0,
+                ..red
+            }),
+            Color::WHITE
+        );
+        assert_eq!(
+            Color::from_hsl(Hsl {
+                s: -1.0,
+                l: -1.0,
+                ..red
+            }),
+            Color::BLACK
+        );
+    }
+
+    #[test]
+    fn parse_hex() {
+        let cases = [
+            ("#ff8800", Color::rgb(255, 136, 0)),
+            ("#FF8800", Color::rgb(255, 136, 0)),
+            ("#f80", Color::rgb(255, 136, 0)),
+            ("#f808", Color::rgba(255, 136, 0, 136)),
+            ("#ff880080", Color::rgba(255, 136, 0, 128)),
+            ("  #000000  ", Color::BLACK),
+        ];
+        for (input, color) in cases {
+            assert_eq!(input.parse::<Color>(), Ok(color), "{:?}", input);
+        }
+        for bad in [
+            "#", "#12", "#12345", "#1234567", "#gg0000", "#+12", "#ff 000",
+        ] {
+            assert_eq!(
+                bad.parse::<Color>(),
+                Err(ColorError::InvalidHex(bad.trim().to_string())),
+                "{:?}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn display_round_trip() {
+        assert_eq!(Color::rgb(255, 136, 0).to_string(), "#ff8800");
+        assert_eq!(Color::rgba(1, 2, 3, 4).to_string(), "#01020304");
+        assert_eq!(Color::rgba(1, 2, 3, 255).to_string(), "#010203");
+        for value in [
+            0u32,
+            1,
+            0x7f,
+            0x80,
+            0xfe,
+            0xff,
+            0x1234_5678,
+            0xdead_beef,
+            u32::MAX,
+        ] {
+            let [r, g, b, a] = value.to_be_bytes();
+            let color = Color::rgba(r, g, b, a);
+            assert_eq!(color.to_string().parse::<Color>(), Ok(color));
+        }
+    }
+
+    #[test]
+    fn named_colors() {
+        assert_eq!(Color::named("Orange"), Some(Color::rgb(255, 165, 0)));
+        assert_eq!("NAVY".parse::<Color>(), Ok(Color::rgb(0, 0, 128)));
+        assert_eq!(
+            "teal-ish".parse::<Color>(),
+            Err(ColorError::UnknownName(String::from("teal-ish")))
+        );
+        assert_eq!(Color::rgb(0, 128, 128).name(), Some("teal"));
+        assert_eq!(Color::rgb(0, 128, 129).name(), None);
+        assert_eq!(Color::rgba(0, 0, 0, 0).name(), Some("transparent"));
+        // 每個名稱都對應到自己
+        for (name, color) in NAMED {
+            assert_eq!(Color::named(name), Some(color));
+            assert_eq!(color.name(), Some(name));
+        }
+    }
+
+    #[test]
+    fn from_ints() {
+        assert_eq!(
+            Color::try_from_ints(255, 128, 0),
+            Ok(Color::rgb(255, 128, 0))
+        );
+        assert_eq!(
+            Color::try_from_ints(0, 256, -1),
+            Err(ColorError::OutOfRange {
+                channel: 'g',
+                value: 256
+            })
+        );
+        assert_eq!(Color::clamped(999, -1, 42), Color::rgb(255, 0, 42));
+    }
+
+    #[test]
+    fn over() {
+        let red = Color::rgb(255, 0, 0);
+        let transparent = Color::rgba(0, 0, 0, 0);
+        // 不透明的前景完全蓋住背景，透明的前景不影響背景
+        assert_eq!(red.over(Color::WHITE), red);
+        assert_eq!(transparent.over(red), red);
+        assert_eq!(transparent.over(transparent), transparent);
+        assert_eq!(
+            red.with_alpha(128).over(Color::WHITE),
+            Color::rgb(255, 127, 127)
+        );
+        // 疊在透明背景上，顏色不變、只保留前景的 alpha
+        assert_eq!(red.with_alpha(128).over(transparent), red.with_alpha(128));
+        // 兩個半透明疊起來：a = 0.5 + 0.5 * 0.5
+        let blended = red.with_alpha(128).over(Color::rgba(0, 0, 255, 128));
+        assert_eq!(blended.a, 192);
+        assert!(blended.r > blended.b);
+    }
+
+    #[test]
+    fn mix() {
+        let red = Color::rgb(255, 0, 0);
+        let blue = Color::rgb(0, 0, 255);
+        assert_eq!(red.mix(blue, 0.0), red);
+        assert_eq!(red.mix(blue, 1.0), blue);
+        assert_eq!(red.mix(blue, 0.5), Color::rgb(128, 0, 128));
+        assert_eq!(red.mix(blue, -3.0), red);
+        assert_eq!(red.mix(blue, 3.0), blue);
+    }
+
+    #[test]
+    fn contrast_ratio() {
+        assert!(approx(Color::BLACK.contrast_ratio(&Color::WHITE), 21.0));
+        assert!(approx(Color::WHITE.contrast_ratio(&Color::BLACK), 21.0));
+        let gray = Color::rgb(128, 128, 128);
+        assert!(approx(gray.contrast_ratio(&gray), 1.0));
+        // gray 在白色上大約 3.95，不到 AA 的 4.5
+        let ratio = gray.contrast_ratio(&Color::WHITE);
+        assert!((3.9..4.0).contains(&ratio), "{}", ratio);
+        assert!(approx(Color::BLACK.relative_luminance(), 0.0));
+        assert!(approx(Color::WHITE.relative_luminance(), 1.0));
+        // alpha 不影響亮度
+        assert_eq!(
+            gray.relative_luminance(),
+            gray.with_alpha(0).relative_luminance()
+        );
+    }
+}
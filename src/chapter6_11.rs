@@ -0,0 +1,522 @@
+// Purse（零錢包與找零）
+use crate::chapter6::{Coin, UsState};
+use std::collections::VecDeque;
+use std::fmt;
+
+// 只看面額，不管 Quarter 是哪一州
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Denomination {
+    Penny,
+    Nickel,
+    Dime,
+    Quarter,
+}
+
+impl Denomination {
+    pub const ALL: [Denomination; 4] = [
+        Denomination::Penny,
+        Denomination::Nickel,
+        Denomination::Dime,
+        Denomination::Quarter,
+    ];
+
+    // 和 chapter6::value_in_cents 相同，但不會印出州名
+    pub fn cents(self) -> u64 {
+        match self {
+            Denomination::Penny => 1,
+            Denomination::Nickel => 5,
+            Denomination::Dime => 10,
+            Denomination::Quarter => 25,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl Coin {
+    pub fn denomination(&self) -> Denomination {
+        match self {
+            Coin::Penny => Denomination::Penny,
+            Coin::Nickel => Denomination::Nickel,
+            Coin::Dime => Denomination::Dime,
+            Coin::Quarter(_) => Denomination::Quarter,
+        }
+    }
+}
+
+// 找零的 DP 需要 O(amount) 的記憶體，超過這個金額直接回傳錯誤
+pub const MAX_CHANGE_AMOUNT: u64 = 100_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeError {
+    InsufficientFunds { amount: u64, total: u64 },
+    NoExactChange(u64),  // 總額夠，但現有的硬幣湊不出這個金額
+    AmountTooLarge(u64), // 超過 MAX_CHANGE_AMOUNT
+}
+
+impl fmt::Display for ChangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChangeError::InsufficientFunds { amount, total } => {
+                write!(f, "需要 {}¢，但只有 {}¢", amount, total)
+            }
+            ChangeError::NoExactChange(amount) => write!(f, "無法剛好湊出 {}¢", amount),
+            ChangeError::AmountTooLarge(amount) => {
+                write!(f, "{}¢ 超過找零上限 {}¢", amount, MAX_CHANGE_AMOUNT)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChangeError {}
+
+// 每種面額的數量用 u32，總額用 u64：4 × 25 × u32::MAX 遠小於 u64::MAX，不會溢位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Purse {
+    counts: [u32; 4],
+}
+
+impl Purse {
+    pub fn new() -> Purse {
+        Purse::default()
+    }
+
+    pub fn with(mut self, denomination: Denomination, count: u32) -> Purse {
+        self.counts[denomination.index()] = count;
+        self
+    }
+
+    pub fn count(&self, denomination: Denomination) -> u32 {
+        self.counts[denomination.index()]
+    }
+
+    // 數量達到 u32::MAX 時回傳 false，不會加入
+    pub fn add(&mut self, coin: &Coin) -> bool {
+        self.add_many(coin.denomination(), 1)
+    }
+
+    pub fn add_many(&mut self, denomination: Denomination, count: u32) -> bool {
+        match self.counts[denomination.index()].checked_add(count) {
+            Some(total) => {
+                self.counts[denomination.index()] = total;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn add_purse(&mut self, other: &Purse) -> bool {
+        let mut result = *self;
+        for d in Denomination::ALL {
+            if !result.add_many(d, other.count(d)) {
+                return false;
+            }
+        }
+        *self = result;
+        true
+    }
+
+    pub fn coin_count(&self) -> u64 {
+        self.counts.iter().map(|&c| c as u64).sum()
+    }
+
+    pub fn total(&self) -> u64 {
+        Denomination::ALL
+            .iter()
+            .map(|d| d.cents() * self.count(*d) as u64)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.coin_count() == 0
+    }
+
+    // 用最少枚硬幣剛好湊出 amount，每種硬幣不能超過現有數量
+    // 有限數量時貪婪法不一定對（例如 30¢ 只有 1 個 Quarter 和 3 個 Dime），所以用 DP
+    pub fn make_change(&self, amount: u64) -> Result<Purse, ChangeError> {
+        let total = self.total();
+        if amount > total {
+            return Err(ChangeError::InsufficientFunds { amount, total });
+        }
+        if amount > MAX_CHANGE_AMOUNT {
+            return Err(ChangeError::AmountTooLarge(amount));
+        }
+        let n = amount as usize;
+        // best[a]：湊出 a 最少需要幾枚；used[i][a]：第 i 種面額用了幾枚
+        let mut best: Vec<Option<u64>> = vec![None; n + 1];
+        best[0] = Some(0);
+        let mut used = vec![vec![0u32; n + 1]; 4];
+        for d in Denomination::ALL {
+            let value = d.cents() as usize;
+            let limit = self.count(d) as usize;
+            let previous = best.clone();
+            // 同餘的金額 r, r + value, r + 2·value... 形成一條序列，
+            // 在長度 limit + 1 的視窗內找 previous[r + j'·value] - j' 的最小值（單調佇列）
+            for r in 0..value.min(n + 1) {
+                let mut window: VecDeque<(usize, u64)> = VecDeque::new();
+                let mut j = 0;
+                while r + j * value <= n {
+                    let a = r + j * value;
+                    if let Some(p) = previous[a] {
+                        let key = p + (n - j) as u64; // 加上 n - j 讓值保持非負
+                        while window.back().is_some_and(|&(_, k)| k >= key) {
+                            window.pop_back();
+                        }
+                        window.push_back((j, key));
+                    }
+                    while window.front().is_some_and(|&(start, _)| j - start > limit) {
+                        window.pop_front();
+                    }
+                    best[a] = window.front().map(|&(start, key)| {
+                        used[d.index()][a] = (j - start) as u32;
+                        key - (n - j) as u64
+                    });
+                    j += 1;
+                }
+            }
+        }
+        if best[n].is_none() {
+            return Err(ChangeError::NoExactChange(amount));
+        }
+        // 從最後一種面額往回推出每種用了幾枚
+        let mut change = Purse::new();
+        let mut rest = n;
+        for d in Denomination::ALL.iter().rev() {
+            let k = used[d.index()][rest];
+            change.counts[d.index()] = k;
+            rest -= k as usize * d.cents() as usize;
+        }
+        Ok(change)
+    }
+
+    // 付出 amount：成功時從錢包扣掉並回傳付出的硬幣
+    pub fn pay(&mut self, amount: u64) -> Result<Purse, ChangeError> {
+        let change = self.make_change(amount)?;
+        for d in Denomination::ALL {
+            self.counts[d.index()] -= change.count(d);
+        }
+        Ok(change)
+    }
+
+    // 在數量限制內剛好湊出 amount 的組合數（只看每種面額的數量，不看順序）
+    // 固定前三種的數量後最後一種就確定了，所以組合數最多 (u32::MAX + 1)^3，u128 放得下
+    // 總額不夠時一定是 0 種；否則 amount 不能超過 MAX_CHANGE_AMOUNT
+    pub fn count_ways(&self, amount: u64) -> Result<u128, ChangeError> {
+        if amount > self.total() {
+            return Ok(0);
+        }
+        if amount > MAX_CHANGE_AMOUNT {
+            return Err(ChangeError::AmountTooLarge(amount));
+        }
+        let n = amount as usize;
+        let mut ways = vec![0u128; n + 1];
+        ways[0] = 1;
+        for d in Denomination::ALL {
+            let value = d.cents() as usize;
+            let limit = self.count(d) as usize;
+            let previous = ways.clone();
+            // ways[a] = previous[a] + previous[a - value] + ... 最多 limit + 1 項，用滑動視窗加總
+            for r in 0..value.min(n + 1) {
+                let mut sum = 0u128;
+                let mut j = 0;
+                while r + j * value <= n {
+                    sum += previous[r + j * value];
+                    if j > limit {
+                        sum -= previous[r + (j - limit - 1) * value];
+                    }
+                    ways[r + j * value] = sum;
+                    j += 1;
+                }
+            }
+        }
+        Ok(ways[n])
+    }
+}
+
+impl fmt::Display for Purse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts: Vec<String> = Denomination::ALL
+            .iter()
+            .filter(|d| self.count(**d) > 0)
+            .map(|d| format!("{:?} × {}", d, self.count(*d)))
+            .collect();
+        if parts.is_empty() {
+            write!(f, "(空)")
+        } else {
+            write!(f, "{} = {}¢", parts.join(", "), self.total())
+        }
+    }
+}
+
+pub fn main() {
+    println!("✅ Purse");
+    let mut purse = Purse::new();
+    for coin in [
+        Coin::Quarter(UsState::Alabama),
+        Coin::Quarter(UsState::Alaska),
+        Coin::Dime,
+        Coin::Dime,
+        Coin::Dime,
+        Coin::Nickel,
+        Coin::Penny,
+        Coin::Penny,
+    ] {
+        purse.add(&coin);
+    }
+    println!("{}", purse);
+    let huge = Purse::new()
+        .with(Denomination::Quarter, u32::MAX)
+        .with(Denomination::Dime, u32::MAX)
+        .with(Denomination::Nickel, u32::MAX)
+        .with(Denomination::Penny, u32::MAX);
+    println!("huge total: {}¢（不會溢位）", huge.total());
+    let mut full = huge;
+    println!("add to full: {}", full.add(&Coin::Penny));
+
+    println!("✅ Tricky amounts");
+    let cases = [
+        // 貪婪法會先拿 Quarter，然後湊不出剩下的 5¢
+        (
+            Purse::new()
+                .with(Denomination::Quarter, 1)
+                .with(Denomination::Dime, 3),
+            30,
+        ),
+        // 貪婪法用 Quarter + 5 Penny = 6 枚，最佳是 4 Dime
+        (
+            Purse::new()
+                .with(Denomination::Quarter, 1)
+                .with(Denomination::Dime, 4)
+                .with(Denomination::Penny, 5),
+            40,
+        ),
+        (Purse::new().with(Denomination::Nickel, 1), 6),
+        // 總額 7¢ 夠，但沒辦法剛好湊出 3¢
+        (
+            Purse::new()
+                .with(Denomination::Nickel, 1)
+                .with(Denomination::Penny, 2),
+            3,
+        ),
+        (Purse::new().with(Denomination::Dime, 1), 0),
+        (purse, 200),
+        (purse, 41),
+        (purse, 97),
+    ];
+    for (purse, amount) in cases {
+        let ways = purse.count_ways(amount).unwrap();
+        match purse.make_change(amount) {
+            Ok(change) => println!("{}¢ from [{}]: {} ({} ways)", amount, purse, change, ways),
+            Err(err) => println!("{}¢ from [{}]: {} ({} ways)", amount, purse, err, ways),
+        }
+    }
+
+    println!("✅ Count ways");
+    // 數量足夠時就是經典的「1 美元有 242 種換法」
+    let plenty = Denomination::ALL
+        .iter()
+        .fold(Purse::new(), |p, d| p.with(*d, 100));
+    println!("100¢ unlimited: {} ways", plenty.count_ways(100).unwrap());
+    println!(
+        "100¢ without pennies: {} ways",
+        plenty.with(Denomination::Penny, 0).count_ways(100).unwrap()
+    );
+    println!(
+        "1000¢ unlimited-ish: {} ways",
+        plenty
+            .with(Denomination::Penny, 1000)
+            .count_ways(1000)
+            .unwrap()
+    );
+    // 金額太大時不配置記憶體，直接回傳錯誤
+    match huge.make_change(u32::MAX as u64) {
+        Ok(change) => println!("huge change: {}", change),
+        Err(err) => println!("huge change: {}", err),
+    }
+
+    println!("✅ Pay");
+    let mut wallet = purse;
+    for amount in [35, 35, 35] {
+        match wallet.pay(amount) {
+            Ok(paid) => println!("pay {}¢: {} -> left {}", amount, paid, wallet),
+            Err(err) => println!("pay {}¢: {} (left {})", amount, err, wallet),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 暴力列舉所有組合，用來驗證 DP 的結果
+    fn brute_force(purse: &Purse, amount: u64) -> (Option<u64>, u128) {
+        let mut best = None;
+        let mut ways = 0;
+        let [p, n, d, q] = Denomination::ALL.map(|d| purse.count(d) as u64);
+        for quarters in 0..=q {
+            for dimes in 0..=d {
+                for nickels in 0..=n {
+                    let value = quarters * 25 + dimes * 10 + nickels * 5;
+                    if value <= amount && amount - value <= p {
+                        let coins = quarters + dimes + nickels + (amount - value);
+                        best = Some(best.map_or(coins, |b: u64| b.min(coins)));
+                        ways += 1;
+                    }
+                }
+            }
+        }
+        (best, ways)
+    }
+
+    fn check(purse: &Purse, amount: u64) {
+        let (best, brute_ways) = brute_force(purse, amount);
+        let result = purse.make_change(amount);
+        assert_eq!(
+            result.as_ref().ok().map(|c| c.coin_count()),
+            best,
+            "{}¢ from [{}]",
+            amount,
+            purse
+        );
+        if let Ok(change) = result {
+            assert_eq!(change.total(), amount);
+            for d in Denomination::ALL {
+                assert!(change.count(d) <= purse.count(d));
+            }
+        }
+        assert_eq!(purse.count_ways(amount), Ok(brute_ways));
+    }
+
+    #[test]
+    fn tricky_amounts_match_brute_force() {
+        let purse = Purse::new()
+            .with(Denomination::Quarter, 2)
+            .with(Denomination::Dime, 3)
+            .with(Denomination::Nickel, 1)
+            .with(Denomination::Penny, 2);
+        let cases = [
+            // 貪婪法會先拿 Quarter，然後湊不出剩下的 5¢
+            (
+                Purse::new()
+                    .with(Denomination::Quarter, 1)
+                    .with(Denomination::Dime, 3),
+                30,
+            ),
+            // 貪婪法用 Quarter + 5 Penny = 6 枚，最佳是 4 Dime
+            (
+                Purse::new()
+                    .with(Denomination::Quarter, 1)
+                    .with(Denomination::Dime, 4)
+                    .with(Denomination::Penny, 5),
+                40,
+            ),
+            (Purse::new().with(Denomination::Nickel, 1), 6),
+            (
+                Purse::new()
+                    .with(Denomination::Nickel, 1)
+                    .with(Denomination::Penny, 2),
+                3,
+            ),
+            (Purse::new().with(Denomination::Dime, 1), 0),
+            (purse, 200),
+            (purse, 41),
+            (purse, 97),
+        ];
+        for (purse, amount) in cases {
+            check(&purse, amount);
+        }
+        let thirty = Purse::new()
+            .with(Denomination::Quarter, 1)
+            .with(Denomination::Dime, 3);
+        assert_eq!(
+            thirty.make_change(30),
+            Ok(Purse::new().with(Denomination::Dime, 3))
+        );
+        assert_eq!(
+            Purse::new()
+                .with(Denomination::Nickel, 1)
+                .with(Denomination::Penny, 2)
+                .make_change(3),
+            Err(ChangeError::NoExactChange(3))
+        );
+    }
+
+    #[test]
+    fn small_purses_match_brute_force() {
+        for counts in 0..4u32.pow(4) {
+            let purse = Denomination::ALL
+                .iter()
+                .enumerate()
+                .fold(Purse::new(), |p, (i, d)| {
+                    p.with(*d, counts / 4u32.pow(i as u32) % 4)
+                });
+            for amount in 0..=purse.total() + 1 {
+                check(&purse, amount);
+            }
+        }
+    }
+
+    #[test]
+    fn count_ways_classic() {
+        let plenty = Denomination::ALL
+            .iter()
+            .fold(Purse::new(), |p, d| p.with(*d, 100));
+        assert_eq!(plenty.count_ways(100), Ok(242));
+        assert_eq!(plenty.with(Denomination::Penny, 0).count_ways(100), Ok(29));
+    }
+
+    #[test]
+    fn large_amounts_are_rejected_without_allocating() {
+        let huge = Denomination::ALL
+            .iter()
+            .fold(Purse::new(), |p, d| p.with(*d, u32::MAX));
+        assert_eq!(huge.total(), 41 * u32::MAX as u64);
+        let amount = u32::MAX as u64;
+        assert_eq!(
+            huge.make_change(amount),
+            Err(ChangeError::AmountTooLarge(amount))
+        );
+        assert_eq!(
+            huge.count_ways(amount),
+            Err(ChangeError::AmountTooLarge(amount))
+        );
+        // 總額不夠時仍然回報 InsufficientFunds
+        let small = Purse::new().with(Denomination::Dime, 1);
+        assert_eq!(
+            small.make_change(u64::MAX),
+            Err(ChangeError::InsufficientFunds {
+                amount: u64::MAX,
+                total: 10
+            })
+        );
+        assert_eq!(small.count_ways(u64::MAX), Ok(0));
+
+        let change = huge.make_change(MAX_CHANGE_AMOUNT).unwrap();
+        assert_eq!(change, Purse::new().with(Denomination::Quarter, 4_000));
+        assert!(huge.count_ways(MAX_CHANGE_AMOUNT).unwrap() > 0);
+    }
+
+    #[test]
+    fn pay_removes_the_coins() {
+        let mut wallet = Purse::new()
+            .with(Denomination::Quarter, 1)
+            .with(Denomination::Dime, 2);
+        assert_eq!(
+            wallet.pay(35),
+            Ok(Purse::new()
+                .with(Denomination::Quarter, 1)
+                .with(Denomination::Dime, 1))
+        );
+        assert_eq!(wallet, Purse::new().with(Denomination::Dime, 1));
+        assert_eq!(
+            wallet.pay(35),
+            Err(ChangeError::InsufficientFunds {
+                amount: 35,
+                total: 10
+            })
+        );
+        assert_eq!(wallet, Purse::new().with(Denomination::Dime, 1));
+    }
+}
@@ -0,0 +1,556 @@
+// State Quarters（美國各州與屬地，以及 Quarter 收藏紀錄）
+use crate::chapter6::{Coin, UsState};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::str::FromStr;
+
+// (州, 縮寫, 名稱, 加入聯邦的年份)；特區與屬地沒有加入年份
+const STATES: [(UsState, &str, &str, Option<u16>); 56] = [
+    (UsState::Alabama, "AL", "Alabama", Some(1819)),
+    (UsState::Alaska, "AK", "Alaska", Some(1959)),
+    (UsState::Arizona, "AZ", "Arizona", Some(1912)),
+    (UsState::Arkansas, "AR", "Arkansas", Some(1836)),
+    (UsState::California, "CA", "California", Some(1850)),
+    (UsState::Colorado, "CO", "Colorado", Some(1876)),
+    (UsState::Connecticut, "CT", "Connecticut", Some(1788)),
+    (UsState::Delaware, "DE", "Delaware", Some(1787)),
+    (UsState::Florida, "FL", "Florida", Some(1845)),
+    (UsState::Georgia, "GA", "Georgia", Some(1788)),
+    (UsState::Hawaii, "HI", "Hawaii", Some(1959)),
+    (UsState::Idaho, "ID", "Idaho", Some(1890)),
+    (UsState::Illinois, "IL", "Illinois", Some(1818)),
+    (UsState::Indiana, "IN", "Indiana", Some(1816)),
+    (UsState::Iowa, "IA", "Iowa", Some(1846)),
+    (UsState::Kansas, "KS", "Kansas", Some(1861)),
+    (UsState::Kentucky, "KY", "Kentucky", Some(1792)),
+    (UsState::Louisiana, "LA", "Louisiana", Some(1812)),
+    (UsState::Maine, "ME", "Maine", Some(1820)),
+    (UsState::Maryland, "MD", "Maryland", Some(1788)),
+    (UsState::Massachusetts, "MA", "Massachusetts", Some(1788)),
+    (UsState::Michigan, "MI", "Michigan", Some(1837)),
+    (UsState::Minnesota, "MN", "Minnesota", Some(1858)),
+    (UsState::Mississippi, "MS", "Mississippi", Some(1817)),
+    (UsState::Missouri, "MO", "Missouri", Some(1821)),
+    (UsState::Montana, "MT", "Montana", Some(1889)),
+    (UsState::Nebraska, "NE", "Nebraska", Some(1867)),
+    (UsState::Nevada, "NV", "Nevada", Some(1864)),
+    (UsState::NewHampshire, "NH", "New Hampshire", Some(1788)),
+    (UsState::NewJersey, "NJ", "New Jersey", Some(1787)),
+    (UsState::NewMexico, "NM", "New Mexico", Some(1912)),
+    (UsState::NewYork, "NY", "New York", Some(1788)),
+    (UsState::NorthCarolina, "NC", "North Carolina", Some(1789)),
+    (UsState::NorthDakota, "ND", "North Dakota", Some(1889)),
+    (UsState::Ohio, "OH", "Ohio", Some(1803)),
+    (UsState::Oklahoma, "OK", "Oklahoma", Some(1907)),
+    (UsState::Oregon, "OR", "Oregon", Some(1859)),
+    (UsState::Pennsylvania, "PA", "Pennsylvania", Some(1787)),
+    (UsState::RhodeIsland, "RI", "Rhode Island", Some(1790)),
+    (UsState::SouthCarolina, "SC", "South Carolina", Some(1788)),
+    (UsState::SouthDakota, "SD", "South Dakota", Some(1889)),
+    (UsState::Tennessee, "TN", "Tennessee", Some(1796)),
+    (UsState::Texas, "TX", "Texas", Some(1845)),
+    (UsState::Utah, "UT", "Utah", Some(1896)),
+    (UsState::Vermont, "VT", "Vermont", Some(1791)),
+    (UsState::Virginia, "VA", "Virginia", Some(1788)),
+    (UsState::Washington, "WA", "Washington", Some(1889)),
+    (UsState::WestVirginia, "WV", "West Virginia", Some(1863)),
+    (UsState::Wisconsin, "WI", "Wisconsin", Some(1848)),
+    (UsState::Wyoming, "WY", "Wyoming", Some(1890)),
+    (
+        UsState::DistrictOfColumbia,
+        "DC",
+        "District of Columbia",
+        None,
+    ),
+    (UsState::PuertoRico, "PR", "Puerto Rico", None),
+    (UsState::Guam, "GU", "Guam", None),
+    (UsState::AmericanSamoa, "AS", "American Samoa", None),
+    (UsState::UsVirginIslands, "VI", "U.S. Virgin Islands", None),
+    (
+        UsState::NorthernMarianaIslands,
+        "MP",
+        "Northern Mariana Islands",
+        None,
+    ),
+];
+
+impl UsState {
+    pub fn all() -> impl Iterator<Item = UsState> {
+        STATES.iter().map(|s| s.0)
+    }
+
+    fn info(self) -> &'static (UsState, &'static str, &'static str, Option<u16>) {
+        // STATES 的順序和 enum 宣告的順序相同，由 tests::table_matches_enum_order 檢查
+        &STATES[self as usize]
+    }
+
+    pub fn abbreviation(self) -> &'static str {
+        self.info().1
+    }
+
+    pub fn name(self) -> &'static str {
+        self.info().2
+    }
+
+    pub fn admission_year(self) -> Option<u16> {
+        self.info().3
+    }
+
+    pub fn is_state(self) -> bool {
+        self.admission_year().is_some()
+    }
+}
+
+impl fmt::Display for UsState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownState(pub String);
+
+impl fmt::Display for UnknownState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "未知的州或屬地 {:?}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownState {}
+
+// 只留英數字並轉小寫："U.S. Virgin Islands" 和 "us virgin islands" 視為相同
+fn normalize(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+// 接受縮寫或名稱，不分大小寫
+impl FromStr for UsState {
+    type Err = UnknownState;
+
+    fn from_str(s: &str) -> Result<UsState, UnknownState> {
+        let key = normalize(s);
+        STATES
+            .iter()
+            .find(|(_, abbr, name, _)| key == normalize(abbr) || key == normalize(name))
+            .map(|s| s.0)
+            .ok_or_else(|| UnknownState(s.trim().to_string()))
+    }
+}
+
+#[derive(Debug)]
+pub enum CollectionError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for CollectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CollectionError::Io(err) => write!(f, "無法讀寫收藏檔: {}", err),
+            CollectionError::Parse { line, message } => write!(f, "第 {} 行: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for CollectionError {}
+
+// 每個州收藏了幾枚 Quarter（重複的可以拿去交換）
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Collection {
+    owned: BTreeMap<UsState, u32>,
+}
+
+impl Collection {
+    pub fn new() -> Collection {
+        Collection::default()
+    }
+
+    // 數量達到 u32::MAX 時回傳 false，不會加入
+    pub fn add(&mut self, state: UsState) -> bool {
+        match self.count(state).checked_add(1) {
+            Some(count) => {
+                self.owned.insert(state, count);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // 只有 Quarter 會被收藏，其他硬幣回傳 false
+    pub fn add_coin(&mut self, coin: &Coin) -> bool {
+        match coin {
+            Coin::Quarter(state) => self.add(*state),
+            _ => false,
+        }
+    }
+
+    // 回傳是否真的有移除
+    pub fn remove(&mut self, state: UsState) -> bool {
+        match self.owned.get_mut(&state) {
+            Some(count) if *count > 1 => *count -= 1,
+            Some(_) => {
+                self.owned.remove(&state);
+            }
+            None => return false,
+        }
+        true
+    }
+
+    pub fn count(&self, state: UsState) -> u32 {
+        self.owned.get(&state).copied().unwrap_or(0)
+    }
+
+    pub fn owns(&self, state: UsState) -> bool {
+        self.count(state) > 0
+    }
+
+    // 還沒收集到的，依 enum 順序
+    pub fn missing(&self) -> Vec<UsState> {
+        UsState::all().filter(|s| !self.owns(*s)).collect()
+    }
+
+    // 每個州多出來（可以交換）的數量
+    pub fn duplicates(&self) -> Vec<(UsState, u32)> {
+        self.owned
+            .iter()
+            .filter(|(_, &count)| count > 1)
+            .map(|(&state, &count)| (state, count - 1))
+            .collect()
+    }
+
+    // (已收集的種類, 全部種類)
+    pub fn progress(&self) -> (usize, usize) {
+        (self.owned.len(), STATES.len())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.owned.len() == STATES.len()
+    }
+
+    // 一行一個州：「縮寫 數量」，# 開頭是註解
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# state quarters: abbreviation count\n");
+        for (state, count) in &self.owned {
+            text.push_str(&format!("{} {}\n", state.abbreviation(), count));
+        }
+        text
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), CollectionError> {
+        fs::write(path, self.to_text()).map_err(CollectionError::Io)
+    }
+
+    pub fn load(path: &str) -> Result<Collection, CollectionError> {
+        fs::read_to_string(path)
+            .map_err(CollectionError::Io)?
+            .parse()
+    }
+}
+
+impl FromStr for Collection {
+    type Err = CollectionError;
+
+    fn from_str(s: &str) -> Result<Collection, CollectionError> {
+        let mut collection = Collection::new();
+        // 數量 0 的州不會存進 collection，所以另外記錄出現過的州
+        let mut seen = BTreeSet::new();
+        for (i, line) in s.lines().enumerate() {
+            let error = |message: String| CollectionError::Parse {
+                line: i + 1,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            // 數量在最後，前面的部分可以是有空白的名稱（例如 New York 2）
+            let (name, count) = line
+                .rsplit_once(char::is_whitespace)
+                .ok_or_else(|| error(String::from("需要「州 數量」")))?;
+            let state: UsState = name
+                .parse()
+                .map_err(|e: UnknownState| error(e.to_string()))?;
+            let count: u32 = count
+                .parse()
+                .map_err(|_| error(format!("{:?} 不是數量", count)))?;
+            if !seen.insert(state) {
+                return Err(error(format!("{} 重複出現", state.abbreviation())));
+            }
+            if count > 0 {
+                collection.owned.insert(state, count);
+            }
+        }
+        Ok(collection)
+    }
+}
+
+pub fn main() {
+    println!("✅ States");
+    let states = UsState::all().filter(|s| s.is_state()).count();
+    println!(
+        "{} states + {} others",
+        states,
+        UsState::all().count() - states
+    );
+    for state in [
+        UsState::Delaware,
+        UsState::Alabama,
+        UsState::Hawaii,
+        UsState::PuertoRico,
+    ] {
+        println!(
+            "{:?}: {} ({}), admitted {:?}",
+            state,
+            state,
+            state.abbreviation(),
+            state.admission_year()
+        );
+    }
+    let newest = UsState::all().max_by_key(|s| s.admission_year()).unwrap();
+    println!("newest: {}", newest);
+
+    println!("✅ FromStr");
+    for input in [
+        "ny",
+        "New York",
+        "new-york",
+        "US Virgin Islands",
+        "DC",
+        "Atlantis",
+    ] {
+        match input.parse::<UsState>() {
+            Ok(state) => println!("{:?} -> {:?}", input, state),
+            Err(err) => println!("{:?} -> {}", input, err),
+        }
+    }
+
+    println!("✅ Collection");
+    let mut collection = Collection::new();
+    for coin in [
+        Coin::Quarter(UsState::Alabama),
+        Coin::Quarter(UsState::Alaska),
+        Coin::Quarter(UsState::Alaska),
+        Coin::Dime,
+        Coin::Quarter(UsState::NewYork),
+        Coin::Quarter(UsState::Guam),
+    ] {
+        collection.add_coin(&coin);
+    }
+    for state in UsState::all().filter(|s| matches!(s.admission_year(), Some(year) if year < 1790))
+    {
+        collection.add(state);
+    }
+    let (owned, total) = collection.progress();
+    println!("progress: {}/{}", owned, total);
+    println!("duplicates: {:?}", collection.duplicates());
+    let missing = collection.missing();
+    let names: Vec<&str> = missing.iter().take(8).map(|s| s.abbreviation()).collect();
+    println!("missing {}: {} ...", missing.len(), names.join(", "));
+    let territories: Vec<String> = missing
+        .iter()
+        .filter(|s| !s.is_state())
+        .map(|s| s.to_string())
+        .collect();
+    println!("missing non-states: {:?}", territories);
+
+    println!("✅ Save and load");
+    let path = std::env::temp_dir().join("chapter6_12_quarters.txt");
+    let path = path.to_str().unwrap();
+    collection.save(path).unwrap();
+    let loaded = Collection::load(path).unwrap();
+    println!("loaded equals saved: {}", loaded == collection);
+    print!(
+        "{}",
+        loaded
+            .to_text()
+            .lines()
+            .take(4)
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+    println!("\n...");
+
+    for bad in ["AL 1\nZZ 2", "AL", "AL two", "AL 1\nalabama 2"] {
+        println!("{:?}: {}", bad, bad.parse::<Collection>().unwrap_err());
+    }
+    println!(
+        "{}",
+        Collection::load("/nonexistent/quarters.txt").unwrap_err()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn table_matches_enum_order() {
+        // info() 依賴 STATES 和 enum 的順序一致
+        for (i, (state, ..)) in STATES.iter().enumerate() {
+            assert_eq!(*state as usize, i, "{:?} 在 STATES 的位置不對", state);
+            assert_eq!(state.info().0, *state);
+        }
+        assert_eq!(UsState::NorthernMarianaIslands as usize, STATES.len() - 1);
+    }
+
+    #[test]
+    fn abbreviations_and_names_are_unique() {
+        let abbreviations: HashSet<_> = UsState::all().map(|s| s.abbreviation()).collect();
+        let names: HashSet<_> = UsState::all().map(|s| normalize(s.name())).collect();
+        assert_eq!(abbreviations.len(), STATES.len());
+        assert_eq!(names.len(), STATES.len());
+        assert_eq!(UsState::all().filter(|s| s.is_state()).count(), 50);
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        for state in UsState::all() {
+            assert_eq!(state.abbreviation().parse(), Ok(state));
+            assert_eq!(state.name().parse(), Ok(state));
+            assert_eq!(state.name().to_uppercase().parse(), Ok(state));
+        }
+        assert_eq!(
+            "Atlantis".parse::<UsState>(),
+            Err(UnknownState(String::from("Atlantis")))
+        );
+    }
+
+    fn parse_error(input: &str) -> (usize, String) {
+        match input.parse::<Collection>() {
+            Err(CollectionError::Parse { line, message }) => (line, message),
+            other => panic!("{:?}: {:?}", input, other),
+        }
+    }
+
+    #[test]
+    fn add_stops_at_u32_max() {
+        let mut collection: Collection = format!("AL {}", u32::MAX - 1).parse().unwrap();
+        assert!(collection.add(UsState::Alabama));
+        assert_eq!(collection.count(UsState::Alabama), u32::MAX);
+        assert!(!collection.add(UsState::Alabama));
+        assert!(!collection.add_coin(&Coin::Quarter(UsState::Alabama)));
+        assert_eq!(collection.count(UsState::Alabama), u32::MAX);
+        assert_eq!(
+            collection.duplicates(),
+            vec![(UsState::Alabama, u32::MAX - 1)]
+        );
+
+        assert!(collection.add_coin(&Coin::Quarter(UsState::Guam)));
+        assert!(!collection.add_coin(&Coin::Dime));
+        assert_eq!(collection.progress().0, 2);
+    }
+
+    #[test]
+    fn parse_errors_have_line_numbers() {
+        let cases = [
+            ("AL 1\nZZ 2", 2, "ZZ"),
+            ("# comment\n\nAL", 3, "州 數量"),
+            ("AL two", 1, "\"two\""),
+            ("AL -1", 1, "\"-1\""),
+            (&format!("AL {}", u64::from(u32::MAX) + 1), 1, "4294967296"),
+            ("AL 1\nalabama 2", 2, "AL 重複出現"),
+            // 數量 0 也算出現過
+            ("AL 0\nAL 1", 2, "AL 重複出現"),
+            ("AL 1\nNY 0\nNew York 0", 3, "NY 重複出現"),
+        ];
+        for (input, line, needle) in cases {
+            let (actual, message) = parse_error(input);
+            assert_eq!(actual, line, "{:?}", input);
+            assert!(message.contains(needle), "{:?}: {}", input, message);
+        }
+        let err = "AL 1\nZZ 2".parse::<Collection>().unwrap_err();
+        assert!(err.to_string().starts_with("第 2 行: "), "{}", err);
+        assert!(matches!(
+            Collection::load("/nonexistent/quarters.txt"),
+            Err(CollectionError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn parse_names_and_zero_counts() {
+        let collection: Collection = "\
+# 註解
+  New York 2
+us virgin islands 1
+DC 0
+"
+        .parse()
+        .unwrap();
+        assert_eq!(collection.count(UsState::NewYork), 2);
+        assert_eq!(collection.count(UsState::UsVirginIslands), 1);
+        assert!(!collection.owns(UsState::DistrictOfColumbia));
+        assert_eq!(collection.progress(), (2, STATES.len()));
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let mut collection = Collection::new();
+        assert_eq!(
+            collection.to_text().parse::<Collection>().unwrap(),
+            collection
+        );
+        for state in [
+            UsState::Alaska,
+            UsState::Alabama,
+            UsState::Alaska,
+            UsState::NewYork,
+            UsState::NorthernMarianaIslands,
+        ] {
+            collection.add(state);
+        }
+        let text = collection.to_text();
+        assert_eq!(
+            text,
+            "# state quarters: abbreviation count\nAL 1\nAK 2\nNY 1\nMP 1\n"
+        );
+        assert_eq!(text.parse::<Collection>().unwrap(), collection);
+
+        let full = Collection {
+            owned: UsState::all().map(|s| (s, u32::MAX)).collect(),
+        };
+        assert!(full.is_complete());
+        assert_eq!(full.to_text().parse::<Collection>().unwrap(), full);
+    }
+
+    #[test]
+    fn missing_and_duplicates() {
+        let mut collection = Collection::new();
+        assert_eq!(collection.missing(), UsState::all().collect::<Vec<_>>());
+        assert!(collection.duplicates().is_empty());
+
+        for _ in 0..3 {
+            collection.add(UsState::Wyoming);
+        }
+        collection.add(UsState::Delaware);
+        collection.add(UsState::Delaware);
+        collection.add(UsState::Guam);
+        // 依 enum 順序
+        assert_eq!(
+            collection.duplicates(),
+            vec![(UsState::Delaware, 1), (UsState::Wyoming, 2)]
+        );
+        let missing = collection.missing();
+        assert_eq!(missing.len(), STATES.len() - 3);
+        assert!(!missing.contains(&UsState::Guam));
+        assert!(missing.windows(2).all(|w| w[0] < w[1]));
+
+        // 移除到 0 之後又變成缺少
+        assert!(collection.remove(UsState::Guam));
+        assert!(!collection.remove(UsState::Guam));
+        assert!(collection.missing().contains(&UsState::Guam));
+        assert!(collection.remove(UsState::Wyoming));
+        assert_eq!(collection.count(UsState::Wyoming), 2);
+
+        for state in UsState::all() {
+            collection.add(state);
+        }
+        assert!(collection.is_complete());
+        assert!(collection.missing().is_empty());
+    }
+}
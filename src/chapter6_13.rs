@@ -0,0 +1,531 @@
+// Vending Machine（結合 Coin、Purse 與 Status 的販賣機）
+use crate::chapter6::{Coin, UsState};
+use crate::chapter6_11::{Denomination, Purse};
+use crate::chapter6_2::Tracked;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Read};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Product {
+    pub name: String,
+    pub price: u64, // 單位是 cent
+    pub stock: u32,
+}
+
+// 一次交易：從投入第一枚硬幣開始，到出貨、找零失敗或取消為止
+#[derive(Debug, Clone, Default)]
+pub struct Transaction {
+    pub id: usize,
+    pub inserted: Purse,
+    pub product: Option<String>, // 成功時買到的商品代碼
+    pub change: Purse,           // 找零；失敗或取消時是退回的硬幣
+}
+
+impl Transaction {
+    pub fn credit(&self) -> u64 {
+        self.inserted.total()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VendError {
+    UnknownProduct(String),
+    SoldOut(String),
+    InsufficientFunds { price: u64, credit: u64 }, // 交易繼續，可以再投幣
+    CannotMakeChange { change: u64 },              // 交易失敗，退回投入的硬幣
+    CoinRejected,                                  // 硬幣數量達到上限
+    BankFull,                                      // 機器收不下投入的硬幣，交易失敗並退幣
+    NothingToCancel,
+}
+
+impl fmt::Display for VendError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VendError::UnknownProduct(code) => write!(f, "沒有商品 {}", code),
+            VendError::SoldOut(code) => write!(f, "{} 已售完", code),
+            VendError::InsufficientFunds { price, credit } => {
+                write!(f, "金額不足：需要 {}¢，已投入 {}¢", price, credit)
+            }
+            VendError::CannotMakeChange { change } => write!(f, "無法找零 {}¢", change),
+            VendError::CoinRejected => write!(f, "無法收下這枚硬幣"),
+            VendError::BankFull => write!(f, "機器的硬幣已滿，已退幣"),
+            VendError::NothingToCancel => write!(f, "沒有進行中的交易"),
+        }
+    }
+}
+
+impl std::error::Error for VendError {}
+
+#[derive(Debug, Clone)]
+pub struct Machine {
+    products: BTreeMap<String, Product>,
+    bank: Purse, // 機器裡可以用來找零的硬幣
+    current: Option<Tracked<Transaction>>,
+    history: Vec<Tracked<Transaction>>,
+}
+
+impl Machine {
+    pub fn new(bank: Purse) -> Machine {
+        Machine {
+            products: BTreeMap::new(),
+            bank,
+            current: None,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn with_product(mut self, code: &str, name: &str, price: u64, stock: u32) -> Machine {
+        self.products.insert(
+            code.to_string(),
+            Product {
+                name: name.to_string(),
+                price,
+                stock,
+            },
+        );
+        self
+    }
+
+    pub fn product(&self, code: &str) -> Option<&Product> {
+        self.products.get(code)
+    }
+
+    pub fn bank(&self) -> Purse {
+        self.bank
+    }
+
+    pub fn credit(&self) -> u64 {
+        self.current.as_ref().map_or(0, |t| t.value.credit())
+    }
+
+    pub fn current(&self) -> Option<&Tracked<Transaction>> {
+        self.current.as_ref()
+    }
+
+    // 已結束的交易，狀態是 Success 或 Error
+    pub fn history(&self) -> &[Tracked<Transaction>] {
+        &self.history
+    }
+
+    fn new_transaction(&self) -> Tracked<Transaction> {
+        Tracked::new(Transaction {
+            id: self.history.len() + 1,
+            ..Transaction::default()
+        })
+    }
+
+    // 沒有進行中的交易時會開始一筆新的（Pending）
+    // 金額由面額決定，不呼叫會印出州名的 value_in_cents
+    pub fn insert(&mut self, coin: Coin) -> Result<u64, VendError> {
+        let fresh = self.new_transaction();
+        let transaction = self.current.get_or_insert(fresh);
+        if !transaction.value.inserted.add(&coin) {
+            return Err(VendError::CoinRejected);
+        }
+        Ok(transaction.value.credit())
+    }
+
+    fn close(&mut self, mut transaction: Tracked<Transaction>, error: Option<&str>) {
+        let result = match error {
+            Some(reason) => transaction.fail(reason),
+            None => transaction.succeed(),
+        };
+        result.expect("進行中的交易一定是 Pending");
+        self.history.push(transaction);
+    }
+
+    // 成功時回傳商品名稱與找零；金額不足、代碼錯誤或售完時交易保持 Pending
+    // 免費的商品不需要投幣，會直接開始並完成一筆交易
+    pub fn select(&mut self, code: &str) -> Result<(String, Purse), VendError> {
+        let product = self
+            .products
+            .get(code)
+            .ok_or_else(|| VendError::UnknownProduct(code.to_string()))?;
+        if product.stock == 0 {
+            return Err(VendError::SoldOut(code.to_string()));
+        }
+        let credit = self.credit();
+        if credit < product.price {
+            return Err(VendError::InsufficientFunds {
+                price: product.price,
+                credit,
+            });
+        }
+
+        let (price, name) = (product.price, product.name.clone());
+        let mut transaction = match self.current.take() {
+            Some(transaction) => transaction,
+            None => self.new_transaction(),
+        };
+        // 投入的硬幣也可以拿來找零
+        let mut available = self.bank;
+        if !available.add_purse(&transaction.value.inserted) {
+            transaction.value.change = transaction.value.inserted;
+            self.close(transaction, Some("機器的硬幣已滿，已退幣"));
+            return Err(VendError::BankFull);
+        }
+        let change_amount = credit - price;
+        match available.pay(change_amount) {
+            Ok(change) => {
+                self.products.get_mut(code).unwrap().stock -= 1;
+                self.bank = available;
+                transaction.value.product = Some(code.to_string());
+                transaction.value.change = change;
+                self.close(transaction, None);
+                Ok((name, change))
+            }
+            Err(_) => {
+                // 退回原本投入的硬幣，機器裡的硬幣不變
+                transaction.value.change = transaction.value.inserted;
+                let reason = format!("無法找零 {}¢，已退幣", change_amount);
+                self.close(transaction, Some(&reason));
+                Err(VendError::CannotMakeChange {
+                    change: change_amount,
+                })
+            }
+        }
+    }
+
+    // 退回投入的硬幣，交易以 Error 結束
+    pub fn cancel(&mut self) -> Result<Purse, VendError> {
+        let mut transaction = self.current.take().ok_or(VendError::NothingToCancel)?;
+        let refund = transaction.value.inserted;
+        transaction.value.change = refund;
+        self.close(transaction, Some("已取消"));
+        Ok(refund)
+    }
+
+    pub fn restock(&mut self, code: &str, count: u32) -> Result<u32, VendError> {
+        let product = self
+            .products
+            .get_mut(code)
+            .ok_or_else(|| VendError::UnknownProduct(code.to_string()))?;
+        product.stock = product.stock.saturating_add(count);
+        Ok(product.stock)
+    }
+}
+
+#[derive(Debug)]
+pub enum Command {
+    Insert(Coin),
+    Select(String),
+    Cancel,
+    Restock(String, u32),
+    Status,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "第 {} 行: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+fn parse_coin(args: &[&str]) -> Result<Coin, String> {
+    match args {
+        ["penny"] => Ok(Coin::Penny),
+        ["nickel"] => Ok(Coin::Nickel),
+        ["dime"] => Ok(Coin::Dime),
+        ["quarter", state @ ..] if !state.is_empty() => {
+            let state: UsState = state.join(" ").parse().map_err(|e| format!("{}", e))?;
+            Ok(Coin::Quarter(state))
+        }
+        ["quarter"] => Err(String::from("quarter 需要州名，例如 insert quarter NY")),
+        _ => Err(format!("無效的硬幣 {:?}", args.join(" "))),
+    }
+}
+
+// 腳本格式，一行一個指令，# 開頭是註解：
+//   insert dime / insert quarter NY
+//   select A1
+//   cancel
+//   restock A1 5
+//   status
+pub fn parse_script(s: &str) -> Result<Vec<Command>, ScriptError> {
+    let mut commands = Vec::new();
+    for (i, line) in s.lines().enumerate() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let command = match words.as_slice() {
+            [] => continue,
+            [first, ..] if first.starts_with('#') => continue,
+            ["insert", coin @ ..] => parse_coin(coin).map(Command::Insert),
+            ["select", code] => Ok(Command::Select(code.to_string())),
+            ["cancel"] => Ok(Command::Cancel),
+            ["restock", code, count] => count
+                .parse()
+                .map(|n| Command::Restock(code.to_string(), n))
+                .map_err(|_| format!("{:?} 不是數量", count)),
+            ["status"] => Ok(Command::Status),
+            _ => Err(format!("無法理解 {:?}", line.trim())),
+        };
+        commands.push(command.map_err(|message| ScriptError {
+            line: i + 1,
+            message,
+        })?);
+    }
+    Ok(commands)
+}
+
+// 執行腳本，每個指令輸出一行結果
+pub fn run_script(machine: &mut Machine, commands: Vec<Command>) -> Vec<String> {
+    commands
+        .into_iter()
+        .map(|command| match command {
+            Command::Insert(coin) => {
+                let name = format!("{:?}", coin);
+                match machine.insert(coin) {
+                    Ok(credit) => format!("insert {}: credit {}¢", name, credit),
+                    Err(err) => format!("insert {}: {}", name, err),
+                }
+            }
+            Command::Select(code) => match machine.select(&code) {
+                Ok((name, change)) => format!("select {}: {}，找零 {}", code, name, change),
+                Err(err) => format!("select {}: {}", code, err),
+            },
+            Command::Cancel => match machine.cancel() {
+                Ok(refund) => format!("cancel: 退回 {}", refund),
+                Err(err) => format!("cancel: {}", err),
+            },
+            Command::Restock(code, count) => match machine.restock(&code, count) {
+                Ok(stock) => format!("restock {}: stock {}", code, stock),
+                Err(err) => format!("restock {}: {}", code, err),
+            },
+            Command::Status => format!(
+                "status: credit {}¢, bank {}, transactions {}",
+                machine.credit(),
+                machine.bank(),
+                machine.history().len()
+            ),
+        })
+        .collect()
+}
+
+fn demo_machine() -> Machine {
+    Machine::new(
+        Purse::new()
+            .with(Denomination::Nickel, 1)
+            .with(Denomination::Dime, 2),
+    )
+    .with_product("A1", "Water", 65, 2)
+    .with_product("A2", "Soda", 90, 1)
+    .with_product("B1", "Candy", 35, 0)
+}
+
+// cargo run -- vend script.txt；沒有檔名時從 stdin 讀取
+pub fn vend_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let source = match args.first() {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            text
+        }
+    };
+    let commands = parse_script(&source)?;
+    let mut machine = demo_machine();
+    for line in run_script(&mut machine, commands) {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+pub fn main() {
+    println!("✅ Script");
+    let script = "\
+# 買水：75¢ 找 10¢
+insert quarter NY
+insert quarter California
+insert quarter DC
+select A1
+# 金額不足，再投幣
+insert quarter TX
+select A2
+insert quarter TX
+insert quarter TX
+insert quarter TX
+select A2
+# 售完與取消
+insert dime
+select B1
+select Z9
+cancel
+cancel
+status
+";
+    let mut machine = demo_machine();
+    for line in run_script(&mut machine, parse_script(script).unwrap()) {
+        println!("{}", line);
+    }
+    for t in machine.history() {
+        println!(
+            "  #{} {:?} paid {}¢ product {:?} change/refund {} reason {:?}",
+            t.value.id,
+            t.status(),
+            t.value.credit(),
+            t.value.product,
+            t.value.change,
+            t.last_error()
+        );
+    }
+
+    println!("✅ Script errors");
+    for bad in [
+        "insert euro",
+        "insert quarter",
+        "insert quarter Atlantis",
+        "restock A1 many",
+        "dance",
+    ] {
+        println!("{:?}: {}", bad, parse_script(bad).unwrap_err());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chapter6::Status;
+
+    fn coin_of(denomination: Denomination) -> Coin {
+        match denomination {
+            Denomination::Penny => Coin::Penny,
+            Denomination::Nickel => Coin::Nickel,
+            Denomination::Dime => Coin::Dime,
+            Denomination::Quarter => Coin::Quarter(UsState::Delaware),
+        }
+    }
+
+    #[test]
+    fn every_coin_mix_bank_and_price() {
+        // 每種投幣組合（各面額 0-3 枚）× 每種找零存量（各面額 0-2 枚）× 每個價格（含免費）
+        let mut counts = [0usize; 3];
+        for inserted in 0..4u32.pow(4) {
+            let coins = Denomination::ALL.map(|d| (inserted / 4u32.pow(d as u32)) % 4);
+            let mut loaded = Machine::new(Purse::new());
+            for (d, &n) in Denomination::ALL.iter().zip(&coins) {
+                for _ in 0..n {
+                    loaded.insert(coin_of(*d)).unwrap();
+                }
+            }
+            for price in [0u64, 5, 35, 65, 90] {
+                for stock in 0..3u32.pow(4) {
+                    let bank = Denomination::ALL.iter().fold(Purse::new(), |p, &d| {
+                        p.with(d, (stock / 3u32.pow(d as u32)) % 3)
+                    });
+                    let mut machine = loaded.clone().with_product("X", "Item", price, 1);
+                    machine.bank = bank;
+                    let credit = machine.credit();
+                    let before = machine.bank().total() + credit;
+                    let result = machine.select("X");
+
+                    // 預期：金額不足 -> 交易保持 Pending；找不開 -> Error 並退幣；否則 Success
+                    let mut available = bank;
+                    for (d, &n) in Denomination::ALL.iter().zip(&coins) {
+                        available.add_many(*d, n);
+                    }
+                    let can_change = credit >= price
+                        && available
+                            .count_ways(credit - price)
+                            .is_ok_and(|ways| ways > 0);
+                    match &result {
+                        Err(VendError::InsufficientFunds { .. }) => {
+                            assert!(credit < price);
+                            assert_eq!(machine.history().len(), 0);
+                            counts[0] += 1;
+                        }
+                        Err(VendError::CannotMakeChange { .. }) => {
+                            assert!(credit >= price && !can_change);
+                            assert_eq!(machine.history()[0].status(), Status::Error);
+                            assert_eq!(machine.bank(), bank);
+                            counts[1] += 1;
+                        }
+                        Ok((_, change)) => {
+                            assert!(can_change);
+                            assert_eq!(machine.history()[0].status(), Status::Success);
+                            assert_eq!(change.total(), credit - price);
+                            // 錢不會憑空出現或消失
+                            assert_eq!(machine.bank().total() + change.total(), before);
+                            assert_eq!(machine.bank().total(), bank.total() + price);
+                            assert_eq!(machine.product("X").unwrap().stock, 0);
+                            counts[2] += 1;
+                        }
+                        Err(err) => panic!("unexpected {:?}", err),
+                    }
+                }
+            }
+        }
+        assert!(counts.iter().all(|&n| n > 0), "{:?}", counts);
+    }
+
+    #[test]
+    fn free_item_without_coins() {
+        let bank = Purse::new().with(Denomination::Dime, 1);
+        let mut machine = Machine::new(bank).with_product("F", "Flyer", 0, 2);
+        assert_eq!(
+            machine.select("F"),
+            Ok((String::from("Flyer"), Purse::new()))
+        );
+        assert_eq!(machine.credit(), 0);
+        assert!(machine.current().is_none());
+        assert_eq!(machine.bank(), bank);
+        assert_eq!(machine.product("F").unwrap().stock, 1);
+        assert_eq!(machine.history().len(), 1);
+        assert_eq!(machine.history()[0].status(), Status::Success);
+        assert_eq!(machine.history()[0].value.product.as_deref(), Some("F"));
+
+        // 有投幣時免費商品會把投入的硬幣全部找回
+        machine.insert(Coin::Nickel).unwrap();
+        let (_, change) = machine.select("F").unwrap();
+        assert_eq!(change, Purse::new().with(Denomination::Nickel, 1));
+        assert_eq!(machine.history()[1].value.id, 2);
+        assert_eq!(
+            machine.select("F"),
+            Err(VendError::SoldOut(String::from("F")))
+        );
+    }
+
+    #[test]
+    fn full_bank_refunds_instead_of_dropping_coins() {
+        let bank = Purse::new().with(Denomination::Penny, u32::MAX);
+        let mut machine = Machine::new(bank).with_product("X", "Item", 1, 1);
+        machine.insert(Coin::Penny).unwrap();
+        assert_eq!(machine.select("X"), Err(VendError::BankFull));
+        assert_eq!(machine.bank(), bank);
+        assert_eq!(machine.product("X").unwrap().stock, 1);
+        let transaction = &machine.history()[0];
+        assert_eq!(transaction.status(), Status::Error);
+        assert_eq!(
+            transaction.value.change,
+            Purse::new().with(Denomination::Penny, 1)
+        );
+        assert!(machine.current().is_none());
+    }
+
+    #[test]
+    fn insert_reports_credit() {
+        let mut machine = demo_machine();
+        assert_eq!(machine.insert(Coin::Quarter(UsState::Alaska)), Ok(25));
+        assert_eq!(machine.insert(Coin::Dime), Ok(35));
+        assert_eq!(
+            machine.select("A1"),
+            Err(VendError::InsufficientFunds {
+                price: 65,
+                credit: 35
+            })
+        );
+        assert_eq!(
+            machine.cancel(),
+            Ok(Purse::new()
+                .with(Denomination::Quarter, 1)
+                .with(Denomination::Dime, 1))
+        );
+        assert_eq!(machine.cancel(), Err(VendError::NothingToCancel));
+    }
+}
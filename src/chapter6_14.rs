@@ -0,0 +1,737 @@
+// CharClass（支援 Unicode 的字元分類，擴充 chapter6::classify）
+use std::collections::BTreeMap;
+use std::fmt;
+
+// 十進位數字所屬的書寫系統，依碼位排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DigitScript {
+    Ascii,
+    ArabicIndic,
+    ExtendedArabicIndic,
+    Nko,
+    Devanagari,
+    Bengali,
+    Gurmukhi,
+    Gujarati,
+    Odia,
+    Tamil,
+    Telugu,
+    Kannada,
+    Malayalam,
+    SinhalaLith,
+    Thai,
+    Lao,
+    Tibetan,
+    Myanmar,
+    MyanmarShan,
+    Khmer,
+    Mongolian,
+    Limbu,
+    NewTaiLue,
+    TaiThamHora,
+    TaiThamTham,
+    Balinese,
+    Sundanese,
+    Lepcha,
+    OlChiki,
+    Vai,
+    Saurashtra,
+    KayahLi,
+    Javanese,
+    MyanmarTaiLaing,
+    Cham,
+    MeeteiMayek,
+    Fullwidth,
+    Osmanya,
+    HanifiRohingya,
+    Garay,
+    Brahmi,
+    SoraSompeng,
+    Chakma,
+    Sharada,
+    Khudawadi,
+    Newa,
+    Tirhuta,
+    Modi,
+    Takri,
+    MyanmarPao,
+    MyanmarEasternPwoKaren,
+    Ahom,
+    WarangCiti,
+    DivesAkuru,
+    Sunuwar,
+    Bhaiksuki,
+    MasaramGondi,
+    GunjalaGondi,
+    TolongSiki,
+    Kawi,
+    GurungKhema,
+    Mro,
+    Tangsa,
+    PahawhHmong,
+    KiratRai,
+    Outlined,
+    Mathematical,
+    NyiakengPuachueHmong,
+    Wancho,
+    NagMundari,
+    OlOnal,
+    Adlam,
+    Segmented,
+}
+
+// Unicode 17 所有 Nd（十進位數字）區段的 0 所在的碼位，0-9 是連續的
+// 數學用的數字有五組，都算 Mathematical
+const DIGIT_ZEROS: [(u32, DigitScript); 77] = [
+    (0x0030, DigitScript::Ascii),
+    (0x0660, DigitScript::ArabicIndic),
+    (0x06F0, DigitScript::ExtendedArabicIndic),
+    (0x07C0, DigitScript::Nko),
+    (0x0966, DigitScript::Devanagari),
+    (0x09E6, DigitScript::Bengali),
+    (0x0A66, DigitScript::Gurmukhi),
+    (0x0AE6, DigitScript::Gujarati),
+    (0x0B66, DigitScript::Odia),
+    (0x0BE6, DigitScript::Tamil),
+    (0x0C66, DigitScript::Telugu),
+    (0x0CE6, DigitScript::Kannada),
+    (0x0D66, DigitScript::Malayalam),
+    (0x0DE6, DigitScript::SinhalaLith),
+    (0x0E50, DigitScript::Thai),
+    (0x0ED0, DigitScript::Lao),
+    (0x0F20, DigitScript::Tibetan),
+    (0x1040, DigitScript::Myanmar),
+    (0x1090, DigitScript::MyanmarShan),
+    (0x17E0, DigitScript::Khmer),
+    (0x1810, DigitScript::Mongolian),
+    (0x1946, DigitScript::Limbu),
+    (0x19D0, DigitScript::NewTaiLue),
+    (0x1A80, DigitScript::TaiThamHora),
+    (0x1A90, DigitScript::TaiThamTham),
+    (0x1B50, DigitScript::Balinese),
+    (0x1BB0, DigitScript::Sundanese),
+    (0x1C40, DigitScript::Lepcha),
+    (0x1C50, DigitScript::OlChiki),
+    (0xA620, DigitScript::Vai),
+    (0xA8D0, DigitScript::Saurashtra),
+    (0xA900, DigitScript::KayahLi),
+    (0xA9D0, DigitScript::Javanese),
+    (0xA9F0, DigitScript::MyanmarTaiLaing),
+    (0xAA50, DigitScript::Cham),
+    (0xABF0, DigitScript::MeeteiMayek),
+    (0xFF10, DigitScript::Fullwidth),
+    (0x104A0, DigitScript::Osmanya),
+    (0x10D30, DigitScript::HanifiRohingya),
+    (0x10D40, DigitScript::Garay),
+    (0x11066, DigitScript::Brahmi),
+    (0x110F0, DigitScript::SoraSompeng),
+    (0x11136, DigitScript::Chakma),
+    (0x111D0, DigitScript::Sharada),
+    (0x112F0, DigitScript::Khudawadi),
+    (0x11450, DigitScript::Newa),
+    (0x114D0, DigitScript::Tirhuta),
+    (0x11650, DigitScript::Modi),
+    (0x116C0, DigitScript::Takri),
+    (0x116D0, DigitScript::MyanmarPao),
+    (0x116DA, DigitScript::MyanmarEasternPwoKaren),
+    (0x11730, DigitScript::Ahom),
+    (0x118E0, DigitScript::WarangCiti),
+    (0x11950, DigitScript::DivesAkuru),
+    (0x11BF0, DigitScript::Sunuwar),
+    (0x11C50, DigitScript::Bhaiksuki),
+    (0x11D50, DigitScript::MasaramGondi),
+    (0x11DA0, DigitScript::GunjalaGondi),
+    (0x11DE0, DigitScript::TolongSiki),
+    (0x11F50, DigitScript::Kawi),
+    (0x16130, DigitScript::GurungKhema),
+    (0x16A60, DigitScript::Mro),
+    (0x16AC0, DigitScript::Tangsa),
+    (0x16B50, DigitScript::PahawhHmong),
+    (0x16D70, DigitScript::KiratRai),
+    (0x1CCF0, DigitScript::Outlined),     // 外框數字
+    (0x1D7CE, DigitScript::Mathematical), // 粗體
+    (0x1D7D8, DigitScript::Mathematical), // 雙線
+    (0x1D7E2, DigitScript::Mathematical), // 無襯線
+    (0x1D7EC, DigitScript::Mathematical), // 無襯線粗體
+    (0x1D7F6, DigitScript::Mathematical), // 等寬
+    (0x1E140, DigitScript::NyiakengPuachueHmong),
+    (0x1E2F0, DigitScript::Wancho),
+    (0x1E4F0, DigitScript::NagMundari),
+    (0x1E5F1, DigitScript::OlOnal),
+    (0x1E950, DigitScript::Adlam),
+    (0x1FBF0, DigitScript::Segmented), // 七段顯示器風格
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CharClass {
+    Lowercase,
+    Uppercase,
+    Titlecase, // ǅ、ᾈ 這類首字母大寫的合字
+    CjkIdeograph,
+    OtherLetter, // 沒有大小寫的字母：假名、諺文、阿拉伯字母...
+    Digit { script: DigitScript, value: u8 },
+    Numeric, // 其他數字：½、Ⅻ、①、〇...
+    Whitespace,
+    Punctuation,
+    Symbol, // 貨幣、數學、箭頭、框線...
+    Emoji,
+    Mark,   // 組合用的附加符號
+    Format, // 看不見的格式字元：ZWJ、變體選擇符、BOM...
+    Control,
+    Other,
+}
+
+// 標準函式庫沒有 titlecase 的判斷，Unicode 的 Lt 類別只有這幾段
+const TITLECASE: [(u32, u32); 10] = [
+    (0x01C5, 0x01C5),
+    (0x01C8, 0x01C8),
+    (0x01CB, 0x01CB),
+    (0x01F2, 0x01F2),
+    (0x1F88, 0x1F8F),
+    (0x1F98, 0x1F9F),
+    (0x1FA8, 0x1FAF),
+    (0x1FBC, 0x1FBC),
+    (0x1FCC, 0x1FCC),
+    (0x1FFC, 0x1FFC),
+];
+
+const CJK_IDEOGRAPHS: [(u32, u32); 10] = [
+    (0x3400, 0x4DBF),   // 擴充 A
+    (0x4E00, 0x9FFF),   // 基本區
+    (0xF900, 0xFAFF),   // 相容表意文字
+    (0x20000, 0x2A6DF), // 擴充 B
+    (0x2A700, 0x2B73F), // 擴充 C
+    (0x2B740, 0x2B81F), // 擴充 D
+    (0x2B820, 0x2CEAF), // 擴充 E
+    (0x2CEB0, 0x2EBEF), // 擴充 F
+    (0x2F800, 0x2FA1F), // 相容補充
+    (0x30000, 0x3134F), // 擴充 G
+];
+
+const MARKS: [(u32, u32); 5] = [
+    (0x0300, 0x036F),
+    (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF),
+    (0x20D0, 0x20FF), // 包含 keycap 的 U+20E3
+    (0xFE20, 0xFE2F),
+];
+
+const FORMATS: [(u32, u32); 6] = [
+    (0x00AD, 0x00AD), // soft hyphen
+    (0x200B, 0x200F), // 零寬空格、ZWNJ、ZWJ、方向標記
+    (0x202A, 0x202E),
+    (0x2060, 0x2064),
+    (0xFE00, 0xFE0F), // 變體選擇符，FE0F 讓前一個字元顯示成 emoji
+    (0xFEFF, 0xFEFF), // BOM
+];
+
+// 中文常用的全形標點都在這裡：，。、：；！？（）「」『』《》—…
+const PUNCTUATION: [(u32, u32); 24] = [
+    (0x00A1, 0x00A1),
+    (0x00A7, 0x00A7),
+    (0x00AB, 0x00AB),
+    (0x00B6, 0x00B7),
+    (0x00BB, 0x00BB),
+    (0x00BF, 0x00BF),
+    (0x2010, 0x2027),
+    (0x2030, 0x205E),
+    (0x2768, 0x2775),
+    (0x3001, 0x3003),
+    (0x3008, 0x3011),
+    (0x3014, 0x301F),
+    (0x30FB, 0x30FB),
+    (0xFE10, 0xFE19),
+    (0xFE30, 0xFE4F),
+    (0xFE50, 0xFE61),
+    (0xFF01, 0xFF03),
+    (0xFF05, 0xFF0A),
+    (0xFF0C, 0xFF0F),
+    (0xFF1A, 0xFF1B),
+    (0xFF1F, 0xFF20),
+    (0xFF3B, 0xFF3D),
+    (0xFF3F, 0xFF3F),
+    (0xFF5B, 0xFF65),
+];
+
+// 會以彩色圖示顯示的區段；✅（U+2705）在 Dingbats 裡
+const EMOJI: [(u32, u32); 8] = [
+    (0x231A, 0x231B),
+    (0x23E9, 0x23FA),
+    (0x2600, 0x27BF),
+    (0x2B05, 0x2B07),
+    (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B50),
+    (0x2B55, 0x2B55),
+    (0x1F000, 0x1FAFF),
+];
+
+const SYMBOLS: [(u32, u32); 18] = [
+    (0x00A2, 0x00A6), // ¢ £ ¤ ¥ ¦
+    (0x00A8, 0x00A9),
+    (0x00AC, 0x00AC),
+    (0x00AE, 0x00B1),
+    (0x00B4, 0x00B4),
+    (0x00B8, 0x00B8),
+    (0x00D7, 0x00D7), // ×
+    (0x00F7, 0x00F7),
+    (0x02C2, 0x02C5),
+    (0x20A0, 0x20CF), // 貨幣
+    (0x2100, 0x214F), // 字母式符號，例如 ℃（字母在前面就分類掉了）
+    (0x2190, 0x23FF), // 箭頭、數學、技術符號
+    (0x2500, 0x25FF), // 框線、方塊、幾何圖形
+    (0x2B00, 0x2BFF),
+    (0x3200, 0x33FF), // 圍起來的 CJK 字母與月份、單位
+    (0xFF04, 0xFF04),
+    (0xFF0B, 0xFF0B),
+    (0xFFE0, 0xFFEE),
+];
+
+fn in_ranges(c: char, ranges: &[(u32, u32)]) -> bool {
+    let c = c as u32;
+    ranges
+        .iter()
+        .any(|&(start, end)| (start..=end).contains(&c))
+}
+
+fn decimal_digit(c: char) -> Option<(DigitScript, u8)> {
+    let c = c as u32;
+    DIGIT_ZEROS
+        .iter()
+        .find(|(zero, _)| (*zero..zero + 10).contains(&c))
+        .map(|&(zero, script)| (script, (c - zero) as u8))
+}
+
+// 依序檢查：空白、控制、格式、數字、字母、標點、emoji、符號、組合記號
+// 順序有意義，例如 '\n' 同時是空白與控制字元，'Ⅷ' 同時是數字與大寫
+pub fn classify(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Whitespace
+    } else if c.is_control() {
+        CharClass::Control
+    } else if in_ranges(c, &FORMATS) {
+        CharClass::Format
+    } else if let Some((script, value)) = decimal_digit(c) {
+        CharClass::Digit { script, value }
+    } else if c.is_numeric() {
+        CharClass::Numeric
+    } else if in_ranges(c, &TITLECASE) {
+        CharClass::Titlecase
+    } else if c.is_lowercase() {
+        CharClass::Lowercase
+    } else if c.is_uppercase() {
+        CharClass::Uppercase
+    } else if in_ranges(c, &CJK_IDEOGRAPHS) {
+        CharClass::CjkIdeograph
+    } else if c.is_alphabetic() {
+        CharClass::OtherLetter
+    } else if c.is_ascii_punctuation() {
+        // ASCII 裡 $ + < = > ^ ` | ~ 在 Unicode 是符號，其他是標點
+        if "$+<=>^`|~".contains(c) {
+            CharClass::Symbol
+        } else {
+            CharClass::Punctuation
+        }
+    } else if in_ranges(c, &PUNCTUATION) {
+        CharClass::Punctuation
+    } else if in_ranges(c, &EMOJI) {
+        CharClass::Emoji
+    } else if in_ranges(c, &SYMBOLS) {
+        CharClass::Symbol
+    } else if in_ranges(c, &MARKS) {
+        CharClass::Mark
+    } else {
+        CharClass::Other
+    }
+}
+
+impl CharClass {
+    pub fn of(c: char) -> CharClass {
+        classify(c)
+    }
+
+    pub fn is_letter(&self) -> bool {
+        matches!(
+            self,
+            CharClass::Lowercase
+                | CharClass::Uppercase
+                | CharClass::Titlecase
+                | CharClass::CjkIdeograph
+                | CharClass::OtherLetter
+        )
+    }
+
+    // 報表裡數字依書寫系統分列，不依值分列
+    fn group(self) -> CharClass {
+        match self {
+            CharClass::Digit { script, .. } => CharClass::Digit { script, value: 0 },
+            other => other,
+        }
+    }
+}
+
+// 和 chapter6::classify 用同樣的說法
+impl fmt::Display for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CharClass::Lowercase => write!(f, "小寫字母"),
+            CharClass::Uppercase => write!(f, "大寫字母"),
+            CharClass::Titlecase => write!(f, "標題字母"),
+            CharClass::CjkIdeograph => write!(f, "漢字"),
+            CharClass::OtherLetter => write!(f, "其他字母"),
+            CharClass::Digit { script, .. } => write!(f, "數字({:?})", script),
+            CharClass::Numeric => write!(f, "其他數字"),
+            CharClass::Whitespace => write!(f, "空白"),
+            CharClass::Punctuation => write!(f, "標點"),
+            CharClass::Symbol => write!(f, "符號"),
+            CharClass::Emoji => write!(f, "表情符號"),
+            CharClass::Mark => write!(f, "組合記號"),
+            CharClass::Format => write!(f, "格式字元"),
+            CharClass::Control => write!(f, "控制字元"),
+            CharClass::Other => write!(f, "其他"),
+        }
+    }
+}
+
+const MAX_SAMPLES: usize = 8;
+
+// 每個分類出現幾次，並記下前幾個不重複的字元當例子
+#[derive(Debug, Clone, Default)]
+pub struct Histogram {
+    counts: BTreeMap<CharClass, (usize, Vec<char>)>,
+    total: usize,
+}
+
+impl Histogram {
+    pub fn new() -> Histogram {
+        Histogram::default()
+    }
+
+    pub fn of(s: &str) -> Histogram {
+        let mut histogram = Histogram::new();
+        histogram.add_str(s);
+        histogram
+    }
+
+    pub fn add(&mut self, c: char) {
+        let (count, samples) = self.counts.entry(classify(c).group()).or_default();
+        *count += 1;
+        if samples.len() < MAX_SAMPLES && !samples.contains(&c) {
+            samples.push(c);
+        }
+        self.total += 1;
+    }
+
+    pub fn add_str(&mut self, s: &str) {
+        s.chars().for_each(|c| self.add(c));
+    }
+
+    // 數字只看書寫系統，value 會被忽略
+    pub fn count(&self, class: CharClass) -> usize {
+        self.counts
+            .get(&class.group())
+            .map_or(0, |(count, _)| *count)
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (CharClass, usize)> + '_ {
+        self.counts
+            .iter()
+            .map(|(class, (count, _))| (*class, *count))
+    }
+}
+
+// 跳脫看不見的字元，讓例子印得出來
+fn sample(c: char) -> String {
+    match classify(c) {
+        CharClass::Whitespace | CharClass::Control | CharClass::Format | CharClass::Mark => {
+            c.escape_unicode().to_string()
+        }
+        _ => c.to_string(),
+    }
+}
+
+// 標籤裡的中文在終端機佔兩格，補空白時要算進去
+fn pad(label: &str, width: usize) -> String {
+    let used: usize = label
+        .chars()
+        .map(|c| if c.is_ascii() { 1 } else { 2 })
+        .sum();
+    format!("{}{}", label, " ".repeat(width.saturating_sub(used)))
+}
+
+// 一行一個分類：名稱、次數、百分比、長條、例子
+impl fmt::Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "共 {} 個字元", self.total)?;
+        for (class, (count, samples)) in &self.counts {
+            let percent = *count as f64 * 100.0 / self.total as f64;
+            let samples: Vec<String> = samples.iter().map(|&c| sample(c)).collect();
+            writeln!(
+                f,
+                "  {} {:>5} {:>5.1}% {:<20} {}",
+                pad(&class.to_string(), 24),
+                count,
+                percent,
+                "█".repeat((percent / 5.0).ceil() as usize),
+                samples.join(" ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub fn main() {
+    println!("✅ 單一字元");
+    for c in [
+        'a', 'A', '0', '9', '@', 'é', 'Ω', 'ǅ', '中', '々', 'あ', '한', '٣', '३', '୩', '෧', '᥏',
+        '６', '𝟝', 'Ⅷ', '½', '〇', '，', '。', '「', '¢', '×', '€', '→', '✅', '👍', '\u{301}',
+        '\u{200D}', '\u{3000}', '\n', '\u{7}', '\u{E000}',
+    ] {
+        match classify(c) {
+            CharClass::Digit { script, value } => {
+                println!("{:<12} 數字 {} ({:?})", c.escape_debug(), value, script)
+            }
+            class => println!("{:<12} {}", c.escape_debug(), class),
+        }
+    }
+
+    println!("✅ Histogram");
+    for text in [
+        "✅範圍模式 \n",
+        "來自 NewYork 州的 Quarter！",
+        "select A1: Water，找零 Dime × 1 = 10¢",
+        "金額不足：需要 90¢，已投入 25¢",
+        "٣ + ३ = ６ ≠ Ⅵ, ½",
+        "Ǆ ǅ ǆ — ᾈ",
+        "👍🏽 👨\u{200D}👩\u{200D}👧 e\u{301} 1\u{FE0F}\u{20E3}",
+    ] {
+        println!("{:?}", text);
+        print!("{}", Histogram::of(text));
+    }
+
+    println!("✅ 本專案的原始碼");
+    // 把這個檔案自己當成輸入：中文註解、全形標點與表格都在裡面
+    let histogram = Histogram::of(include_str!("chapter6_14.rs"));
+    print!("{}", histogram);
+
+    println!("✅ 所有 Unicode 字元");
+    let mut all = Histogram::new();
+    for c in (0..=0x10FFFF).filter_map(char::from_u32) {
+        all.add(c);
+    }
+    for (class, count) in all.iter() {
+        println!("  {} {}", pad(&class.to_string(), 28), count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digit(script: DigitScript, value: u8) -> CharClass {
+        CharClass::Digit { script, value }
+    }
+
+    #[test]
+    fn ascii_matches_chapter6_classify() {
+        // ASCII 範圍內分類結果要和原本的範圍模式相同
+        for c in (0u8..128).map(char::from) {
+            let class = classify(c);
+            assert_eq!(CharClass::of(c), class);
+            match c {
+                'a'..='z' => assert_eq!(class, CharClass::Lowercase),
+                'A'..='Z' => assert_eq!(class, CharClass::Uppercase),
+                '0'..='9' => assert_eq!(
+                    class,
+                    digit(DigitScript::Ascii, c.to_digit(10).unwrap() as u8)
+                ),
+                _ => assert!(
+                    !class.is_letter() && !matches!(class, CharClass::Digit { .. }),
+                    "{:?}: {:?}",
+                    c,
+                    class
+                ),
+            }
+        }
+        assert_eq!(classify(' '), CharClass::Whitespace);
+        assert_eq!(classify('\n'), CharClass::Whitespace);
+        assert_eq!(classify('\u{7}'), CharClass::Control);
+        assert_eq!(classify('!'), CharClass::Punctuation);
+        assert_eq!(classify('$'), CharClass::Symbol);
+        assert_eq!(classify('~'), CharClass::Symbol);
+    }
+
+    #[test]
+    fn every_digit_block_maps_to_its_values() {
+        for (zero, script) in DIGIT_ZEROS {
+            for value in 0..10u8 {
+                let c = char::from_u32(zero + value as u32).unwrap();
+                assert!(c.is_numeric(), "U+{:04X}", c as u32);
+                assert_eq!(classify(c), digit(script, value), "U+{:04X}", c as u32);
+            }
+        }
+        // 區段不重疊，而且依碼位排列
+        for pair in DIGIT_ZEROS.windows(2) {
+            assert!(pair[0].0 + 10 <= pair[1].0, "{:?}", pair);
+        }
+        // 區段的前一個字元不能是同一組的 0-8，後一個不能是 1-9
+        // （數學數字的五組緊接在一起，所以前後可以是另一組的 9 和 0）
+        for (zero, script) in DIGIT_ZEROS {
+            let neighbors = [(zero - 1, 9), (zero + 10, 0)];
+            for (code, allowed) in neighbors {
+                let class = char::from_u32(code).map(classify);
+                if let Some(CharClass::Digit {
+                    script: other,
+                    value,
+                }) = class
+                {
+                    assert!(other != script || value == allowed, "U+{:04X}", code);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn digit_values() {
+        assert_eq!(classify('٣'), digit(DigitScript::ArabicIndic, 3));
+        assert_eq!(classify('३'), digit(DigitScript::Devanagari, 3));
+        assert_eq!(classify('୩'), digit(DigitScript::Odia, 3));
+        assert_eq!(classify('෧'), digit(DigitScript::SinhalaLith, 1));
+        assert_eq!(classify('᥏'), digit(DigitScript::Limbu, 9));
+        assert_eq!(classify('᧐'), digit(DigitScript::NewTaiLue, 0));
+        assert_eq!(classify('᭗'), digit(DigitScript::Balinese, 7));
+        assert_eq!(classify('６'), digit(DigitScript::Fullwidth, 6));
+        // 五組數學數字的值都從 0 開始
+        assert_eq!(classify('𝟎'), digit(DigitScript::Mathematical, 0));
+        assert_eq!(classify('𝟝'), digit(DigitScript::Mathematical, 5));
+        assert_eq!(classify('𝟿'), digit(DigitScript::Mathematical, 9));
+        // 不是十進位的數字
+        for c in ['½', 'Ⅷ', 'ⅷ', '①', '〇', '²', '፩'] {
+            assert_eq!(classify(c), CharClass::Numeric, "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn digits_are_numeric() {
+        for c in (0..=0x10FFFF).filter_map(char::from_u32) {
+            if let CharClass::Digit { value, .. } = classify(c) {
+                assert!(c.is_numeric() && value < 10, "U+{:04X}", c as u32);
+            }
+        }
+    }
+
+    #[test]
+    fn letters() {
+        for c in ['ǅ', 'ǈ', 'ǋ', 'ǲ', 'ᾈ', 'ᾏ', 'ᾼ', 'ῼ'] {
+            assert_eq!(classify(c), CharClass::Titlecase, "{:?}", c);
+        }
+        assert_eq!(classify('Ǆ'), CharClass::Uppercase);
+        assert_eq!(classify('ǆ'), CharClass::Lowercase);
+        assert_eq!(classify('é'), CharClass::Lowercase);
+        assert_eq!(classify('Ω'), CharClass::Uppercase);
+        assert_eq!(classify('Ａ'), CharClass::Uppercase);
+
+        for c in ['中', '文', '㐀', '豈', '𠀀', '𰀀'] {
+            assert_eq!(classify(c), CharClass::CjkIdeograph, "{:?}", c);
+        }
+        for c in ['々', 'あ', 'カ', '한', 'ع', 'א'] {
+            assert_eq!(classify(c), CharClass::OtherLetter, "{:?}", c);
+        }
+        assert!(CharClass::Titlecase.is_letter());
+        assert!(CharClass::CjkIdeograph.is_letter());
+        assert!(!digit(DigitScript::Ascii, 1).is_letter());
+    }
+
+    #[test]
+    fn fullwidth_punctuation_and_symbols() {
+        for c in "，。、：；！？（）「」『』《》—…・".chars() {
+            assert_eq!(classify(c), CharClass::Punctuation, "{:?}", c);
+        }
+        for c in ['＄', '＋', '￥', '¢', '×', '€', '→', '℃', '┌'] {
+            assert_eq!(classify(c), CharClass::Symbol, "{:?}", c);
+        }
+        assert_eq!(classify('\u{3000}'), CharClass::Whitespace);
+    }
+
+    #[test]
+    fn emoji_format_and_marks() {
+        for c in ['✅', '👍', '🏽', '👨', '🀄', '⭐'] {
+            assert_eq!(classify(c), CharClass::Emoji, "{:?}", c);
+        }
+        for c in ['\u{200D}', '\u{200B}', '\u{FE0F}', '\u{FEFF}', '\u{AD}'] {
+            assert_eq!(classify(c), CharClass::Format, "{:?}", c);
+        }
+        for c in ['\u{301}', '\u{20E3}', '\u{1DC0}', '\u{FE20}'] {
+            assert_eq!(classify(c), CharClass::Mark, "{:?}", c);
+        }
+        assert_eq!(classify('\u{E000}'), CharClass::Other);
+
+        // ZWJ 家庭是三個 emoji 加兩個 ZWJ；keycap 是數字、變體選擇符、組合記號
+        let family = Histogram::of("👨\u{200D}👩\u{200D}👧");
+        assert_eq!(family.count(CharClass::Emoji), 3);
+        assert_eq!(family.count(CharClass::Format), 2);
+        let keycap: Vec<CharClass> = "1\u{FE0F}\u{20E3}".chars().map(classify).collect();
+        assert_eq!(
+            keycap,
+            [
+                digit(DigitScript::Ascii, 1),
+                CharClass::Format,
+                CharClass::Mark
+            ]
+        );
+    }
+
+    #[test]
+    fn histogram_counts() {
+        let histogram = Histogram::of("a1٣ ３2b，");
+        assert_eq!(histogram.total(), 8);
+        assert_eq!(histogram.count(CharClass::Lowercase), 2);
+        // 數字依書寫系統分列，value 會被忽略
+        assert_eq!(histogram.count(digit(DigitScript::Ascii, 7)), 2);
+        assert_eq!(histogram.count(digit(DigitScript::ArabicIndic, 0)), 1);
+        assert_eq!(histogram.count(digit(DigitScript::Fullwidth, 3)), 1);
+        assert_eq!(histogram.count(CharClass::Punctuation), 1);
+        assert_eq!(histogram.count(CharClass::Emoji), 0);
+
+        let empty = Histogram::new();
+        assert_eq!(empty.total(), 0);
+        assert_eq!(empty.iter().count(), 0);
+        assert_eq!(empty.to_string(), "共 0 個字元\n");
+    }
+
+    #[test]
+    fn histogram_totals_match() {
+        for text in [
+            include_str!("chapter6_14.rs"),
+            "來自 NewYork 州的 Quarter！",
+            "👍🏽 👨\u{200D}👩\u{200D}👧 e\u{301} 1\u{FE0F}\u{20E3}",
+            "",
+        ] {
+            let histogram = Histogram::of(text);
+            assert_eq!(histogram.total(), text.chars().count());
+            assert_eq!(
+                histogram.iter().map(|(_, n)| n).sum::<usize>(),
+                histogram.total()
+            );
+            // 報表每個分類一行，再加上總數那一行
+            assert_eq!(
+                histogram.to_string().lines().count(),
+                histogram.iter().count() + 1
+            );
+        }
+    }
+
+    #[test]
+    fn samples_are_unique_and_escaped() {
+        let mut histogram = Histogram::new();
+        histogram.add_str("aaaa\u{200D}bcdefghijk");
+        let report = histogram.to_string();
+        assert!(report.contains("a b c d e f g h\n"), "{}", report);
+        assert!(report.contains("\\u{200d}"), "{}", report);
+        assert_eq!(classify('a').to_string(), "小寫字母");
+        assert_eq!(digit(DigitScript::Odia, 3).to_string(), "數字(Odia)");
+    }
+}
@@ -0,0 +1,306 @@
+// Status Lifecycle（狀態機與合法的狀態轉換）
+use crate::chapter6::Status;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitionError {
+    pub from: Status,
+    pub to: Status,
+}
+
+impl fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "不允許的狀態轉換: {:?} -> {:?}", self.from, self.to)
+    }
+}
+
+impl std::error::Error for TransitionError {}
+
+impl Status {
+    // Pending -> Success | Error；開啟 retry 時 Error -> Pending
+    // Success 是終點，不能再改變
+    pub fn can_transition_to(self, to: Status, allow_retry: bool) -> bool {
+        matches!(
+            (self, to),
+            (Status::Pending, Status::Success) | (Status::Pending, Status::Error)
+        ) || (allow_retry && self == Status::Error && to == Status::Pending)
+    }
+
+    pub fn transition(self, to: Status, allow_retry: bool) -> Result<Status, TransitionError> {
+        if self.can_transition_to(to, allow_retry) {
+            Ok(to)
+        } else {
+            Err(TransitionError { from: self, to })
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Transition {
+    pub from: Status,
+    pub to: Status,
+    pub at: SystemTime,
+    pub reason: Option<String>, // 轉成 Error 時的原因
+}
+
+// 把狀態附加到任何工作上
+#[derive(Debug, Clone)]
+pub struct Tracked<T> {
+    pub value: T,
+    status: Status,
+    allow_retry: bool,
+    created_at: SystemTime,
+    history: Vec<Transition>,
+}
+
+impl<T> Tracked<T> {
+    pub fn new(value: T) -> Tracked<T> {
+        Tracked {
+            value,
+            status: Status::Pending,
+            allow_retry: false,
+            created_at: SystemTime::now(),
+            history: Vec::new(),
+        }
+    }
+
+    pub fn with_retry(mut self) -> Tracked<T> {
+        self.allow_retry = true;
+        self
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn created_at(&self) -> SystemTime {
+        self.created_at
+    }
+
+    pub fn history(&self) -> &[Transition] {
+        &self.history
+    }
+
+    // 失敗時狀態與歷史都不變
+    pub fn transition_at(
+        &mut self,
+        to: Status,
+        reason: Option<String>,
+        at: SystemTime,
+    ) -> Result<(), TransitionError> {
+        let from = self.status;
+        self.status = from.transition(to, self.allow_retry)?;
+        self.history.push(Transition {
+            from,
+            to,
+            at,
+            reason,
+        });
+        Ok(())
+    }
+
+    pub fn succeed(&mut self) -> Result<(), TransitionError> {
+        self.transition_at(Status::Success, None, SystemTime::now())
+    }
+
+    pub fn fail(&mut self, reason: &str) -> Result<(), TransitionError> {
+        self.transition_at(Status::Error, Some(reason.to_string()), SystemTime::now())
+    }
+
+    pub fn retry(&mut self) -> Result<(), TransitionError> {
+        self.transition_at(Status::Pending, None, SystemTime::now())
+    }
+
+    pub fn last_error(&self) -> Option<&str> {
+        self.history
+            .iter()
+            .rev()
+            .find(|t| t.to == Status::Error)
+            .and_then(|t| t.reason.as_deref())
+    }
+
+    // 第幾次嘗試：每次 retry 加 1
+    pub fn attempts(&self) -> usize {
+        1 + self
+            .history
+            .iter()
+            .filter(|t| t.to == Status::Pending)
+            .count()
+    }
+
+    // 從建立到現在（或到結束）經過的時間
+    pub fn elapsed(&self) -> Duration {
+        let end = match self.status {
+            Status::Pending => SystemTime::now(),
+            _ => self.history.last().map(|t| t.at).unwrap_or(self.created_at),
+        };
+        end.duration_since(self.created_at).unwrap_or_default()
+    }
+}
+
+#[derive(Debug)]
+struct Job {
+    name: String,
+}
+
+pub fn main() {
+    println!("✅ Status transitions");
+    let all = [Status::Pending, Status::Success, Status::Error];
+    for from in all {
+        for to in all {
+            println!(
+                "{:?} -> {:?}: {} (retry: {})",
+                from,
+                to,
+                from.can_transition_to(to, false),
+                from.can_transition_to(to, true)
+            );
+        }
+    }
+    println!("{:?}", Status::Success.transition(Status::Pending, true));
+
+    println!("✅ Tracked<T>");
+    let mut job = Tracked::new(Job {
+        name: String::from("backup"),
+    })
+    .with_retry();
+    job.fail("disk full").unwrap();
+    job.retry().unwrap();
+    job.fail("timeout").unwrap();
+    job.retry().unwrap();
+    job.succeed().unwrap();
+    println!("{} status: {:?}", job.value.name, job.status());
+    println!(
+        "attempts: {}, last error: {:?}",
+        job.attempts(),
+        job.last_error()
+    );
+    for t in job.history() {
+        println!("  {:?} -> {:?} reason: {:?}", t.from, t.to, t.reason);
+    }
+    if let Err(err) = job.fail("too late") {
+        println!("{}", err);
+    }
+
+    let mut once = Tracked::new("send email");
+    once.fail("smtp down").unwrap();
+    println!("retry without with_retry: {:?}", once.retry());
+    println!(
+        "status still: {:?}, elapsed: {:?}",
+        once.status(),
+        once.elapsed()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Status; 3] = [Status::Pending, Status::Success, Status::Error];
+
+    #[test]
+    fn transition_matrix() {
+        for from in ALL {
+            for to in ALL {
+                for allow_retry in [false, true] {
+                    let allowed =
+                        matches!(
+                            (from, to),
+                            (Status::Pending, Status::Success) | (Status::Pending, Status::Error)
+                        ) || (allow_retry && from == Status::Error && to == Status::Pending);
+                    assert_eq!(
+                        from.can_transition_to(to, allow_retry),
+                        allowed,
+                        "{:?} -> {:?} (retry: {})",
+                        from,
+                        to,
+                        allow_retry
+                    );
+                    let expected = if allowed {
+                        Ok(to)
+                    } else {
+                        Err(TransitionError { from, to })
+                    };
+                    assert_eq!(from.transition(to, allow_retry), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn success_is_terminal() {
+        for to in ALL {
+            assert!(!Status::Success.can_transition_to(to, true));
+        }
+    }
+
+    #[test]
+    fn tracked_history_and_last_error() {
+        let mut job = Tracked::new("backup").with_retry();
+        assert_eq!(job.status(), Status::Pending);
+        assert_eq!(job.last_error(), None);
+        assert_eq!(job.attempts(), 1);
+
+        job.fail("disk full").unwrap();
+        assert_eq!(job.last_error(), Some("disk full"));
+        job.retry().unwrap();
+        // retry 之後仍保留上一次的錯誤
+        assert_eq!(job.last_error(), Some("disk full"));
+        job.fail("timeout").unwrap();
+        job.retry().unwrap();
+        job.succeed().unwrap();
+
+        assert_eq!(job.status(), Status::Success);
+        assert_eq!(job.attempts(), 3);
+        assert_eq!(job.last_error(), Some("timeout"));
+        let steps: Vec<_> = job
+            .history()
+            .iter()
+            .map(|t| (t.from, t.to, t.reason.as_deref()))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (Status::Pending, Status::Error, Some("disk full")),
+                (Status::Error, Status::Pending, None),
+                (Status::Pending, Status::Error, Some("timeout")),
+                (Status::Error, Status::Pending, None),
+                (Status::Pending, Status::Success, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejected_transitions_leave_state_unchanged() {
+        let mut once = Tracked::new("send email");
+        once.fail("smtp down").unwrap();
+        assert_eq!(
+            once.retry(),
+            Err(TransitionError {
+                from: Status::Error,
+                to: Status::Pending
+            })
+        );
+        assert_eq!(once.status(), Status::Error);
+        assert_eq!(once.history().len(), 1);
+        assert_eq!(once.attempts(), 1);
+
+        let mut done = Tracked::new(()).with_retry();
+        done.succeed().unwrap();
+        assert!(done.fail("too late").is_err());
+        assert!(done.succeed().is_err());
+        assert_eq!(done.status(), Status::Success);
+        assert_eq!(done.history().len(), 1);
+        assert_eq!(done.last_error(), None);
+    }
+
+    #[test]
+    fn elapsed_stops_at_the_final_transition() {
+        let mut job = Tracked::new(());
+        let end = job.created_at() + Duration::from_secs(5);
+        job.transition_at(Status::Success, None, end).unwrap();
+        assert_eq!(job.elapsed(), Duration::from_secs(5));
+        assert_eq!(job.history()[0].at, end);
+    }
+}
@@ -0,0 +1,392 @@
+// IP Address（解析、格式化與分類 IpAddr）
+use crate::chapter6::IpAddr;
+use std::fmt;
+use std::net;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IpParseError {
+    Empty,
+    InvalidV4(String),
+    InvalidV6(String),
+}
+
+impl fmt::Display for IpParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IpParseError::Empty => write!(f, "IP 位址是空的"),
+            IpParseError::InvalidV4(reason) => write!(f, "無效的 IPv4 位址: {}", reason),
+            IpParseError::InvalidV6(reason) => write!(f, "無效的 IPv6 位址: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for IpParseError {}
+
+// 不接受前導 0（例如 "01"），避免和八進位寫法混淆
+fn parse_v4(s: &str) -> Result<[u8; 4], IpParseError> {
+    let invalid = |reason: &str| IpParseError::InvalidV4(format!("{:?} {}", s, reason));
+    let parts: Vec<&str> = s.split('.').collect();
+    if parts.len() != 4 {
+        return Err(invalid("需要四段"));
+    }
+    let mut octets = [0u8; 4];
+    for (i, part) in parts.iter().enumerate() {
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid("每段必須是數字"));
+        }
+        if part.len() > 1 && part.starts_with('0') {
+            return Err(invalid("不能有前導 0"));
+        }
+        octets[i] = part.parse().map_err(|_| invalid("每段必須是 0-255"))?;
+    }
+    Ok(octets)
+}
+
+// 解析以 ':' 分隔的多組十六進位，最後一組可以是內嵌的 IPv4
+fn parse_groups(s: &str, allow_v4: bool) -> Result<Vec<u16>, IpParseError> {
+    let invalid = |reason: &str| IpParseError::InvalidV6(reason.to_string());
+    if s.is_empty() {
+        return Ok(Vec::new());
+    }
+    let parts: Vec<&str> = s.split(':').collect();
+    let mut groups = Vec::new();
+    for (i, part) in parts.iter().enumerate() {
+        if allow_v4 && i == parts.len() - 1 && part.contains('.') {
+            let [a, b, c, d] = parse_v4(part).map_err(|_| invalid("內嵌的 IPv4 格式錯誤"))?;
+            groups.push(u16::from_be_bytes([a, b]));
+            groups.push(u16::from_be_bytes([c, d]));
+        } else if part.is_empty() || part.len() > 4 {
+            return Err(invalid("每組必須是 1 到 4 個十六進位數字"));
+        } else if !part.bytes().all(|b| b.is_ascii_hexdigit()) {
+            // from_str_radix 會接受開頭的 '+'，所以先自己檢查
+            return Err(invalid("包含非十六進位字元"));
+        } else {
+            groups.push(u16::from_str_radix(part, 16).unwrap());
+        }
+    }
+    Ok(groups)
+}
+
+fn parse_v6(s: &str) -> Result<[u16; 8], IpParseError> {
+    let invalid = |reason: &str| IpParseError::InvalidV6(reason.to_string());
+    let mut segments = [0u16; 8];
+    match s.split_once("::") {
+        // "::" 代表一組或多組 0，只能出現一次
+        Some((head, tail)) => {
+            if tail.contains("::") {
+                return Err(invalid("\"::\" 只能出現一次"));
+            }
+            let head = parse_groups(head, false)?;
+            let tail = parse_groups(tail, true)?;
+            if head.len() + tail.len() > 7 {
+                return Err(invalid("太多組"));
+            }
+            segments[..head.len()].copy_from_slice(&head);
+            segments[8 - tail.len()..].copy_from_slice(&tail);
+        }
+        None => {
+            let groups = parse_groups(s, true)?;
+            if groups.len() != 8 {
+                return Err(invalid("需要八組，或使用 \"::\" 省略"));
+            }
+            segments.copy_from_slice(&groups);
+        }
+    }
+    Ok(segments)
+}
+
+impl FromStr for IpAddr {
+    type Err = IpParseError;
+
+    fn from_str(s: &str) -> Result<IpAddr, IpParseError> {
+        if s.is_empty() {
+            Err(IpParseError::Empty)
+        } else if s.contains(':') {
+            Ok(IpAddr::V6(parse_v6(s)?))
+        } else {
+            let [a, b, c, d] = parse_v4(s)?;
+            Ok(IpAddr::V4(a, b, c, d))
+        }
+    }
+}
+
+// 最長的一串連續 0（至少兩組），平手取前面的：(起點, 長度)
+fn longest_zero_run(segments: &[u16; 8]) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize)> = None;
+    let mut i = 0;
+    while i < 8 {
+        if segments[i] == 0 {
+            let start = i;
+            while i < 8 && segments[i] == 0 {
+                i += 1;
+            }
+            let len = i - start;
+            if len >= 2 && best.map(|(_, l)| len > l).unwrap_or(true) {
+                best = Some((start, len));
+            }
+        } else {
+            i += 1;
+        }
+    }
+    best
+}
+
+fn join_hex(segments: &[u16]) -> String {
+    segments
+        .iter()
+        .map(|s| format!("{:x}", s))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+// IPv6 依照 RFC 5952 的標準格式：小寫、去掉前導 0、壓縮最長的 0
+impl fmt::Display for IpAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IpAddr::V4(a, b, c, d) => write!(f, "{}.{}.{}.{}", a, b, c, d),
+            IpAddr::V6(segments) => {
+                // IPv4-mapped（::ffff:a.b.c.d）保留 IPv4 寫法
+                if segments[..6] == [0, 0, 0, 0, 0, 0xffff] {
+                    let [a, b] = segments[6].to_be_bytes();
+                    let [c, d] = segments[7].to_be_bytes();
+                    return write!(f, "::ffff:{}.{}.{}.{}", a, b, c, d);
+                }
+                match longest_zero_run(segments) {
+                    Some((start, len)) => write!(
+                        f,
+                        "{}::{}",
+                        join_hex(&segments[..start]),
+                        join_hex(&segments[start + len..])
+                    ),
+                    None => write!(f, "{}", join_hex(segments)),
+                }
+            }
+        }
+    }
+}
+
+impl From<IpAddr> for net::IpAddr {
+    fn from(ip: IpAddr) -> net::IpAddr {
+        match ip {
+            IpAddr::V4(a, b, c, d) => net::IpAddr::V4(net::Ipv4Addr::new(a, b, c, d)),
+            IpAddr::V6(s) => net::IpAddr::V6(net::Ipv6Addr::from(s)),
+        }
+    }
+}
+
+impl From<net::IpAddr> for IpAddr {
+    fn from(ip: net::IpAddr) -> IpAddr {
+        match ip {
+            net::IpAddr::V4(v4) => {
+                let [a, b, c, d] = v4.octets();
+                IpAddr::V4(a, b, c, d)
+            }
+            net::IpAddr::V6(v6) => IpAddr::V6(v6.segments()),
+        }
+    }
+}
+
+impl IpAddr {
+    pub fn is_loopback(&self) -> bool {
+        match self {
+            IpAddr::V4(a, ..) => *a == 127,
+            IpAddr::V6(s) => *s == [0, 0, 0, 0, 0, 0, 0, 1],
+        }
+    }
+
+    // IPv4: 10/8、172.16/12、192.168/16；IPv6: fc00::/7（Unique Local）
+    pub fn is_private(&self) -> bool {
+        match *self {
+            IpAddr::V4(a, b, ..) => {
+                a == 10 || (a == 172 && (16..=31).contains(&b)) || (a == 192 && b == 168)
+            }
+            IpAddr::V6(s) => s[0] & 0xfe00 == 0xfc00,
+        }
+    }
+
+    // IPv4: 224/4；IPv6: ff00::/8
+    pub fn is_multicast(&self) -> bool {
+        match *self {
+            IpAddr::V4(a, ..) => (224..=239).contains(&a),
+            IpAddr::V6(s) => s[0] & 0xff00 == 0xff00,
+        }
+    }
+
+    // IPv4: 169.254/16；IPv6: fe80::/10
+    pub fn is_link_local(&self) -> bool {
+        match *self {
+            IpAddr::V4(a, b, ..) => a == 169 && b == 254,
+            IpAddr::V6(s) => s[0] & 0xffc0 == 0xfe80,
+        }
+    }
+}
+
+pub fn main() {
+    println!("✅ Parse and Display");
+    for input in [
+        "127.0.0.1",
+        "::1",
+        "::",
+        "2001:0DB8:0000:0000:0000:ff00:0042:8329",
+        "2001:db8:0:1:0:0:0:1",
+        "2001:0:0:1:0:0:0:1", // 兩串一樣長，壓縮前面那串
+        "1:0:2:3:4:5:6:7",    // 只有一組 0 不壓縮
+        "::ffff:192.168.1.1",
+        "64:ff9b::10.0.0.1",
+        "fe80::1%eth0",
+        "1::2::3",
+        "256.0.0.1",
+        "01.2.3.4",
+        "1:2:3:4:5:6:7:8:9",
+    ] {
+        match input.parse::<IpAddr>() {
+            Ok(ip) => {
+                // 和標準庫的格式比對
+                let std_ip: net::IpAddr = ip.into();
+                println!("{} -> {} (std: {})", input, ip, std_ip);
+            }
+            Err(err) => println!("{} -> {}", input, err),
+        }
+    }
+
+    println!("✅ Classification");
+    for input in [
+        "127.0.0.1",
+        "10.1.2.3",
+        "172.20.0.1",
+        "192.168.0.1",
+        "8.8.8.8",
+        "224.0.0.1",
+        "169.254.1.1",
+        "::1",
+        "fd00::1",
+        "ff02::1",
+        "fe80::1",
+        "2001:db8::1",
+    ] {
+        let ip: IpAddr = input.parse().unwrap();
+        println!(
+            "{:<12} loopback: {:<5} private: {:<5} multicast: {:<5} link-local: {}",
+            ip.to_string(),
+            ip.is_loopback(),
+            ip.is_private(),
+            ip.is_multicast(),
+            ip.is_link_local()
+        );
+    }
+
+    println!("✅ std::net 轉換");
+    let std_ip: net::IpAddr = "2001:db8::8a2e:370:7334".parse().unwrap();
+    let ip = IpAddr::from(std_ip);
+    println!("{:?} -> {}", ip, ip);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_signs_in_v6_groups() {
+        for input in [
+            "2001:db8::+1",
+            "+1::",
+            "::+ffff",
+            "1:2:3:4:5:6:7:+8",
+            "2001:db8::-1",
+        ] {
+            assert!(
+                matches!(input.parse::<IpAddr>(), Err(IpParseError::InvalidV6(_))),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_valid_v6_groups() {
+        assert_eq!(
+            "2001:DB8::1".parse::<IpAddr>(),
+            Ok(IpAddr::V6([0x2001, 0xdb8, 0, 0, 0, 0, 0, 1]))
+        );
+        assert_eq!(
+            "::ffff:192.0.2.1".parse::<IpAddr>(),
+            Ok(IpAddr::V6([0, 0, 0, 0, 0, 0xffff, 0xc000, 0x0201]))
+        );
+    }
+
+    fn v6(segments: [u16; 8]) -> String {
+        IpAddr::V6(segments).to_string()
+    }
+
+    #[test]
+    fn display_compresses_the_longest_zero_run() {
+        assert_eq!(v6([0x2001, 0xdb8, 0, 1, 0, 0, 0, 1]), "2001:db8:0:1::1");
+        assert_eq!(v6([1, 0, 0, 2, 0, 0, 0, 3]), "1:0:0:2::3");
+        // 一樣長時壓縮前面那串
+        assert_eq!(v6([0x2001, 0, 0, 1, 0, 0, 1, 1]), "2001::1:0:0:1:1");
+        assert_eq!(v6([1, 0, 0, 0, 2, 0, 0, 0]), "1::2:0:0:0");
+    }
+
+    #[test]
+    fn display_keeps_a_single_zero_group() {
+        assert_eq!(v6([1, 0, 2, 3, 4, 5, 6, 7]), "1:0:2:3:4:5:6:7");
+        assert_eq!(v6([0, 1, 0, 2, 0, 3, 0, 4]), "0:1:0:2:0:3:0:4");
+    }
+
+    #[test]
+    fn display_edges() {
+        assert_eq!(v6([0; 8]), "::");
+        assert_eq!(v6([0, 0, 0, 0, 0, 0, 0, 1]), "::1");
+        assert_eq!(v6([1, 0, 0, 0, 0, 0, 0, 0]), "1::");
+        assert_eq!(v6([0xABCD, 0x0DB8, 0, 0, 0, 0, 0, 0x00FF]), "abcd:db8::ff");
+        assert_eq!(
+            v6([0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff]),
+            "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"
+        );
+    }
+
+    #[test]
+    fn display_ipv4_mapped() {
+        assert_eq!(
+            v6([0, 0, 0, 0, 0, 0xffff, 0xc000, 0x0201]),
+            "::ffff:192.0.2.1"
+        );
+        assert_eq!(v6([0, 0, 0, 0, 0, 0xffff, 0, 0]), "::ffff:0.0.0.0");
+        // 只有 ::ffff:0:0/96 用 IPv4 寫法
+        assert_eq!(
+            v6([0, 0, 0, 0, 0, 0xfffe, 0xc000, 0x0201]),
+            "::fffe:c000:201"
+        );
+        assert_eq!(
+            v6([0, 0, 0, 0, 1, 0xffff, 0xc000, 0x0201]),
+            "::1:ffff:c000:201"
+        );
+    }
+
+    #[test]
+    fn display_matches_std_and_round_trips() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(39);
+        for _ in 0..10_000 {
+            // 大部分是 0，才會常常出現長短不一的 0 串
+            let mut segments = [0u16; 8];
+            for s in segments.iter_mut() {
+                if rng.random_bool(0.4) {
+                    *s = rng.random();
+                }
+            }
+            if rng.random_bool(0.05) {
+                segments[..6].copy_from_slice(&[0, 0, 0, 0, 0, 0xffff]);
+            }
+            let ip = IpAddr::V6(segments);
+            let text = ip.to_string();
+            assert_eq!(text, net::Ipv6Addr::from(segments).to_string());
+            assert_eq!(text.parse::<IpAddr>(), Ok(ip));
+            let std_ip: net::IpAddr = text.parse().unwrap();
+            assert_eq!(IpAddr::from(std_ip), ip);
+        }
+    }
+}
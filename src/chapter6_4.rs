@@ -0,0 +1,626 @@
+// CIDR（子網路計算）
+use crate::chapter6::IpAddr;
+use std::fmt;
+use std::str::FromStr;
+
+// 位址轉成整數比較好計算，IPv4 只用低 32 位元
+impl IpAddr {
+    pub fn bit_width(&self) -> u32 {
+        match self {
+            IpAddr::V4(..) => 32,
+            IpAddr::V6(_) => 128,
+        }
+    }
+
+    pub fn to_bits(self) -> u128 {
+        match self {
+            IpAddr::V4(a, b, c, d) => u32::from_be_bytes([a, b, c, d]) as u128,
+            IpAddr::V6(s) => s.iter().fold(0u128, |acc, &seg| (acc << 16) | seg as u128),
+        }
+    }
+
+    pub fn from_bits(bits: u128, v6: bool) -> IpAddr {
+        if v6 {
+            let mut segments = [0u16; 8];
+            for (i, seg) in segments.iter_mut().enumerate() {
+                *seg = (bits >> (112 - 16 * i)) as u16;
+            }
+            IpAddr::V6(segments)
+        } else {
+            let [a, b, c, d] = (bits as u32).to_be_bytes();
+            IpAddr::V4(a, b, c, d)
+        }
+    }
+
+    pub fn is_v6(&self) -> bool {
+        matches!(self, IpAddr::V6(_))
+    }
+}
+
+// 低 bits 個位元為 1
+fn low_mask(bits: u32) -> u128 {
+    if bits >= 128 {
+        u128::MAX
+    } else {
+        (1u128 << bits) - 1
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CidrError {
+    MissingPrefix,
+    InvalidAddress(String),
+    InvalidPrefix(String),
+    PrefixTooShort { prefix: u8, new_prefix: u8 },
+}
+
+impl fmt::Display for CidrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CidrError::MissingPrefix => write!(f, "缺少 /prefix"),
+            CidrError::InvalidAddress(s) => write!(f, "無效的位址: {}", s),
+            CidrError::InvalidPrefix(s) => write!(f, "無效的 prefix: {}", s),
+            CidrError::PrefixTooShort { prefix, new_prefix } => {
+                write!(f, "/{} 無法切成 /{}", prefix, new_prefix)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CidrError {}
+
+// network 一定是網段的第一個位址（host 位元全為 0）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    // host 位元會被清成 0：10.1.2.3/8 -> 10.0.0.0/8
+    pub fn new(addr: IpAddr, prefix: u8) -> Result<Cidr, CidrError> {
+        if prefix as u32 > addr.bit_width() {
+            return Err(CidrError::InvalidPrefix(prefix.to_string()));
+        }
+        let host_bits = addr.bit_width() - prefix as u32;
+        let bits = addr.to_bits() & !low_mask(host_bits);
+        Ok(Cidr {
+            network: IpAddr::from_bits(bits, addr.is_v6()),
+            prefix,
+        })
+    }
+
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    pub fn is_v6(&self) -> bool {
+        self.network.is_v6()
+    }
+
+    fn host_bits(&self) -> u32 {
+        self.network.bit_width() - self.prefix as u32
+    }
+
+    fn first_bits(&self) -> u128 {
+        self.network.to_bits()
+    }
+
+    fn last_bits(&self) -> u128 {
+        self.first_bits() | low_mask(self.host_bits())
+    }
+
+    pub fn network(&self) -> IpAddr {
+        self.network
+    }
+
+    pub fn netmask(&self) -> IpAddr {
+        let width = self.network.bit_width();
+        let mask = low_mask(width) & !low_mask(self.host_bits());
+        IpAddr::from_bits(mask, self.is_v6())
+    }
+
+    // 網段最後一個位址
+    pub fn last(&self) -> IpAddr {
+        IpAddr::from_bits(self.last_bits(), self.is_v6())
+    }
+
+    // 只有 IPv4 有廣播位址；/31、/32 沒有（RFC 3021）
+    pub fn broadcast(&self) -> Option<IpAddr> {
+        if self.is_v6() || self.prefix >= 31 {
+            None
+        } else {
+            Some(self.last())
+        }
+    }
+
+    // 位址總數；::/0 有 2^128 個，超過 u128 所以回傳 None
+    pub fn num_addresses(&self) -> Option<u128> {
+        1u128.checked_shl(self.host_bits())
+    }
+
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        addr.is_v6() == self.is_v6()
+            && addr.to_bits() >= self.first_bits()
+            && addr.to_bits() <= self.last_bits()
+    }
+
+    pub fn contains_cidr(&self, other: &Cidr) -> bool {
+        other.prefix >= self.prefix && self.contains(&other.network)
+    }
+
+    // 可用的主機範圍：IPv4 /30 以下扣掉網路與廣播位址
+    pub fn host_range(&self) -> (IpAddr, IpAddr) {
+        let (first, last) = self.host_bits_range();
+        (
+            IpAddr::from_bits(first, self.is_v6()),
+            IpAddr::from_bits(last, self.is_v6()),
+        )
+    }
+
+    fn host_bits_range(&self) -> (u128, u128) {
+        if self.broadcast().is_some() {
+            (self.first_bits() + 1, self.last_bits() - 1)
+        } else {
+            (self.first_bits(), self.last_bits())
+        }
+    }
+
+    // 逐一產生主機位址，不會先建立整個列表
+    pub fn hosts(&self) -> Hosts {
+        let (next, last) = self.host_bits_range();
+        Hosts {
+            next: Some(next),
+            last,
+            v6: self.is_v6(),
+        }
+    }
+
+    // 上一層網段：10.0.0.0/9 -> 10.0.0.0/8
+    pub fn supernet(&self) -> Option<Cidr> {
+        if self.prefix == 0 {
+            None
+        } else {
+            Cidr::new(self.network, self.prefix - 1).ok()
+        }
+    }
+
+    // 切成較小的子網段，例如 /24 切成四個 /26
+    pub fn split(&self, new_prefix: u8) -> Result<Subnets, CidrError> {
+        if new_prefix < self.prefix {
+            return Err(CidrError::PrefixTooShort {
+                prefix: self.prefix,
+                new_prefix,
+            });
+        }
+        if new_prefix as u32 > self.network.bit_width() {
+            return Err(CidrError::InvalidPrefix(new_prefix.to_string()));
+        }
+        Ok(Subnets {
+            next: Some(self.first_bits()),
+            last: self.last_bits(),
+            prefix: new_prefix,
+            v6: self.is_v6(),
+        })
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = CidrError;
+
+    fn from_str(s: &str) -> Result<Cidr, CidrError> {
+        let (addr, prefix) = s.split_once('/').ok_or(CidrError::MissingPrefix)?;
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| CidrError::InvalidAddress(addr.to_string()))?;
+        if prefix.is_empty() || !prefix.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(CidrError::InvalidPrefix(prefix.to_string()));
+        }
+        let prefix: u8 = prefix
+            .parse()
+            .map_err(|_| CidrError::InvalidPrefix(prefix.to_string()))?;
+        Cidr::new(addr, prefix)
+    }
+}
+
+pub struct Hosts {
+    next: Option<u128>, // None 代表已經結束
+    last: u128,
+    v6: bool,
+}
+
+impl Iterator for Hosts {
+    type Item = IpAddr;
+
+    fn next(&mut self) -> Option<IpAddr> {
+        let current = self.next?;
+        self.next = if current < self.last {
+            Some(current + 1)
+        } else {
+            None
+        };
+        Some(IpAddr::from_bits(current, self.v6))
+    }
+}
+
+pub struct Subnets {
+    next: Option<u128>,
+    last: u128,
+    prefix: u8,
+    v6: bool,
+}
+
+impl Iterator for Subnets {
+    type Item = Cidr;
+
+    fn next(&mut self) -> Option<Cidr> {
+        let current = self.next?;
+        let width = if self.v6 { 128 } else { 32 };
+        let block_last = current | low_mask(width - self.prefix as u32);
+        self.next = if block_last < self.last {
+            Some(block_last + 1)
+        } else {
+            None
+        };
+        Cidr::new(IpAddr::from_bits(current, self.v6), self.prefix).ok()
+    }
+}
+
+// 把 [start, end] 範圍轉成最少的 CIDR
+fn range_to_cidrs(mut start: u128, end: u128, v6: bool, result: &mut Vec<Cidr>) {
+    let width = if v6 { 128 } else { 32 };
+    loop {
+        // 起點對齊的位元數決定區塊最大能多大
+        let mut host_bits = start.trailing_zeros().min(width);
+        while host_bits > 0 && (start | low_mask(host_bits)) > end {
+            host_bits -= 1;
+        }
+        let block_last = start | low_mask(host_bits);
+        result.push(Cidr::new(IpAddr::from_bits(start, v6), (width - host_bits) as u8).unwrap());
+        if block_last >= end {
+            break;
+        }
+        start = block_last + 1;
+    }
+}
+
+// 合併重疊或相鄰的網段，IPv4 排在 IPv6 前面
+pub fn aggregate(cidrs: &[Cidr]) -> Vec<Cidr> {
+    let mut result = Vec::new();
+    for v6 in [false, true] {
+        let mut ranges: Vec<(u128, u128)> = cidrs
+            .iter()
+            .filter(|c| c.is_v6() == v6)
+            .map(|c| (c.first_bits(), c.last_bits()))
+            .collect();
+        ranges.sort();
+
+        let mut merged: Vec<(u128, u128)> = Vec::new();
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                // 重疊或剛好相鄰（last + 1 == start）
+                Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        for (start, end) in merged {
+            range_to_cidrs(start, end, v6, &mut result);
+        }
+    }
+    result
+}
+
+fn describe(cidr: &Cidr) {
+    let (first_host, last_host) = cidr.host_range();
+    println!(
+        "{}: netmask {}, last {}, broadcast {:?}, hosts {} - {}, addresses {:?}",
+        cidr,
+        cidr.netmask(),
+        cidr.last(),
+        cidr.broadcast().map(|b| b.to_string()),
+        first_host,
+        last_host,
+        cidr.num_addresses()
+    );
+}
+
+pub fn main() {
+    println!("✅ Parse");
+    for input in [
+        "10.0.0.0/8",
+        "10.1.2.3/8",
+        "10.0.0.0",
+        "10.0.0.0/33",
+        "::1/129",
+        "x/8",
+    ] {
+        match input.parse::<Cidr>() {
+            Ok(cidr) => println!("{} -> {}", input, cidr),
+            Err(err) => println!("{} -> {}", input, err),
+        }
+    }
+
+    println!("✅ Edge prefixes");
+    for input in [
+        "0.0.0.0/0",
+        "192.168.1.0/24",
+        "192.168.1.0/30",
+        "192.168.1.0/31",
+        "192.168.1.7/32",
+        "::/0",
+        "2001:db8::/64",
+        "2001:db8::1/128",
+    ] {
+        describe(&input.parse().unwrap());
+    }
+
+    println!("✅ Membership");
+    let net: Cidr = "172.16.0.0/12".parse().unwrap();
+    for ip in [
+        "172.16.0.1",
+        "172.31.255.255",
+        "172.32.0.0",
+        "::ffff:172.16.0.1",
+    ] {
+        println!("{} in {}: {}", ip, net, net.contains(&ip.parse().unwrap()));
+    }
+    let all: Cidr = "0.0.0.0/0".parse().unwrap();
+    println!(
+        "0.0.0.0/0 contains 255.255.255.255: {}",
+        all.contains(&"255.255.255.255".parse().unwrap())
+    );
+    println!(
+        "{} contains 172.20.0.0/16: {}",
+        net,
+        net.contains_cidr(&"172.20.0.0/16".parse().unwrap())
+    );
+
+    println!("✅ Hosts（lazy）");
+    let small: Cidr = "192.168.1.0/29".parse().unwrap();
+    let hosts: Vec<String> = small.hosts().map(|h| h.to_string()).collect();
+    println!("{}: {:?}", small, hosts);
+    let huge: Cidr = "::/0".parse().unwrap();
+    let first: Vec<String> = huge.hosts().take(3).map(|h| h.to_string()).collect();
+    println!("{} first 3: {:?}", huge, first);
+    let single: Cidr = "2001:db8::1/128".parse().unwrap();
+    println!("{} hosts: {}", single, single.hosts().count());
+
+    println!("✅ Split and aggregate");
+    let parts: Vec<String> = "192.168.0.0/24"
+        .parse::<Cidr>()
+        .unwrap()
+        .split(26)
+        .unwrap()
+        .map(|c| c.to_string())
+        .collect();
+    println!("split /26: {:?}", parts);
+    println!(
+        "split /16 from /24: {:?}",
+        "192.168.0.0/24".parse::<Cidr>().unwrap().split(16).err()
+    );
+    let first_v6: Vec<String> = "2001:db8::/32"
+        .parse::<Cidr>()
+        .unwrap()
+        .split(64)
+        .unwrap()
+        .take(2)
+        .map(|c| c.to_string())
+        .collect();
+    println!("2001:db8::/32 -> /64 first 2: {:?}", first_v6);
+    println!(
+        "supernet of 10.0.0.0/9: {:?}",
+        "10.0.0.0/9"
+            .parse::<Cidr>()
+            .unwrap()
+            .supernet()
+            .map(|c| c.to_string())
+    );
+
+    let list: Vec<Cidr> = [
+        "192.168.0.0/26",
+        "192.168.0.64/26",
+        "192.168.0.128/25",
+        "10.0.0.0/8",
+        "10.1.0.0/16",
+        "192.168.1.0/24",
+        "2001:db8::/33",
+        "2001:db8:8000::/33",
+        "0.0.0.1/32",
+    ]
+    .iter()
+    .map(|s| s.parse().unwrap())
+    .collect();
+    let merged: Vec<String> = aggregate(&list).iter().map(|c| c.to_string()).collect();
+    println!("aggregate: {:?}", merged);
+    let everything: Vec<Cidr> = ["0.0.0.0/1", "128.0.0.0/1"]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+    println!(
+        "aggregate halves: {:?}",
+        aggregate(&everything)
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cidr(s: &str) -> Cidr {
+        s.parse().unwrap()
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn strings(cidrs: impl IntoIterator<Item = Cidr>) -> Vec<String> {
+        cidrs.into_iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn v4_slash_0() {
+        let all = cidr("10.1.2.3/0");
+        assert_eq!(all.to_string(), "0.0.0.0/0");
+        assert_eq!(all.netmask(), ip("0.0.0.0"));
+        assert_eq!(all.last(), ip("255.255.255.255"));
+        assert_eq!(all.broadcast(), Some(ip("255.255.255.255")));
+        assert_eq!(all.host_range(), (ip("0.0.0.1"), ip("255.255.255.254")));
+        assert_eq!(all.num_addresses(), Some(1 << 32));
+        assert!(all.contains(&ip("0.0.0.0")) && all.contains(&ip("255.255.255.255")));
+        assert!(!all.contains(&ip("::")));
+        assert_eq!(all.supernet(), None);
+    }
+
+    #[test]
+    fn v4_slash_31_and_32() {
+        let pair = cidr("192.168.1.7/31");
+        assert_eq!(pair.network(), ip("192.168.1.6"));
+        assert_eq!(pair.broadcast(), None);
+        assert_eq!(pair.host_range(), (ip("192.168.1.6"), ip("192.168.1.7")));
+        assert_eq!(pair.num_addresses(), Some(2));
+
+        let single = cidr("192.168.1.7/32");
+        assert_eq!(single.netmask(), ip("255.255.255.255"));
+        assert_eq!(single.broadcast(), None);
+        assert_eq!(single.host_range(), (ip("192.168.1.7"), ip("192.168.1.7")));
+        assert_eq!(single.num_addresses(), Some(1));
+        assert_eq!(single.hosts().collect::<Vec<_>>(), vec![ip("192.168.1.7")]);
+        assert_eq!(strings(single.split(32).unwrap()), vec!["192.168.1.7/32"]);
+        assert!(single.split(33).is_err());
+        assert_eq!(
+            cidr("192.168.1.0/30").host_range(),
+            (ip("192.168.1.1"), ip("192.168.1.2"))
+        );
+    }
+
+    #[test]
+    fn v6_slash_0_and_128() {
+        let all = cidr("::/0");
+        assert_eq!(all.num_addresses(), None);
+        assert_eq!(all.broadcast(), None);
+        assert_eq!(all.last(), ip("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"));
+        assert_eq!(all.host_range(), (ip("::"), all.last()));
+        assert_eq!(
+            all.hosts().take(2).collect::<Vec<_>>(),
+            vec![ip("::"), ip("::1")]
+        );
+        assert_eq!(strings(all.split(1).unwrap()), vec!["::/1", "8000::/1"]);
+
+        let single = cidr("2001:db8::1/128");
+        assert_eq!(single.num_addresses(), Some(1));
+        assert_eq!(
+            single.netmask(),
+            ip("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff")
+        );
+        assert_eq!(single.hosts().count(), 1);
+        assert!(single.split(129).is_err());
+        assert_eq!(cidr("2001:db8::/64").num_addresses(), Some(1 << 64));
+    }
+
+    #[test]
+    fn hosts_stop_at_the_top_of_the_address_space() {
+        // 最後一個位址是全 1，不能溢位繞回 0
+        let top_v6 = cidr("ffff:ffff:ffff:ffff:ffff:ffff:ffff:fffc/126");
+        let hosts: Vec<IpAddr> = top_v6.hosts().collect();
+        assert_eq!(hosts.len(), 4);
+        assert_eq!(hosts[3], ip("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"));
+        let mut iter = cidr("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff/128").hosts();
+        assert!(iter.next().is_some());
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+
+        let top_v4 = cidr("255.255.255.252/30");
+        assert_eq!(
+            top_v4.hosts().collect::<Vec<_>>(),
+            vec![ip("255.255.255.253"), ip("255.255.255.254")]
+        );
+        assert_eq!(cidr("255.255.255.255/32").hosts().count(), 1);
+        assert_eq!(
+            strings(cidr("255.255.255.0/24").split(25).unwrap()),
+            vec!["255.255.255.0/25", "255.255.255.128/25"]
+        );
+        assert_eq!(cidr("255.255.255.254/31").hosts().count(), 2);
+    }
+
+    #[test]
+    fn split_edges() {
+        assert_eq!(
+            strings(cidr("0.0.0.0/0").split(1).unwrap()),
+            vec!["0.0.0.0/1", "128.0.0.0/1"]
+        );
+        let mut singles = cidr("0.0.0.0/0").split(32).unwrap();
+        assert_eq!(singles.next(), Some(cidr("0.0.0.0/32")));
+        assert_eq!(singles.next(), Some(cidr("0.0.0.1/32")));
+        assert_eq!(
+            strings(cidr("255.255.255.252/30").split(32).unwrap()),
+            vec![
+                "255.255.255.252/32",
+                "255.255.255.253/32",
+                "255.255.255.254/32",
+                "255.255.255.255/32"
+            ]
+        );
+        assert_eq!(
+            strings(cidr("10.0.0.0/8").split(8).unwrap()),
+            vec!["10.0.0.0/8"]
+        );
+        assert_eq!(
+            cidr("192.168.0.0/24").split(16).err(),
+            Some(CidrError::PrefixTooShort {
+                prefix: 24,
+                new_prefix: 16
+            })
+        );
+        let last = cidr("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ff00/120")
+            .split(128)
+            .unwrap()
+            .last();
+        assert_eq!(
+            last,
+            Some(cidr("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff/128"))
+        );
+    }
+
+    #[test]
+    fn aggregate_edges() {
+        let aggregated = |list: &[&str]| {
+            let cidrs: Vec<Cidr> = list.iter().map(|s| cidr(s)).collect();
+            strings(aggregate(&cidrs))
+        };
+        assert_eq!(aggregated(&["0.0.0.0/1", "128.0.0.0/1"]), vec!["0.0.0.0/0"]);
+        assert_eq!(aggregated(&["::/1", "8000::/1"]), vec!["::/0"]);
+        assert_eq!(
+            aggregated(&["255.255.255.255/32", "255.255.255.254/32"]),
+            vec!["255.255.255.254/31"]
+        );
+        assert_eq!(
+            aggregated(&["10.0.0.0/8", "10.1.0.0/16", "0.0.0.0/0", "::1/128"]),
+            vec!["0.0.0.0/0", "::1/128"]
+        );
+        assert_eq!(
+            aggregated(&[
+                "192.168.0.0/26",
+                "192.168.0.64/26",
+                "192.168.0.128/25",
+                "192.168.1.0/24"
+            ]),
+            vec!["192.168.0.0/23"]
+        );
+        // 不對齊的範圍要拆成多個
+        assert_eq!(
+            aggregated(&["10.0.0.1/32", "10.0.0.2/31"]),
+            vec!["10.0.0.1/32", "10.0.0.2/31"]
+        );
+        assert!(aggregate(&[]).is_empty());
+    }
+}
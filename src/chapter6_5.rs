@@ -0,0 +1,570 @@
+// Access Control List（依 IP 位址或網段允許／拒絕）
+use crate::chapter6::IpAddr;
+use crate::chapter6_4::Cidr;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Allow,
+    Deny,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Action::Allow => write!(f, "allow"),
+            Action::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub action: Action,
+    pub cidr: Cidr,          // 單一位址會存成 /32 或 /128
+    pub line: Option<usize>, // 從檔案載入時的行號
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.action, self.cidr)
+    }
+}
+
+// 二元（radix-2）trie：每往下一層代表位址的下一個位元
+#[derive(Debug, Default)]
+struct Node {
+    children: [Option<Box<Node>>; 2],
+    rule: Option<usize>, // 以這個節點為結尾的規則（rules 的 index）
+}
+
+impl Node {
+    fn insert(&mut self, bits: u128, width: u32, prefix: u8, rule: usize) {
+        let mut node = self;
+        for i in 0..prefix as u32 {
+            let bit = ((bits >> (width - 1 - i)) & 1) as usize;
+            node = node.children[bit].get_or_insert_with(Box::default);
+        }
+        // 同一個網段出現多次時，先寫的規則優先
+        node.rule.get_or_insert(rule);
+    }
+
+    // 沿著位址往下走，記住最後（最長）符合的規則
+    fn longest_match(&self, bits: u128, width: u32) -> Option<usize> {
+        let mut node = self;
+        let mut best = node.rule;
+        for i in 0..width {
+            let bit = ((bits >> (width - 1 - i)) & 1) as usize;
+            match &node.children[bit] {
+                Some(child) => node = child,
+                None => break,
+            }
+            best = node.rule.or(best);
+        }
+        best
+    }
+}
+
+// 最長前綴優先；一樣長時依規則順序，都不符合時使用 default
+#[derive(Debug)]
+pub struct Acl {
+    rules: Vec<Rule>,
+    default: Action,
+    v4: Node,
+    v6: Node,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Verdict<'a> {
+    pub action: Action,
+    pub rule: Option<&'a Rule>, // None 代表使用 default
+}
+
+impl Acl {
+    pub fn new(default: Action) -> Acl {
+        Acl {
+            rules: Vec::new(),
+            default,
+            v4: Node::default(),
+            v6: Node::default(),
+        }
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn default_action(&self) -> Action {
+        self.default
+    }
+
+    pub fn add(&mut self, rule: Rule) {
+        let root = if rule.cidr.is_v6() {
+            &mut self.v6
+        } else {
+            &mut self.v4
+        };
+        let network = rule.cidr.network();
+        root.insert(
+            network.to_bits(),
+            network.bit_width(),
+            rule.cidr.prefix(),
+            self.rules.len(),
+        );
+        self.rules.push(rule);
+    }
+
+    pub fn allow(&mut self, cidr: Cidr) {
+        self.add(Rule {
+            action: Action::Allow,
+            cidr,
+            line: None,
+        });
+    }
+
+    pub fn deny(&mut self, cidr: Cidr) {
+        self.add(Rule {
+            action: Action::Deny,
+            cidr,
+            line: None,
+        });
+    }
+
+    pub fn check(&self, addr: &IpAddr) -> Verdict<'_> {
+        let root = if addr.is_v6() { &self.v6 } else { &self.v4 };
+        match root.longest_match(addr.to_bits(), addr.bit_width()) {
+            Some(i) => Verdict {
+                action: self.rules[i].action,
+                rule: Some(&self.rules[i]),
+            },
+            None => Verdict {
+                action: self.default,
+                rule: None,
+            },
+        }
+    }
+
+    pub fn is_allowed(&self, addr: &IpAddr) -> bool {
+        self.check(addr).action == Action::Allow
+    }
+
+    pub fn load(path: &str) -> Result<Acl, LoadError> {
+        let text = fs::read_to_string(path).map_err(LoadError::Io)?;
+        text.parse().map_err(LoadError::Parse)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AclErrorKind {
+    UnknownAction(String),
+    MissingAddress,
+    InvalidAddress(String),
+    TrailingInput(String),
+    DuplicateDefault,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AclError {
+    pub line: usize, // 從 1 開始
+    pub kind: AclErrorKind,
+}
+
+impl fmt::Display for AclError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "第 {} 行: ", self.line)?;
+        match &self.kind {
+            AclErrorKind::UnknownAction(s) => {
+                write!(f, "未知的動作 {:?}（allow、deny 或 default）", s)
+            }
+            AclErrorKind::MissingAddress => write!(f, "缺少位址或網段"),
+            AclErrorKind::InvalidAddress(s) => write!(f, "無效的位址或網段 {:?}", s),
+            AclErrorKind::TrailingInput(s) => write!(f, "多餘的內容 {:?}", s),
+            AclErrorKind::DuplicateDefault => write!(f, "default 只能設定一次"),
+        }
+    }
+}
+
+impl std::error::Error for AclError {}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Parse(AclError),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(err) => write!(f, "無法讀取規則檔: {}", err),
+            LoadError::Parse(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+fn parse_action(s: &str) -> Option<Action> {
+    match s {
+        "allow" => Some(Action::Allow),
+        "deny" => Some(Action::Deny),
+        _ => None,
+    }
+}
+
+// 單一位址視為 /32 或 /128
+fn parse_target(s: &str) -> Option<Cidr> {
+    if s.contains('/') {
+        s.parse().ok()
+    } else {
+        let addr: IpAddr = s.parse().ok()?;
+        Cidr::new(addr, addr.bit_width() as u8).ok()
+    }
+}
+
+// 規則檔格式，一行一條，# 之後是註解：
+//   allow 10.0.0.0/8
+//   deny 10.1.2.3
+//   default deny      （省略時為 deny）
+impl FromStr for Acl {
+    type Err = AclError;
+
+    fn from_str(s: &str) -> Result<Acl, AclError> {
+        let mut rules = Vec::new();
+        let mut default = None;
+        for (i, line) in s.lines().enumerate() {
+            let error = |kind| AclError { line: i + 1, kind };
+            let content = line.split('#').next().unwrap_or("");
+            let mut words = content.split_whitespace();
+            let Some(keyword) = words.next() else {
+                continue;
+            };
+            let target = words
+                .next()
+                .ok_or_else(|| error(AclErrorKind::MissingAddress))?;
+            if let Some(extra) = words.next() {
+                return Err(error(AclErrorKind::TrailingInput(extra.to_string())));
+            }
+            if keyword == "default" {
+                let action = parse_action(target)
+                    .ok_or_else(|| error(AclErrorKind::UnknownAction(target.to_string())))?;
+                if default.replace(action).is_some() {
+                    return Err(error(AclErrorKind::DuplicateDefault));
+                }
+                continue;
+            }
+            let action = parse_action(keyword)
+                .ok_or_else(|| error(AclErrorKind::UnknownAction(keyword.to_string())))?;
+            let cidr = parse_target(target)
+                .ok_or_else(|| error(AclErrorKind::InvalidAddress(target.to_string())))?;
+            rules.push(Rule {
+                action,
+                cidr,
+                line: Some(i + 1),
+            });
+        }
+
+        let mut acl = Acl::new(default.unwrap_or(Action::Deny));
+        for rule in rules {
+            acl.add(rule);
+        }
+        Ok(acl)
+    }
+}
+
+// 逐行判斷 input 中的位址，結果寫到 output；回傳無法解析的行數
+pub fn check_lines<R: BufRead, W: io::Write>(
+    acl: &Acl,
+    input: R,
+    mut output: W,
+) -> io::Result<usize> {
+    let mut invalid = 0;
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        let text = line.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        match text.parse::<IpAddr>() {
+            Ok(addr) => {
+                let verdict = acl.check(&addr);
+                match verdict.rule {
+                    Some(rule) => match rule.line {
+                        Some(n) => writeln!(
+                            output,
+                            "{} {} (第 {} 行: {})",
+                            addr, verdict.action, n, rule
+                        )?,
+                        None => writeln!(output, "{} {} ({})", addr, verdict.action, rule)?,
+                    },
+                    None => writeln!(output, "{} {} (default)", addr, verdict.action)?,
+                }
+            }
+            Err(err) => {
+                invalid += 1;
+                writeln!(output, "第 {} 行: {}", i + 1, err)?;
+            }
+        }
+    }
+    Ok(invalid)
+}
+
+// cargo run -- check rules.txt < addresses.txt
+pub fn check_command(args: &[String]) -> Result<usize, LoadError> {
+    let path = args.first().map(String::as_str).unwrap_or("acl.txt");
+    let acl = Acl::load(path)?;
+    let stdin = io::stdin();
+    check_lines(&acl, stdin.lock(), io::stdout()).map_err(LoadError::Io)
+}
+
+pub fn main() {
+    println!("✅ Rules");
+    let rules = "\
+# 公司網路
+allow 10.0.0.0/8
+deny  10.66.0.0/16      # 訪客網段
+allow 10.66.1.10        # 訪客網段中的印表機
+deny  10.0.0.0/8        # 和第 2 行重複，第 2 行優先
+allow 2001:db8::/32
+deny  2001:db8:bad::/48
+default deny
+";
+    let acl: Acl = rules.parse().unwrap();
+    for rule in acl.rules() {
+        println!("  第 {} 行: {}", rule.line.unwrap(), rule);
+    }
+
+    println!("✅ Longest prefix match");
+    for input in [
+        "10.1.2.3",
+        "10.66.5.5",
+        "10.66.1.10",
+        "192.168.1.1",
+        "2001:db8::1",
+        "2001:db8:bad::1",
+        "::1",
+    ] {
+        let addr: IpAddr = input.parse().unwrap();
+        let verdict = acl.check(&addr);
+        println!(
+            "{:<16} {} by {}",
+            input,
+            verdict.action,
+            verdict
+                .rule
+                .map(|r| r.to_string())
+                .unwrap_or_else(|| String::from("default"))
+        );
+    }
+
+    println!("✅ 從程式加入規則");
+    let mut open = Acl::new(Action::Allow);
+    open.deny("0.0.0.0/0".parse().unwrap());
+    open.allow("127.0.0.1/32".parse().unwrap());
+    println!(
+        "127.0.0.1 allowed: {}",
+        open.is_allowed(&"127.0.0.1".parse().unwrap())
+    );
+    println!(
+        "8.8.8.8 allowed: {}",
+        open.is_allowed(&"8.8.8.8".parse().unwrap())
+    );
+    println!(
+        "::1 allowed (default): {}",
+        open.is_allowed(&"::1".parse().unwrap())
+    );
+
+    println!("✅ Errors");
+    for bad in [
+        "allow 10.0.0.0/8\npermit 1.2.3.4",
+        "allow 10.0.0.0/8\n\ndeny 10.0.0.256",
+        "deny",
+        "allow 1.2.3.4 now",
+        "default deny\ndefault allow",
+        "default maybe",
+    ] {
+        println!("{}", bad.parse::<Acl>().unwrap_err());
+    }
+
+    println!("✅ Load and check");
+    let path = std::env::temp_dir().join("chapter6_5_acl.txt");
+    let path = path.to_str().unwrap();
+    fs::write(path, rules).unwrap();
+    let acl = Acl::load(path).unwrap();
+    let input = "10.66.1.10\n# comment\n\n172.16.0.1\nnot-an-ip\n2001:db8:bad::beef\n";
+    let invalid = check_lines(&acl, input.as_bytes(), io::stdout()).unwrap();
+    println!("invalid lines: {}", invalid);
+    match Acl::load("/nonexistent/acl.txt") {
+        Err(err) => println!("{}", err),
+        Ok(_) => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    // (動作, 規則的行號)；None 代表使用 default
+    fn verdict(acl: &Acl, addr: &str) -> (Action, Option<usize>) {
+        let verdict = acl.check(&ip(addr));
+        (verdict.action, verdict.rule.and_then(|r| r.line))
+    }
+
+    #[test]
+    fn longest_prefix_beats_rule_order() {
+        let acl: Acl = "\
+deny 10.1.2.3
+deny 10.1.0.0/16
+allow 10.0.0.0/8
+allow 0.0.0.0/0
+deny 10.1.2.0/24
+"
+        .parse()
+        .unwrap();
+        assert_eq!(verdict(&acl, "10.1.2.3"), (Action::Deny, Some(1)));
+        assert_eq!(verdict(&acl, "10.1.2.4"), (Action::Deny, Some(5)));
+        assert_eq!(verdict(&acl, "10.1.3.4"), (Action::Deny, Some(2)));
+        assert_eq!(verdict(&acl, "10.2.3.4"), (Action::Allow, Some(3)));
+        assert_eq!(verdict(&acl, "11.0.0.1"), (Action::Allow, Some(4)));
+        // IPv4 的 0.0.0.0/0 不影響 IPv6
+        assert_eq!(verdict(&acl, "::1"), (Action::Deny, None));
+    }
+
+    #[test]
+    fn duplicate_prefixes_keep_the_first_rule() {
+        let acl: Acl = "\
+allow 10.0.0.0/8
+deny 10.0.0.0/8
+deny 10.9.9.9/8   # 網段部分相同，一樣算重複
+"
+        .parse()
+        .unwrap();
+        assert_eq!(acl.rules().len(), 3);
+        assert_eq!(verdict(&acl, "10.1.1.1"), (Action::Allow, Some(1)));
+
+        let mut acl = Acl::new(Action::Allow);
+        acl.deny("192.168.0.0/16".parse().unwrap());
+        acl.allow("192.168.0.0/16".parse().unwrap());
+        assert!(!acl.is_allowed(&ip("192.168.1.1")));
+    }
+
+    #[test]
+    fn default_action() {
+        let acl: Acl = "allow 10.0.0.0/8".parse().unwrap();
+        assert_eq!(acl.default_action(), Action::Deny);
+        assert_eq!(verdict(&acl, "8.8.8.8"), (Action::Deny, None));
+
+        let acl: Acl = "deny 10.0.0.0/8\ndefault allow".parse().unwrap();
+        assert_eq!(acl.default_action(), Action::Allow);
+        assert!(acl.is_allowed(&ip("8.8.8.8")));
+        assert!(acl.is_allowed(&ip("2001:db8::1")));
+        assert!(!acl.is_allowed(&ip("10.0.0.1")));
+
+        let acl: Acl = "# 只有註解\n\n".parse().unwrap();
+        assert!(acl.rules().is_empty());
+        assert!(!acl.is_allowed(&ip("127.0.0.1")));
+    }
+
+    #[test]
+    fn ipv6_rules() {
+        let acl: Acl = "\
+allow 2001:db8::/32
+deny 2001:db8:bad::/48
+allow ::1
+"
+        .parse()
+        .unwrap();
+        assert_eq!(verdict(&acl, "2001:db8::1"), (Action::Allow, Some(1)));
+        assert_eq!(verdict(&acl, "2001:db8:bad::1"), (Action::Deny, Some(2)));
+        assert_eq!(verdict(&acl, "::1"), (Action::Allow, Some(3)));
+        assert_eq!(verdict(&acl, "::2"), (Action::Deny, None));
+        assert_eq!(verdict(&acl, "127.0.0.1"), (Action::Deny, None));
+    }
+
+    #[test]
+    fn parse_errors_have_line_numbers() {
+        let cases = [
+            (
+                "allow 10.0.0.0/8\npermit 1.2.3.4",
+                2,
+                AclErrorKind::UnknownAction(String::from("permit")),
+            ),
+            (
+                "allow 10.0.0.0/8\n\ndeny 10.0.0.256",
+                3,
+                AclErrorKind::InvalidAddress(String::from("10.0.0.256")),
+            ),
+            (
+                "# comment\ndeny 10.0.0.0/33",
+                2,
+                AclErrorKind::InvalidAddress(String::from("10.0.0.0/33")),
+            ),
+            ("deny", 1, AclErrorKind::MissingAddress),
+            ("deny # 10.0.0.1", 1, AclErrorKind::MissingAddress),
+            (
+                "allow 1.2.3.4 now",
+                1,
+                AclErrorKind::TrailingInput(String::from("now")),
+            ),
+            (
+                "default deny\ndefault allow",
+                2,
+                AclErrorKind::DuplicateDefault,
+            ),
+            (
+                "default maybe",
+                1,
+                AclErrorKind::UnknownAction(String::from("maybe")),
+            ),
+        ];
+        for (input, line, kind) in cases {
+            assert_eq!(
+                input.parse::<Acl>().unwrap_err(),
+                AclError { line, kind },
+                "{:?}",
+                input
+            );
+        }
+        let err = "\n\nallow nope".parse::<Acl>().unwrap_err();
+        assert!(err.to_string().starts_with("第 3 行: "), "{}", err);
+    }
+
+    #[test]
+    fn check_lines_output() {
+        let mut acl: Acl = "allow 10.0.0.0/8\ndeny 10.66.0.0/16".parse().unwrap();
+        acl.allow("10.66.1.10/32".parse().unwrap());
+        let input =
+            "10.1.1.1\n# comment\n\n  10.66.5.5  \n10.66.1.10\n8.8.8.8\nnot-an-ip\n1::2::3\n";
+        let mut output = Vec::new();
+        let invalid = check_lines(&acl, input.as_bytes(), &mut output).unwrap();
+        assert_eq!(invalid, 2);
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "10.1.1.1 allow (第 1 行: allow 10.0.0.0/8)");
+        assert_eq!(lines[1], "10.66.5.5 deny (第 2 行: deny 10.66.0.0/16)");
+        assert_eq!(lines[2], "10.66.1.10 allow (allow 10.66.1.10/32)");
+        assert_eq!(lines[3], "8.8.8.8 deny (default)");
+        assert!(lines[4].starts_with("第 7 行: "), "{}", lines[4]);
+        assert!(lines[5].starts_with("第 8 行: "), "{}", lines[5]);
+
+        let mut output = Vec::new();
+        assert_eq!(check_lines(&acl, "".as_bytes(), &mut output).unwrap(), 0);
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn load_reports_io_errors() {
+        assert!(matches!(
+            Acl::load("/nonexistent/acl.txt"),
+            Err(LoadError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,451 @@
+// Wire Format（把 Message 編碼成二進位）
+use crate::chapter6::Message;
+use crate::chapter6_10::Color;
+use std::fmt;
+
+// 每則訊息：tag（1 byte）+ payload 長度（varint）+ payload
+// - Quit：沒有 payload
+// - Move：x、y 兩個 zigzag varint
+// - Write：UTF-8 字串本身
+// - ChangeColor：r、g、b、a 四個 byte
+const TAG_QUIT: u8 = 0;
+const TAG_MOVE: u8 = 1;
+const TAG_WRITE: u8 = 2;
+const TAG_CHANGE_COLOR: u8 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    Truncated,                                  // 資料在訊息結束前就沒了
+    UnknownTag(u8),                             // 不認得的訊息種類
+    VarintTooLong,                              // 超過 u32 能表示的範圍
+    NonCanonicalVarint,                         // 多餘的 0x80 填充，同一個值只接受一種寫法
+    InvalidUtf8,                                // Write 的內容不是合法的 UTF-8
+    PayloadLength { tag: u8, declared: usize }, // 宣告的長度和內容不符
+    TrailingBytes(usize),                       // 一則訊息之後還有多餘的資料
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "資料不完整"),
+            DecodeError::UnknownTag(tag) => write!(f, "未知的 tag: {:#04x}", tag),
+            DecodeError::VarintTooLong => write!(f, "varint 超過 32 位元"),
+            DecodeError::NonCanonicalVarint => write!(f, "varint 不是最短的寫法"),
+            DecodeError::InvalidUtf8 => write!(f, "字串不是合法的 UTF-8"),
+            DecodeError::PayloadLength { tag, declared } => {
+                write!(
+                    f,
+                    "tag {:#04x} 的 payload 長度 {} 和內容不符",
+                    tag, declared
+                )
+            }
+            DecodeError::TrailingBytes(n) => write!(f, "訊息後面多了 {} bytes", n),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+// 每個 byte 放 7 位元，最高位元 1 代表後面還有
+fn write_varint(out: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+// zigzag 讓小的負數也只需要少量 bytes：0, -1, 1, -2 -> 0, 1, 2, 3
+fn zigzag(value: i32) -> u32 {
+    ((value << 1) ^ (value >> 31)) as u32
+}
+
+fn unzigzag(value: u32) -> i32 {
+    ((value >> 1) as i32) ^ -((value & 1) as i32)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        let b = *self.bytes.get(self.pos).ok_or(DecodeError::Truncated)?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let end = self.pos.checked_add(len).ok_or(DecodeError::Truncated)?;
+        let slice = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or(DecodeError::Truncated)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn varint(&mut self) -> Result<u32, DecodeError> {
+        let mut value: u32 = 0;
+        for i in 0..5 {
+            let b = self.byte()?;
+            let bits = (b & 0x7f) as u32;
+            // 第 5 個 byte 只能放剩下的 4 位元
+            if i == 4 && bits > 0x0f {
+                return Err(DecodeError::VarintTooLong);
+            }
+            value |= bits << (7 * i);
+            if b & 0x80 == 0 {
+                if b == 0 && i > 0 {
+                    return Err(DecodeError::NonCanonicalVarint);
+                }
+                return Ok(value);
+            }
+        }
+        Err(DecodeError::VarintTooLong)
+    }
+
+    fn int(&mut self) -> Result<i32, DecodeError> {
+        Ok(unzigzag(self.varint()?))
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+}
+
+pub fn encode_into(message: &Message, out: &mut Vec<u8>) {
+    let mut payload = Vec::new();
+    let tag = match message {
+        Message::Quit => TAG_QUIT,
+        Message::Move { x, y } => {
+            write_varint(&mut payload, zigzag(*x));
+            write_varint(&mut payload, zigzag(*y));
+            TAG_MOVE
+        }
+        Message::Write(text) => {
+            payload.extend_from_slice(text.as_bytes());
+            TAG_WRITE
+        }
+        Message::ChangeColor(color) => {
+            payload.extend_from_slice(&[color.r, color.g, color.b, color.a]);
+            TAG_CHANGE_COLOR
+        }
+    };
+    out.push(tag);
+    // Write 的字串超過 4 GiB 就無法編碼
+    let len = u32::try_from(payload.len()).expect("payload 超過 u32::MAX bytes");
+    write_varint(out, len);
+    out.extend_from_slice(&payload);
+}
+
+pub fn encode(message: &Message) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_into(message, &mut out);
+    out
+}
+
+fn decode_payload(tag: u8, payload: &[u8]) -> Result<Message, DecodeError> {
+    let mut reader = Reader {
+        bytes: payload,
+        pos: 0,
+    };
+    // payload 內部不完整或有剩，都代表宣告的長度不對
+    let mismatch = DecodeError::PayloadLength {
+        tag,
+        declared: payload.len(),
+    };
+    let short = |err| match err {
+        DecodeError::Truncated => mismatch.clone(),
+        err => err,
+    };
+    let message = match tag {
+        TAG_QUIT => Message::Quit,
+        TAG_MOVE => Message::Move {
+            x: reader.int().map_err(short)?,
+            y: reader.int().map_err(short)?,
+        },
+        TAG_WRITE => {
+            let text = std::str::from_utf8(payload).map_err(|_| DecodeError::InvalidUtf8)?;
+            reader.pos = payload.len();
+            Message::Write(text.to_string())
+        }
+        TAG_CHANGE_COLOR => {
+            let [r, g, b, a]: [u8; 4] = reader.take(4).map_err(short)?.try_into().unwrap();
+            Message::ChangeColor(Color::rgba(r, g, b, a))
+        }
+        _ => return Err(DecodeError::UnknownTag(tag)),
+    };
+    if reader.remaining() > 0 {
+        return Err(mismatch);
+    }
+    Ok(message)
+}
+
+// 從開頭解出一則訊息，回傳訊息與用掉的 bytes 數，可用來讀連續的訊息
+pub fn decode_prefix(bytes: &[u8]) -> Result<(Message, usize), DecodeError> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let tag = reader.byte()?;
+    if tag > TAG_CHANGE_COLOR {
+        return Err(DecodeError::UnknownTag(tag));
+    }
+    let len = reader.varint()? as usize;
+    let payload = reader.take(len)?;
+    Ok((decode_payload(tag, payload)?, reader.pos))
+}
+
+// 剛好一則訊息，多餘的資料視為錯誤
+pub fn decode(bytes: &[u8]) -> Result<Message, DecodeError> {
+    let (message, used) = decode_prefix(bytes)?;
+    match bytes.len() - used {
+        0 => Ok(message),
+        n => Err(DecodeError::TrailingBytes(n)),
+    }
+}
+
+pub fn decode_all(mut bytes: &[u8]) -> Result<Vec<Message>, DecodeError> {
+    let mut messages = Vec::new();
+    while !bytes.is_empty() {
+        let (message, used) = decode_prefix(bytes)?;
+        messages.push(message);
+        bytes = &bytes[used..];
+    }
+    Ok(messages)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn main() {
+    println!("✅ Encode");
+    let messages = vec![
+        Message::Quit,
+        Message::Move { x: 10, y: -20 },
+        Message::Write(String::from("Hello, 世界")),
+        Message::ChangeColor(Color::rgb(255, 136, 0)),
+        Message::Move {
+            x: i32::MIN,
+            y: i32::MAX,
+        },
+    ];
+    for message in &messages {
+        let bytes = encode(message);
+        println!(
+            "{:?} -> [{}] round-trip: {}",
+            message,
+            hex(&bytes),
+            decode(&bytes).as_ref() == Ok(message)
+        );
+    }
+
+    println!("✅ Stream");
+    let mut stream = Vec::new();
+    for message in &messages {
+        encode_into(message, &mut stream);
+    }
+    println!(
+        "{} bytes -> {} messages",
+        stream.len(),
+        decode_all(&stream).unwrap().len()
+    );
+
+    println!("✅ Errors");
+    let cases: Vec<(&str, Vec<u8>)> = vec![
+        ("empty", vec![]),
+        ("unknown tag", vec![0x09, 0x00]),
+        ("truncated length", vec![TAG_WRITE]),
+        ("truncated payload", vec![TAG_WRITE, 0x05, b'h', b'i']),
+        ("payload too short", vec![TAG_MOVE, 0x01, 0x02]),
+        (
+            "color too short",
+            vec![TAG_CHANGE_COLOR, 0x03, 0xff, 0x88, 0x00],
+        ),
+        ("payload too long", vec![TAG_QUIT, 0x01, 0x00]),
+        (
+            "varint too long",
+            vec![TAG_MOVE, 0x06, 0xff, 0xff, 0xff, 0xff, 0x7f, 0x00],
+        ),
+        ("non-canonical", vec![TAG_MOVE, 0x03, 0x80, 0x00, 0x00]),
+        ("invalid utf-8", vec![TAG_WRITE, 0x02, 0xc3, 0x28]),
+        ("trailing bytes", vec![TAG_QUIT, 0x00, 0xaa]),
+    ];
+    for (name, bytes) in cases {
+        match decode(&bytes) {
+            Ok(message) => println!("{}: 意外成功 {:?}", name, message),
+            Err(err) => println!("{}: {}", name, err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_int(rng: &mut impl Rng) -> i32 {
+        // 混合小數字、邊界值與任意值，測試 varint 的各種長度
+        match rng.random_range(0..4) {
+            0 => rng.random_range(-200..200),
+            1 => [i32::MIN, i32::MAX, -64, 63, 64, -65, 0][rng.random_range(0..7)],
+            _ => rng.random(),
+        }
+    }
+
+    fn random_message(rng: &mut impl Rng) -> Message {
+        match rng.random_range(0..4) {
+            0 => Message::Quit,
+            1 => Message::Move {
+                x: random_int(rng),
+                y: random_int(rng),
+            },
+            2 => {
+                let len = rng.random_range(0..200);
+                Message::Write((0..len).map(|_| rng.random::<char>()).collect())
+            }
+            _ => Message::ChangeColor(Color::rgba(
+                rng.random(),
+                rng.random(),
+                rng.random(),
+                rng.random(),
+            )),
+        }
+    }
+
+    fn samples() -> Vec<Message> {
+        vec![
+            Message::Quit,
+            Message::Move { x: 10, y: -20 },
+            Message::Move { x: 0, y: 0 },
+            Message::Move {
+                x: i32::MIN,
+                y: i32::MAX,
+            },
+            Message::Write(String::new()),
+            Message::Write(String::from("Hello, 世界")),
+            Message::ChangeColor(Color::rgba(255, 136, 0, 0)),
+        ]
+    }
+
+    #[test]
+    fn round_trip_and_stream() {
+        let mut stream = Vec::new();
+        for message in samples() {
+            let bytes = encode(&message);
+            assert_eq!(decode(&bytes), Ok(message.clone()));
+            assert_eq!(decode_prefix(&bytes), Ok((message.clone(), bytes.len())));
+            encode_into(&message, &mut stream);
+        }
+        assert_eq!(decode_all(&stream), Ok(samples()));
+        assert_eq!(decode_all(&[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn known_encodings() {
+        assert_eq!(encode(&Message::Quit), vec![TAG_QUIT, 0x00]);
+        assert_eq!(
+            encode(&Message::Move { x: 10, y: -20 }),
+            vec![TAG_MOVE, 0x02, 0x14, 0x27]
+        );
+        assert_eq!(
+            encode(&Message::Move {
+                x: i32::MIN,
+                y: i32::MAX
+            }),
+            vec![TAG_MOVE, 0x0a, 0xff, 0xff, 0xff, 0xff, 0x0f, 0xfe, 0xff, 0xff, 0xff, 0x0f]
+        );
+    }
+
+    #[test]
+    fn every_truncated_prefix_is_an_error() {
+        for message in samples() {
+            let bytes = encode(&message);
+            for cut in 0..bytes.len() {
+                assert!(decode(&bytes[..cut]).is_err(), "{:?} 截到 {}", message, cut);
+            }
+        }
+    }
+
+    #[test]
+    fn malformed_inputs() {
+        let cases: Vec<(Vec<u8>, DecodeError)> = vec![
+            (vec![], DecodeError::Truncated),
+            (vec![0x09, 0x00], DecodeError::UnknownTag(0x09)),
+            (vec![TAG_WRITE], DecodeError::Truncated),
+            (vec![TAG_WRITE, 0x05, b'h', b'i'], DecodeError::Truncated),
+            (
+                vec![TAG_MOVE, 0x01, 0x02],
+                DecodeError::PayloadLength {
+                    tag: TAG_MOVE,
+                    declared: 1,
+                },
+            ),
+            (
+                vec![TAG_CHANGE_COLOR, 0x03, 0xff, 0x88, 0x00],
+                DecodeError::PayloadLength {
+                    tag: TAG_CHANGE_COLOR,
+                    declared: 3,
+                },
+            ),
+            (
+                vec![TAG_QUIT, 0x01, 0x00],
+                DecodeError::PayloadLength {
+                    tag: TAG_QUIT,
+                    declared: 1,
+                },
+            ),
+            (
+                vec![TAG_MOVE, 0x06, 0xff, 0xff, 0xff, 0xff, 0x7f, 0x00],
+                DecodeError::VarintTooLong,
+            ),
+            (
+                vec![TAG_WRITE, 0xff, 0xff, 0xff, 0xff, 0xff],
+                DecodeError::VarintTooLong,
+            ),
+            (
+                vec![TAG_MOVE, 0x03, 0x80, 0x00, 0x00],
+                DecodeError::NonCanonicalVarint,
+            ),
+            (vec![TAG_WRITE, 0x02, 0xc3, 0x28], DecodeError::InvalidUtf8),
+            (vec![TAG_QUIT, 0x00, 0xaa], DecodeError::TrailingBytes(1)),
+        ];
+        for (bytes, expected) in cases {
+            assert_eq!(decode(&bytes), Err(expected), "{:02x?}", bytes);
+        }
+    }
+
+    #[test]
+    fn fuzz_round_trip() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..10_000 {
+            let message = random_message(&mut rng);
+            let bytes = encode(&message);
+            assert_eq!(decode(&bytes), Ok(message));
+            // 每個截斷的前綴都必須回傳錯誤
+            let cut = rng.random_range(0..bytes.len());
+            assert!(decode(&bytes[..cut]).is_err());
+        }
+    }
+
+    #[test]
+    fn fuzz_random_bytes() {
+        // 隨機 bytes 不能 panic；能解開的再編碼必須得到一樣的 bytes
+        let mut rng = StdRng::seed_from_u64(4242);
+        for _ in 0..10_000 {
+            let len = rng.random_range(0..12);
+            let mut bytes: Vec<u8> = (0..len).map(|_| rng.random()).collect();
+            if !bytes.is_empty() {
+                bytes[0] %= 5; // 讓大部分 tag 是合法的，才測得到後面的驗證
+            }
+            if let Ok(message) = decode(&bytes) {
+                assert_eq!(encode(&message), bytes);
+            }
+        }
+    }
+}
@@ -0,0 +1,390 @@
+// Dispatcher（依 Message 種類呼叫註冊的 handler）
+use crate::chapter6::Message;
+use crate::chapter6_10::Color;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageKind {
+    Quit,
+    Move,
+    Write,
+    ChangeColor,
+}
+
+impl Message {
+    pub fn kind(&self) -> MessageKind {
+        match self {
+            Message::Quit => MessageKind::Quit,
+            Message::Move { .. } => MessageKind::Move,
+            Message::Write(_) => MessageKind::Write,
+            Message::ChangeColor(..) => MessageKind::ChangeColor,
+        }
+    }
+}
+
+pub trait Handler<R> {
+    fn handle(&mut self, message: &Message) -> R;
+}
+
+// 任何 FnMut(&Message) -> R 的 closure 都可以當 handler
+impl<R, F: FnMut(&Message) -> R> Handler<R> for F {
+    fn handle(&mut self, message: &Message) -> R {
+        self(message)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HandlerId(usize);
+
+// 一次 dispatch 的結果，依註冊順序排列
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome<R> {
+    pub kind: MessageKind,
+    pub results: Vec<R>,
+    pub fallback: bool, // 沒有 handler 訂閱，由 fallback 處理
+}
+
+impl<R> Outcome<R> {
+    // 沒有 handler 也沒有 fallback
+    pub fn is_unhandled(&self) -> bool {
+        self.results.is_empty()
+    }
+}
+
+impl<T, E> Outcome<Result<T, E>> {
+    // 全部成功才是 Ok，否則回傳所有錯誤
+    pub fn into_result(self) -> Result<Vec<T>, Vec<E>> {
+        let (oks, errs): (Vec<_>, Vec<_>) = self.results.into_iter().partition(|r| r.is_ok());
+        if errs.is_empty() {
+            Ok(oks.into_iter().filter_map(Result::ok).collect())
+        } else {
+            Err(errs.into_iter().filter_map(Result::err).collect())
+        }
+    }
+}
+
+type Subscriber<R> = (HandlerId, Box<dyn Handler<R>>);
+
+pub struct Dispatcher<R> {
+    handlers: HashMap<MessageKind, Vec<Subscriber<R>>>,
+    fallback: Option<Box<dyn Handler<R>>>,
+    next_id: usize,
+}
+
+impl<R> Default for Dispatcher<R> {
+    fn default() -> Self {
+        Dispatcher::new()
+    }
+}
+
+impl<R> Dispatcher<R> {
+    pub fn new() -> Dispatcher<R> {
+        Dispatcher {
+            handlers: HashMap::new(),
+            fallback: None,
+            next_id: 0,
+        }
+    }
+
+    // 同一種訊息可以有多個 handler，依註冊順序呼叫
+    pub fn subscribe(
+        &mut self,
+        kind: MessageKind,
+        handler: impl Handler<R> + 'static,
+    ) -> HandlerId {
+        let id = HandlerId(self.next_id);
+        self.next_id += 1;
+        self.handlers
+            .entry(kind)
+            .or_default()
+            .push((id, Box::new(handler)));
+        id
+    }
+
+    // 回傳是否真的有移除
+    pub fn unsubscribe(&mut self, id: HandlerId) -> bool {
+        for list in self.handlers.values_mut() {
+            if let Some(pos) = list.iter().position(|(h, _)| *h == id) {
+                list.remove(pos);
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn set_fallback(&mut self, handler: impl Handler<R> + 'static) {
+        self.fallback = Some(Box::new(handler));
+    }
+
+    pub fn subscribers(&self, kind: MessageKind) -> usize {
+        self.handlers.get(&kind).map(Vec::len).unwrap_or(0)
+    }
+
+    pub fn dispatch(&mut self, message: &Message) -> Outcome<R> {
+        let kind = message.kind();
+        match self.handlers.get_mut(&kind) {
+            Some(list) if !list.is_empty() => Outcome {
+                kind,
+                results: list.iter_mut().map(|(_, h)| h.handle(message)).collect(),
+                fallback: false,
+            },
+            _ => Outcome {
+                kind,
+                results: self
+                    .fallback
+                    .iter_mut()
+                    .map(|h| h.handle(message))
+                    .collect(),
+                fallback: self.fallback.is_some(),
+            },
+        }
+    }
+
+    pub fn dispatch_all<'a>(
+        &mut self,
+        messages: impl IntoIterator<Item = &'a Message>,
+    ) -> Vec<Outcome<R>> {
+        messages.into_iter().map(|m| self.dispatch(m)).collect()
+    }
+}
+
+// 有狀態的 handler：記錄走過的總距離
+struct Odometer {
+    position: (i32, i32),
+    distance: f64,
+}
+
+impl Handler<Result<String, String>> for Odometer {
+    fn handle(&mut self, message: &Message) -> Result<String, String> {
+        let Message::Move { x, y } = *message else {
+            return Err(String::from("Odometer 只處理 Move"));
+        };
+        let dx = (x as f64) - (self.position.0 as f64);
+        let dy = (y as f64) - (self.position.1 as f64);
+        self.distance += (dx * dx + dy * dy).sqrt();
+        self.position = (x, y);
+        Ok(format!("總距離 {:.1}", self.distance))
+    }
+}
+
+pub fn main() {
+    println!("✅ Dispatcher");
+    let mut dispatcher: Dispatcher<Result<String, String>> = Dispatcher::new();
+    dispatcher.subscribe(MessageKind::Move, |m: &Message| Ok(format!("log: {:?}", m)));
+    dispatcher.subscribe(
+        MessageKind::Move,
+        Odometer {
+            position: (0, 0),
+            distance: 0.0,
+        },
+    );
+    let logger = dispatcher.subscribe(MessageKind::Write, |m: &Message| {
+        Ok(format!("log: {:?}", m))
+    });
+    dispatcher.subscribe(MessageKind::Write, |m: &Message| match m {
+        Message::Write(text) if text.is_empty() => Err(String::from("不能寫入空字串")),
+        Message::Write(text) => Ok(format!("寫入 {} 個字", text.chars().count())),
+        _ => unreachable!(),
+    });
+    // 文字畫在白色背景上，對比太低就拒絕
+    dispatcher.subscribe(MessageKind::ChangeColor, |m: &Message| match m {
+        Message::ChangeColor(color) if color.contrast_ratio(&Color::WHITE) >= 4.5 => {
+            Ok(format!("顏色 {}", color))
+        }
+        Message::ChangeColor(color) => Err(format!(
+            "{} 在白色背景上對比只有 {:.2}",
+            color,
+            color.contrast_ratio(&Color::WHITE)
+        )),
+        _ => unreachable!(),
+    });
+
+    let messages = vec![
+        Message::Move { x: 3, y: 4 },
+        Message::Move { x: 6, y: 8 },
+        Message::Write(String::from("Hello")),
+        Message::Write(String::new()),
+        Message::ChangeColor(Color::rgb(0, 0, 128)),
+        Message::ChangeColor(Color::rgb(255, 128, 0)),
+        Message::Quit,
+    ];
+    for outcome in dispatcher.dispatch_all(&messages) {
+        println!(
+            "{:?} (fallback: {}) -> {:?}",
+            outcome.kind, outcome.fallback, outcome.results
+        );
+    }
+
+    println!("✅ Fallback");
+    let mut unhandled = 0;
+    dispatcher.set_fallback(move |m: &Message| {
+        unhandled += 1;
+        Err(format!("沒有 handler（第 {} 次）: {:?}", unhandled, m))
+    });
+    println!("{:?}", dispatcher.dispatch(&Message::Quit).into_result());
+    println!("{:?}", dispatcher.dispatch(&Message::Quit).into_result());
+
+    println!("✅ Unsubscribe");
+    println!(
+        "Write 訂閱數: {}",
+        dispatcher.subscribers(MessageKind::Write)
+    );
+    println!("移除 logger: {}", dispatcher.unsubscribe(logger));
+    println!("再移除一次: {}", dispatcher.unsubscribe(logger));
+    println!(
+        "{:?}",
+        dispatcher
+            .dispatch(&Message::Write(String::from("Hi")))
+            .into_result()
+    );
+
+    println!("✅ 沒有 fallback");
+    let mut silent: Dispatcher<()> = Dispatcher::default();
+    println!(
+        "unhandled: {}",
+        silent.dispatch(&Message::Quit).is_unhandled()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // 回傳一個 handler，被呼叫時把 name 記到 log
+    fn recorder(
+        log: &Rc<RefCell<Vec<&'static str>>>,
+        name: &'static str,
+    ) -> impl Handler<&'static str> {
+        let log = Rc::clone(log);
+        move |_: &Message| {
+            log.borrow_mut().push(name);
+            name
+        }
+    }
+
+    #[test]
+    fn kind_of_each_message() {
+        assert_eq!(Message::Quit.kind(), MessageKind::Quit);
+        assert_eq!(Message::Move { x: 1, y: 2 }.kind(), MessageKind::Move);
+        assert_eq!(Message::Write(String::new()).kind(), MessageKind::Write);
+        assert_eq!(
+            Message::ChangeColor(Color::WHITE).kind(),
+            MessageKind::ChangeColor
+        );
+    }
+
+    #[test]
+    fn handlers_run_in_subscription_order() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut dispatcher = Dispatcher::new();
+        dispatcher.subscribe(MessageKind::Move, recorder(&log, "a"));
+        dispatcher.subscribe(MessageKind::Write, recorder(&log, "w"));
+        dispatcher.subscribe(MessageKind::Move, recorder(&log, "b"));
+        dispatcher.subscribe(MessageKind::Move, recorder(&log, "c"));
+        assert_eq!(dispatcher.subscribers(MessageKind::Move), 3);
+        assert_eq!(dispatcher.subscribers(MessageKind::Quit), 0);
+
+        let outcome = dispatcher.dispatch(&Message::Move { x: 0, y: 0 });
+        assert_eq!(
+            outcome,
+            Outcome {
+                kind: MessageKind::Move,
+                results: vec!["a", "b", "c"],
+                fallback: false,
+            }
+        );
+        assert_eq!(*log.borrow(), ["a", "b", "c"]);
+
+        let outcomes = dispatcher.dispatch_all(&[
+            Message::Write(String::from("x")),
+            Message::Move { x: 1, y: 1 },
+        ]);
+        assert_eq!(outcomes[0].results, ["w"]);
+        assert_eq!(outcomes[1].results, ["a", "b", "c"]);
+        assert_eq!(*log.borrow(), ["a", "b", "c", "w", "a", "b", "c"]);
+    }
+
+    #[test]
+    fn fallback_only_runs_without_subscribers() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut dispatcher = Dispatcher::new();
+        let quit = Message::Quit;
+        assert!(dispatcher.dispatch(&quit).is_unhandled());
+        assert!(!dispatcher.dispatch(&quit).fallback);
+
+        dispatcher.subscribe(MessageKind::Move, recorder(&log, "move"));
+        dispatcher.set_fallback(recorder(&log, "fallback"));
+        let outcome = dispatcher.dispatch(&quit);
+        assert!(outcome.fallback);
+        assert_eq!(outcome.results, ["fallback"]);
+        assert!(!outcome.is_unhandled());
+
+        let outcome = dispatcher.dispatch(&Message::Move { x: 0, y: 0 });
+        assert!(!outcome.fallback);
+        assert_eq!(outcome.results, ["move"]);
+        assert_eq!(*log.borrow(), ["fallback", "move"]);
+    }
+
+    #[test]
+    fn unsubscribe_removes_one_handler() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut dispatcher = Dispatcher::new();
+        let a = dispatcher.subscribe(MessageKind::Write, recorder(&log, "a"));
+        let b = dispatcher.subscribe(MessageKind::Write, recorder(&log, "b"));
+        dispatcher.set_fallback(recorder(&log, "fallback"));
+
+        assert!(dispatcher.unsubscribe(a));
+        assert!(!dispatcher.unsubscribe(a));
+        assert_eq!(dispatcher.subscribers(MessageKind::Write), 1);
+        let write = Message::Write(String::from("hi"));
+        assert_eq!(dispatcher.dispatch(&write).results, ["b"]);
+
+        // 全部移除後改由 fallback 處理
+        assert!(dispatcher.unsubscribe(b));
+        let outcome = dispatcher.dispatch(&write);
+        assert!(outcome.fallback);
+        assert_eq!(outcome.results, ["fallback"]);
+        assert!(!dispatcher.unsubscribe(HandlerId(99)));
+    }
+
+    #[test]
+    fn stateful_handlers_keep_state() {
+        let mut dispatcher = Dispatcher::new();
+        dispatcher.subscribe(
+            MessageKind::Move,
+            Odometer {
+                position: (0, 0),
+                distance: 0.0,
+            },
+        );
+        dispatcher.dispatch(&Message::Move { x: 3, y: 4 });
+        let outcome = dispatcher.dispatch(&Message::Move { x: 6, y: 8 });
+        assert_eq!(outcome.results, [Ok(String::from("總距離 10.0"))]);
+    }
+
+    #[test]
+    fn into_result_collects_all_errors() {
+        let outcome: Outcome<Result<i32, &str>> = Outcome {
+            kind: MessageKind::Quit,
+            results: vec![Ok(1), Ok(2)],
+            fallback: false,
+        };
+        assert_eq!(outcome.into_result(), Ok(vec![1, 2]));
+
+        let outcome: Outcome<Result<i32, &str>> = Outcome {
+            kind: MessageKind::Quit,
+            results: vec![Err("x"), Ok(1), Err("y")],
+            fallback: false,
+        };
+        assert_eq!(outcome.into_result(), Err(vec!["x", "y"]));
+
+        // 沒有 handler 時沒有錯誤，結果是空的 Ok
+        let mut dispatcher: Dispatcher<Result<i32, String>> = Dispatcher::new();
+        assert_eq!(
+            dispatcher.dispatch(&Message::Quit).into_result(),
+            Ok(vec![])
+        );
+    }
+}
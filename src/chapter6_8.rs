@@ -0,0 +1,558 @@
+// TCP Transport（透過 TCP 在行程之間傳送 Message）
+use crate::chapter6::Message;
+use crate::chapter6_10::Color;
+use crate::chapter6_6::{decode, encode};
+use crate::chapter6_7::Handler;
+use std::fmt;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// 每個 frame：長度（u32 big-endian）+ 內容，內容是 chapter6_6 的編碼
+const MAX_FRAME: usize = 16 * 1024 * 1024;
+
+// client 閒置超過這個時間沒送資料，server 就關閉連線，避免執行緒被一直佔住
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+// 最多保留幾個還沒被取走的連線錯誤，超過的直接丟掉
+const MAX_PENDING_ERRORS: usize = 64;
+
+#[derive(Debug)]
+pub enum TransportError {
+    Io(io::Error),
+    FrameTooLarge(usize),
+    InvalidAck,
+    Closed,   // 對方在回覆前關閉連線
+    TimedOut, // 等待資料逾時
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransportError::Io(err) => write!(f, "連線錯誤: {}", err),
+            TransportError::FrameTooLarge(len) => {
+                write!(f, "frame 長度 {} 超過上限 {}", len, MAX_FRAME)
+            }
+            TransportError::InvalidAck => write!(f, "無效的回覆"),
+            TransportError::Closed => write!(f, "連線已關閉"),
+            TransportError::TimedOut => write!(f, "等待資料逾時"),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+impl From<io::Error> for TransportError {
+    fn from(err: io::Error) -> TransportError {
+        // 設了 read timeout 的 socket 逾時時，不同平台回傳 WouldBlock 或 TimedOut
+        match err.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => TransportError::TimedOut,
+            _ => TransportError::Io(err),
+        }
+    }
+}
+
+pub fn write_frame<W: Write>(writer: &mut W, payload: &[u8]) -> Result<(), TransportError> {
+    if payload.len() > MAX_FRAME {
+        return Err(TransportError::FrameTooLarge(payload.len()));
+    }
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()?;
+    Ok(())
+}
+
+// 在 frame 開頭遇到 EOF 代表對方正常關閉，回傳 None
+pub fn read_frame<R: Read>(reader: &mut R) -> Result<Option<Vec<u8>>, TransportError> {
+    let mut header = [0u8; 4];
+    let mut filled = 0;
+    while filled < header.len() {
+        match reader.read(&mut header[filled..])? {
+            0 if filled == 0 => return Ok(None),
+            0 => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+            n => filled += n,
+        }
+    }
+    let len = u32::from_be_bytes(header) as usize;
+    if len > MAX_FRAME {
+        return Err(TransportError::FrameTooLarge(len));
+    }
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload)?;
+    Ok(Some(payload))
+}
+
+// server 對每則訊息的回覆
+#[derive(Debug, Clone, PartialEq)]
+pub enum Ack {
+    Ok(String),
+    Rejected(String), // handler 回傳錯誤或 panic，或訊息無法解碼
+}
+
+impl Ack {
+    fn to_bytes(&self) -> Vec<u8> {
+        let (status, text) = match self {
+            Ack::Ok(text) => (0u8, text),
+            Ack::Rejected(text) => (1u8, text),
+        };
+        let mut bytes = vec![status];
+        bytes.extend_from_slice(text.as_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Ack, TransportError> {
+        let (&status, text) = bytes.split_first().ok_or(TransportError::InvalidAck)?;
+        let text = String::from_utf8(text.to_vec()).map_err(|_| TransportError::InvalidAck)?;
+        match status {
+            0 => Ok(Ack::Ok(text)),
+            1 => Ok(Ack::Rejected(text)),
+            _ => Err(TransportError::InvalidAck),
+        }
+    }
+}
+
+type SharedHandler = Arc<Mutex<dyn Handler<Result<String, String>> + Send>>;
+
+// handler panic 時回覆 Rejected，連線和 server 都繼續運作
+// panic 會讓 mutex 變成 poisoned，之後的呼叫直接取回裡面的 handler
+fn call_handler(handler: &SharedHandler, message: &Message) -> Ack {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        handler
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .handle(message)
+    }));
+    match result {
+        Ok(Ok(text)) => Ack::Ok(text),
+        Ok(Err(text)) => Ack::Rejected(text),
+        Err(payload) => {
+            let reason = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("unknown");
+            Ack::Rejected(format!("handler panic: {}", reason))
+        }
+    }
+}
+
+// 一條連線：讀 frame -> 解碼 -> handler -> 回覆，收到 Quit 後結束
+// 超過 idle_timeout 沒收到資料就結束
+fn serve_connection(
+    stream: TcpStream,
+    handler: SharedHandler,
+    idle_timeout: Duration,
+) -> Result<(), TransportError> {
+    stream.set_read_timeout(Some(idle_timeout))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = BufWriter::new(stream);
+    while let Some(frame) = read_frame(&mut reader)? {
+        let (ack, quit) = match decode(&frame) {
+            Ok(message) => (call_handler(&handler, &message), message == Message::Quit),
+            // frame 的邊界還在，所以回覆錯誤後可以繼續讀下一則
+            Err(err) => (Ack::Rejected(err.to_string()), false),
+        };
+        write_frame(&mut writer, &ack.to_bytes())?;
+        if quit {
+            break;
+        }
+    }
+    Ok(())
+}
+
+pub struct Server {
+    listener: TcpListener,
+    idle_timeout: Duration,
+}
+
+pub struct ServerHandle {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+    errors: Receiver<TransportError>,
+}
+
+// 佇列滿了就丟掉，呼叫端沒有取走錯誤時不會無限制地佔用記憶體
+fn report(errors: &SyncSender<TransportError>, err: TransportError) {
+    let _ = errors.try_send(err);
+}
+
+impl Server {
+    // 用 127.0.0.1:0 讓系統挑一個可用的 port
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Server> {
+        Ok(Server {
+            listener: TcpListener::bind(addr)?,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+        })
+    }
+
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Server {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    // 在背景執行緒接受連線，每條連線一個執行緒，handler 由所有連線共用
+    pub fn spawn<H>(self, handler: H) -> io::Result<ServerHandle>
+    where
+        H: Handler<Result<String, String>> + Send + 'static,
+    {
+        let addr = self.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let handler: SharedHandler = Arc::new(Mutex::new(handler));
+        let stop_flag = Arc::clone(&stop);
+        let idle_timeout = self.idle_timeout;
+        let (error_tx, errors) = mpsc::sync_channel(MAX_PENDING_ERRORS);
+        let thread = thread::spawn(move || {
+            for stream in self.listener.incoming() {
+                if stop_flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        report(&error_tx, TransportError::Io(err));
+                        continue;
+                    }
+                };
+                let handler = Arc::clone(&handler);
+                let error_tx = error_tx.clone();
+                // 閒置逾時是正常的結束方式，不算錯誤
+                thread::spawn(
+                    move || match serve_connection(stream, handler, idle_timeout) {
+                        Ok(()) | Err(TransportError::TimedOut) => {}
+                        Err(err) => report(&error_tx, err),
+                    },
+                );
+            }
+        });
+        Ok(ServerHandle {
+            addr,
+            stop,
+            thread,
+            errors,
+        })
+    }
+}
+
+impl ServerHandle {
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    // 取走目前為止連線中斷的錯誤（不會等待）
+    pub fn take_errors(&self) -> Vec<TransportError> {
+        self.errors.try_iter().collect()
+    }
+
+    // 停止接受新連線；已建立的連線會在 client 關閉後結束
+    pub fn shutdown(self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // accept 會一直等待，連一次自己讓它醒來檢查 stop
+        let _ = TcpStream::connect(self.addr);
+        let _ = self.thread.join();
+    }
+}
+
+pub struct Client {
+    reader: BufReader<TcpStream>,
+    writer: BufWriter<TcpStream>,
+}
+
+impl Client {
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<Client> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        Ok(Client {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: BufWriter::new(stream),
+        })
+    }
+
+    // 送出一則訊息並等待回覆
+    pub fn send(&mut self, message: &Message) -> Result<Ack, TransportError> {
+        self.send_raw(&encode(message))
+    }
+
+    // 直接送出任意 bytes，用來測試 server 對錯誤資料的處理
+    pub fn send_raw(&mut self, payload: &[u8]) -> Result<Ack, TransportError> {
+        write_frame(&mut self.writer, payload)?;
+        match read_frame(&mut self.reader)? {
+            Some(frame) => Ack::from_bytes(&frame),
+            None => Err(TransportError::Closed),
+        }
+    }
+}
+
+pub fn main() {
+    println!("✅ Server");
+    let received: Arc<Mutex<Vec<Message>>> = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&received);
+    let server = Server::bind("127.0.0.1:0").unwrap();
+    let handle = server
+        .spawn(move |message: &Message| {
+            log.lock().unwrap().push(message.clone());
+            match message {
+                Message::Write(text) if text.is_empty() => Err(String::from("空字串")),
+                Message::Quit => Ok(String::from("bye")),
+                _ => Ok(format!("收到 {:?}", message)),
+            }
+        })
+        .unwrap();
+    println!("listening on {}", handle.addr());
+
+    println!("✅ Client");
+    let mut client = Client::connect(handle.addr()).unwrap();
+    for message in [
+        Message::Move { x: 1, y: 2 },
+        Message::Write(String::from("Hello")),
+        Message::Write(String::new()),
+        Message::ChangeColor(Color::rgb(0, 128, 255)),
+    ] {
+        println!("{:?} -> {:?}", message, client.send(&message).unwrap());
+    }
+    println!(
+        "壞掉的資料 -> {:?}",
+        client.send_raw(&[0x09, 0x00]).unwrap()
+    );
+    println!("Quit -> {:?}", client.send(&Message::Quit).unwrap());
+    // server 已經關閉這條連線
+    println!(
+        "Quit 之後送出失敗: {}",
+        client.send(&Message::Quit).is_err()
+    );
+
+    println!("✅ 多個 client 同時連線");
+    let addr = handle.addr();
+    let workers: Vec<_> = (0..4)
+        .map(|id| {
+            thread::spawn(move || {
+                let mut client = Client::connect(addr).unwrap();
+                for i in 0..25 {
+                    let ack = client.send(&Message::Move { x: id, y: i }).unwrap();
+                    assert!(matches!(ack, Ack::Ok(_)));
+                }
+                client.send(&Message::Quit).unwrap();
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    for err in handle.take_errors() {
+        println!("連線中斷: {}", err);
+    }
+    handle.shutdown();
+
+    let received = received.lock().unwrap();
+    let moves = received
+        .iter()
+        .filter(|m| matches!(m, Message::Move { .. }))
+        .count();
+    println!(
+        "server 收到 {} 則訊息，其中 Move {} 則",
+        received.len(),
+        moves
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn echo_server(idle_timeout: Duration) -> (ServerHandle, Arc<Mutex<Vec<Message>>>) {
+        let received: Arc<Mutex<Vec<Message>>> = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&received);
+        let handle = Server::bind("127.0.0.1:0")
+            .unwrap()
+            .with_idle_timeout(idle_timeout)
+            .spawn(move |message: &Message| {
+                log.lock().unwrap().push(message.clone());
+                match message {
+                    Message::Write(text) if text.is_empty() => Err(String::from("空字串")),
+                    _ => Ok(format!("{:?}", message)),
+                }
+            })
+            .unwrap();
+        (handle, received)
+    }
+
+    #[test]
+    fn frame_round_trip_and_limits() {
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, b"hello").unwrap();
+        write_frame(&mut buffer, b"").unwrap();
+        let mut reader = &buffer[..];
+        assert_eq!(read_frame(&mut reader).unwrap(), Some(b"hello".to_vec()));
+        assert_eq!(read_frame(&mut reader).unwrap(), Some(vec![]));
+        assert_eq!(read_frame(&mut reader).unwrap(), None);
+
+        let mut truncated = &[0u8, 0, 0, 5, b'h'][..];
+        assert!(matches!(
+            read_frame(&mut truncated),
+            Err(TransportError::Io(_))
+        ));
+        let mut huge = &(MAX_FRAME as u32 + 1).to_be_bytes()[..];
+        assert!(matches!(
+            read_frame(&mut huge),
+            Err(TransportError::FrameTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn client_and_server_over_loopback() {
+        let (handle, received) = echo_server(DEFAULT_IDLE_TIMEOUT);
+        assert!(handle.addr().ip().is_loopback());
+        assert_ne!(handle.addr().port(), 0);
+
+        let mut client = Client::connect(handle.addr()).unwrap();
+        let hello = Message::Write(String::from("Hello"));
+        assert_eq!(
+            client.send(&hello).unwrap(),
+            Ack::Ok(format!("{:?}", hello))
+        );
+        assert_eq!(
+            client.send(&Message::Write(String::new())).unwrap(),
+            Ack::Rejected(String::from("空字串"))
+        );
+        // 解不開的訊息回覆錯誤，但連線還能繼續用
+        assert!(matches!(
+            client.send_raw(&[0x09, 0x00]).unwrap(),
+            Ack::Rejected(_)
+        ));
+        assert!(matches!(
+            client.send(&Message::Move { x: 1, y: 2 }).unwrap(),
+            Ack::Ok(_)
+        ));
+        assert!(matches!(client.send(&Message::Quit).unwrap(), Ack::Ok(_)));
+        // server 在 Quit 之後關閉連線
+        assert!(client.send(&Message::Quit).is_err());
+
+        handle.shutdown();
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![
+                hello,
+                Message::Write(String::new()),
+                Message::Move { x: 1, y: 2 },
+                Message::Quit
+            ]
+        );
+    }
+
+    #[test]
+    fn concurrent_clients() {
+        let (handle, received) = echo_server(DEFAULT_IDLE_TIMEOUT);
+        let addr = handle.addr();
+        let workers: Vec<_> = (0..4)
+            .map(|id| {
+                thread::spawn(move || {
+                    let mut client = Client::connect(addr).unwrap();
+                    for i in 0..25 {
+                        let ack = client.send(&Message::Move { x: id, y: i }).unwrap();
+                        assert!(matches!(ack, Ack::Ok(_)));
+                    }
+                    client.send(&Message::Quit).unwrap();
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        handle.shutdown();
+        assert_eq!(received.lock().unwrap().len(), 4 * 26);
+    }
+
+    #[test]
+    fn idle_client_is_disconnected() {
+        let (handle, received) = echo_server(Duration::from_millis(100));
+        let mut client = Client::connect(handle.addr()).unwrap();
+        assert!(client.send(&Message::Move { x: 0, y: 0 }).is_ok());
+        // 閒置超過 idle timeout，server 端已經關閉連線
+        thread::sleep(Duration::from_millis(400));
+        assert!(client.send(&Message::Move { x: 1, y: 1 }).is_err());
+        handle.shutdown();
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    // 等 server 端的連線執行緒回報錯誤
+    fn wait_for_errors(handle: &ServerHandle) -> Vec<TransportError> {
+        for _ in 0..50 {
+            let errors = handle.take_errors();
+            if !errors.is_empty() {
+                return errors;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        Vec::new()
+    }
+
+    #[test]
+    fn panicking_handler_is_rejected_and_recovers() {
+        let handle = Server::bind("127.0.0.1:0")
+            .unwrap()
+            .spawn(|message: &Message| match message {
+                Message::Write(text) if text == "panic" => panic!("boom"),
+                _ => Ok(String::from("ok")),
+            })
+            .unwrap();
+        let panic_message = Message::Write(String::from("panic"));
+
+        let mut client = Client::connect(handle.addr()).unwrap();
+        assert_eq!(
+            client.send(&panic_message).unwrap(),
+            Ack::Rejected(String::from("handler panic: boom"))
+        );
+        // 同一條連線和新的連線都還能使用 handler
+        assert_eq!(
+            client.send(&Message::Quit).unwrap(),
+            Ack::Ok(String::from("ok"))
+        );
+        let mut other = Client::connect(handle.addr()).unwrap();
+        assert!(matches!(
+            other.send(&panic_message).unwrap(),
+            Ack::Rejected(_)
+        ));
+        assert_eq!(
+            other.send(&Message::Quit).unwrap(),
+            Ack::Ok(String::from("ok"))
+        );
+
+        assert!(handle.take_errors().is_empty());
+        handle.shutdown();
+    }
+
+    #[test]
+    fn connection_errors_are_reported() {
+        let (handle, _) = echo_server(DEFAULT_IDLE_TIMEOUT);
+        let mut stream = TcpStream::connect(handle.addr()).unwrap();
+        stream
+            .write_all(&(MAX_FRAME as u32 + 1).to_be_bytes())
+            .unwrap();
+        let errors = wait_for_errors(&handle);
+        assert!(
+            matches!(errors[..], [TransportError::FrameTooLarge(_)]),
+            "{:?}",
+            errors
+        );
+        assert!(handle.take_errors().is_empty());
+        handle.shutdown();
+    }
+
+    #[test]
+    fn idle_timeout_is_not_an_error() {
+        let (handle, _) = echo_server(Duration::from_millis(50));
+        let _client = Client::connect(handle.addr()).unwrap();
+        thread::sleep(Duration::from_millis(300));
+        assert!(handle.take_errors().is_empty());
+        handle.shutdown();
+    }
+}
@@ -0,0 +1,864 @@
+// Turtle（用 Message 當作繪圖指令，輸出 PPM / PNG 圖檔）
+use crate::chapter6::Message;
+use crate::chapter6_10::{Color, ColorError};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::str::FromStr;
+
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize, background: Color) -> Canvas {
+        Canvas {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> Option<Color> {
+        self.index(x, y).map(|i| self.pixels[i])
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            None
+        } else {
+            Some(y as usize * self.width + x as usize)
+        }
+    }
+
+    // 半透明的顏色會和原本的像素混合；超出畫布的點直接忽略
+    pub fn set(&mut self, x: i32, y: i32, color: Color) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = color.over(self.pixels[i]);
+        }
+    }
+
+    // Liang–Barsky：把線段裁到畫布範圍內，避免極大座標時逐點走很久
+    fn clip(&self, from: (i32, i32), to: (i32, i32)) -> Option<((i64, i64), (i64, i64))> {
+        let (x0, y0) = (from.0 as f64, from.1 as f64);
+        let (dx, dy) = (to.0 as f64 - x0, to.1 as f64 - y0);
+        let (max_x, max_y) = (self.width as f64 - 1.0, self.height as f64 - 1.0);
+        let (mut t0, mut t1) = (0.0f64, 1.0f64);
+        for (p, q) in [(-dx, x0), (dx, max_x - x0), (-dy, y0), (dy, max_y - y0)] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+            } else {
+                let t = q / p;
+                if p < 0.0 {
+                    t0 = t0.max(t);
+                } else {
+                    t1 = t1.min(t);
+                }
+            }
+        }
+        if t0 > t1 {
+            return None;
+        }
+        let point = |t: f64| ((x0 + t * dx).round() as i64, (y0 + t * dy).round() as i64);
+        Some((point(t0), point(t1)))
+    }
+
+    // Bresenham 直線演算法，兩端點都會畫到
+    pub fn line(&mut self, from: (i32, i32), to: (i32, i32), color: Color) {
+        let Some(((mut x, mut y), (x1, y1))) = self.clip(from, to) else {
+            return;
+        };
+        let dx = (x1 - x).abs();
+        let dy = -(y1 - y).abs();
+        let sx = if x < x1 { 1 } else { -1 };
+        let sy = if y < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        loop {
+            self.set(x as i32, y as i32, color);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    // 3x5 點陣字，小寫會轉成大寫，沒有字形的字元畫成實心方塊
+    // 座標用 i64 計算，位置接近 i32 邊界或 scale 很大時也不會溢位
+    pub fn text(&mut self, x: i32, y: i32, text: &str, scale: i32, color: Color) {
+        if scale <= 0 {
+            return;
+        }
+        let scale = scale as i64;
+        let mut cursor = x as i64;
+        for c in text.chars() {
+            // 之後的字都在畫布右邊
+            if cursor >= self.width as i64 {
+                break;
+            }
+            let rows = glyph(c.to_ascii_uppercase());
+            for (row, bits) in rows.iter().enumerate() {
+                for col in 0..3 {
+                    if bits & (0b100 >> col) != 0 {
+                        let (px, py) = (cursor + col * scale, y as i64 + row as i64 * scale);
+                        self.fill_square(px, py, scale, color);
+                    }
+                }
+            }
+            cursor += 4 * scale;
+        }
+    }
+
+    // 只畫方塊落在畫布內的部分
+    fn fill_square(&mut self, x: i64, y: i64, size: i64, color: Color) {
+        let (x0, x1) = (x.max(0), (x + size).min(self.width as i64));
+        let (y0, y1) = (y.max(0), (y + size).min(self.height as i64));
+        for py in y0..y1 {
+            for px in x0..x1 {
+                self.set(px as i32, py as i32, color);
+            }
+        }
+    }
+
+    // P6（二進位）PPM：標頭之後依序是每個像素的 RGB，沒有 alpha
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for pixel in &self.pixels {
+            bytes.extend_from_slice(&[pixel.r, pixel.g, pixel.b]);
+        }
+        bytes
+    }
+
+    pub fn save_ppm(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_ppm())
+    }
+
+    // 8-bit RGB 的 PNG，每列的 filter 都是 None，zlib 資料用不壓縮的 stored block
+    // 檔案和 PPM 差不多大，但不需要額外的壓縮套件
+    // PNG 的寬高必須是 1 到 2^31 - 1，超出範圍回傳 None
+    pub fn to_png(&self) -> Option<Vec<u8>> {
+        const MAX_SIDE: usize = i32::MAX as usize;
+        if !(1..=MAX_SIDE).contains(&self.width) || !(1..=MAX_SIDE).contains(&self.height) {
+            return None;
+        }
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // bit depth 8、color type 2（RGB）、壓縮 0、filter 0、不交錯
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut raw = Vec::with_capacity(self.height * (1 + self.width * 3));
+        for row in self.pixels.chunks(self.width) {
+            raw.push(0); // filter: None
+            for pixel in row {
+                raw.extend_from_slice(&[pixel.r, pixel.g, pixel.b]);
+            }
+        }
+
+        let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut bytes, b"IHDR", &header);
+        png_chunk(&mut bytes, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut bytes, b"IEND", &[]);
+        Some(bytes)
+    }
+
+    pub fn save_png(&self, path: &str) -> io::Result<()> {
+        let bytes = self
+            .to_png()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "畫布大小無法存成 PNG"))?;
+        fs::write(path, bytes)
+    }
+}
+
+// 每個 chunk：長度 + 類型 + 資料 + CRC（涵蓋類型和資料）
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+// zlib 標頭 + deflate stored block（每塊最多 65535 bytes）+ Adler-32
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(last as u8); // BFINAL，BTYPE = 00
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b110, 0b001, 0b010, 0b100, 0b111],
+        '3' => [0b110, 0b001, 0b010, 0b001, 0b110],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b110, 0b001, 0b110],
+        '6' => [0b011, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b110],
+        ' ' => [0; 5],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        '!' => [0b010, 0b010, 0b010, 0b000, 0b010],
+        '?' => [0b110, 0b001, 0b010, 0b000, 0b010],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        _ => [0b111; 5],
+    }
+}
+
+// Message 之外的畫筆控制，只能從腳本或直接呼叫使用
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    Send(Message),
+    PenUp,   // 之後的 Move 只移動不畫線
+    PenDown, // 恢復畫線
+}
+
+pub struct Turtle {
+    pub canvas: Canvas,
+    position: (i32, i32),
+    color: Color,
+    pen_down: bool,
+    font_scale: i32,
+    stopped: bool,
+}
+
+impl Turtle {
+    // 畫筆從 (0, 0) 開始，預設黑色、落筆
+    pub fn new(canvas: Canvas) -> Turtle {
+        Turtle {
+            canvas,
+            position: (0, 0),
+            color: Color::BLACK,
+            pen_down: true,
+            font_scale: 1,
+            stopped: false,
+        }
+    }
+
+    pub fn with_font_scale(mut self, scale: i32) -> Turtle {
+        self.font_scale = scale.max(1);
+        self
+    }
+
+    pub fn position(&self) -> (i32, i32) {
+        self.position
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    // 收到 Quit 之後忽略所有訊息，回傳這則訊息是否有被執行
+    pub fn execute(&mut self, message: &Message) -> bool {
+        if self.stopped {
+            return false;
+        }
+        match message {
+            Message::Quit => self.stopped = true,
+            Message::Move { x, y } => {
+                if self.pen_down {
+                    self.canvas.line(self.position, (*x, *y), self.color);
+                }
+                self.position = (*x, *y);
+            }
+            // 標籤的左上角在畫筆位置，畫筆不移動
+            Message::Write(text) => {
+                let (x, y) = self.position;
+                self.canvas.text(x, y, text, self.font_scale, self.color);
+            }
+            Message::ChangeColor(color) => self.color = *color,
+        }
+        true
+    }
+
+    pub fn step(&mut self, step: &Step) -> bool {
+        match step {
+            Step::Send(message) => self.execute(message),
+            Step::PenUp | Step::PenDown if self.stopped => false,
+            Step::PenUp => {
+                self.pen_down = false;
+                true
+            }
+            Step::PenDown => {
+                self.pen_down = true;
+                true
+            }
+        }
+    }
+
+    // 回傳實際執行的步數（遇到 Quit 之後的不算）
+    pub fn run(&mut self, script: &Script) -> usize {
+        script.steps.iter().filter(|s| self.step(s)).count()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptError {
+    pub line: usize, // 從 1 開始
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "第 {} 行: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+// 腳本格式，一行一個指令，# 開頭是註解：
+//   move 10 20
+//   color 255 0 0     （也可以寫 #ff0000 或 red，超出 0-255 是錯誤）
+//   write HELLO WORLD   （write 之後整行都是文字）
+//   up / down
+//   quit
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Script {
+    pub steps: Vec<Step>,
+}
+
+fn parse_ints<const N: usize>(args: &str) -> Result<[i32; N], String> {
+    let words: Vec<&str> = args.split_whitespace().collect();
+    if words.len() != N {
+        return Err(format!("需要 {} 個整數，拿到 {} 個", N, words.len()));
+    }
+    let mut values = [0; N];
+    for (value, word) in values.iter_mut().zip(&words) {
+        *value = word.parse().map_err(|_| format!("{:?} 不是整數", word))?;
+    }
+    Ok(values)
+}
+
+fn parse_color(args: &str) -> Result<Color, String> {
+    if args.split_whitespace().count() == 1 {
+        return args.parse().map_err(|e: ColorError| e.to_string());
+    }
+    let [r, g, b] = parse_ints::<3>(args)?;
+    Color::try_from_ints(r, g, b).map_err(|e| e.to_string())
+}
+
+impl FromStr for Script {
+    type Err = ScriptError;
+
+    fn from_str(s: &str) -> Result<Script, ScriptError> {
+        let mut steps = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+            let no_args = |step: Step| {
+                if args.trim().is_empty() {
+                    Ok(step)
+                } else {
+                    Err(format!("{} 不需要參數", command))
+                }
+            };
+            let step = match command {
+                "move" => parse_ints::<2>(args).map(|[x, y]| Step::Send(Message::Move { x, y })),
+                "color" => parse_color(args).map(|c| Step::Send(Message::ChangeColor(c))),
+                "write" => Ok(Step::Send(Message::Write(args.to_string()))),
+                "quit" => no_args(Step::Send(Message::Quit)),
+                "up" => no_args(Step::PenUp),
+                "down" => no_args(Step::PenDown),
+                _ => Err(format!("未知的指令 {:?}", command)),
+            };
+            steps.push(step.map_err(|message| ScriptError {
+                line: i + 1,
+                message,
+            })?);
+        }
+        Ok(Script { steps })
+    }
+}
+
+impl Script {
+    pub fn load(path: &str) -> Result<Script, Box<dyn std::error::Error>> {
+        Ok(fs::read_to_string(path)?.parse()?)
+    }
+}
+
+// 縮小後用字元預覽畫布，非背景色的格子印成 #
+fn preview(canvas: &Canvas, background: Color, step: usize) {
+    for y in (0..canvas.height()).step_by(step) {
+        let row: String = (0..canvas.width())
+            .step_by(step)
+            .map(|x| {
+                let ink = (0..step).any(|dy| {
+                    (0..step).any(|dx| {
+                        canvas
+                            .get((x + dx) as i32, (y + dy) as i32)
+                            .is_some_and(|c| c != background)
+                    })
+                });
+                if ink {
+                    '#'
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        println!("{}", row);
+    }
+}
+
+pub fn main() {
+    println!("✅ Messages");
+    let white = Color::WHITE;
+    let mut turtle = Turtle::new(Canvas::new(40, 20, white));
+    let messages = [
+        Message::ChangeColor(Color::rgb(255, 0, 0)),
+        Message::Move { x: 39, y: 0 },
+        Message::Move { x: 39, y: 19 },
+        Message::ChangeColor(Color::clamped(999, -1, 0)), // 截斷成 #ff0000
+        Message::Move { x: 0, y: 19 },
+        Message::Move { x: 0, y: 0 },
+        Message::Move {
+            x: i32::MIN,
+            y: i32::MAX,
+        }, // 裁切後只畫畫布內的部分
+        Message::Quit,
+        Message::Move { x: 20, y: 10 }, // Quit 之後不執行
+    ];
+    let executed = messages.iter().filter(|m| turtle.execute(m)).count();
+    println!(
+        "executed {} of {}, stopped: {}, position: {:?}, color: {}",
+        executed,
+        messages.len(),
+        turtle.is_stopped(),
+        turtle.position(),
+        turtle.color()
+    );
+    preview(&turtle.canvas, white, 2);
+
+    println!("✅ Script");
+    let source = "\
+# 外框
+color navy
+up
+move 2 2
+down
+move 57 2
+move 57 17
+move 2 17
+move 2 2
+# 文字
+up
+move 6 6
+color #c00000
+write Hello, Rust!
+move 6 11
+write 2024-06-01
+quit
+write 不會被執行
+";
+    let script: Script = source.parse().unwrap();
+    let mut turtle = Turtle::new(Canvas::new(60, 20, white));
+    println!(
+        "{} steps, executed {}",
+        script.steps.len(),
+        turtle.run(&script)
+    );
+    preview(&turtle.canvas, white, 1);
+
+    let path = std::env::temp_dir().join("chapter6_9.ppm");
+    let path = path.to_str().unwrap();
+    turtle.canvas.save_ppm(path).unwrap();
+    println!(
+        "saved {} ({} bytes)",
+        path,
+        fs::metadata(path).unwrap().len()
+    );
+    let path = std::env::temp_dir().join("chapter6_9.png");
+    let path = path.to_str().unwrap();
+    turtle.canvas.save_png(path).unwrap();
+    println!(
+        "saved {} ({} bytes)",
+        path,
+        fs::metadata(path).unwrap().len()
+    );
+
+    println!("✅ Script errors");
+    for bad in [
+        "move 1",
+        "move 1 two",
+        "color 1 2 3 4",
+        "color 300 0 0",
+        "color #12",
+        "jump 1 2",
+        "quit now",
+    ] {
+        println!("{:?}: {}", bad, bad.parse::<Script>().unwrap_err());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn painted(canvas: &Canvas) -> usize {
+        let mut count = 0;
+        for y in 0..canvas.height() as i32 {
+            for x in 0..canvas.width() as i32 {
+                if canvas.get(x, y) != Some(Color::WHITE) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn text_at_extreme_positions_does_not_overflow() {
+        let mut turtle = Turtle::new(Canvas::new(8, 8, Color::WHITE));
+        turtle.step(&Step::PenUp);
+        turtle.execute(&Message::Move {
+            x: i32::MIN,
+            y: i32::MAX,
+        });
+        assert!(turtle.execute(&Message::Write(String::from("A"))));
+        assert_eq!(painted(&turtle.canvas), 0);
+
+        let mut canvas = Canvas::new(8, 8, Color::WHITE);
+        canvas.text(i32::MAX, i32::MAX, "HELLO", i32::MAX, Color::BLACK);
+        canvas.text(i32::MIN, i32::MIN, "HELLO", i32::MAX, Color::BLACK);
+        canvas.text(i32::MAX - 3, 0, "WWWW", 2, Color::BLACK);
+        assert_eq!(painted(&canvas), 0);
+    }
+
+    #[test]
+    fn text_is_clipped_to_the_canvas() {
+        // I 的第一列是實心，scale 很大時蓋滿整張畫布
+        let mut canvas = Canvas::new(8, 8, Color::WHITE);
+        canvas.text(0, 0, "I", i32::MAX, Color::BLACK);
+        assert_eq!(painted(&canvas), 64);
+
+        // 只露出右下角的一部分
+        let mut canvas = Canvas::new(8, 8, Color::WHITE);
+        canvas.text(-2, -4, "I", 1, Color::BLACK);
+        assert_eq!(painted(&canvas), 1);
+        assert_eq!(canvas.get(0, 0), Some(Color::BLACK));
+
+        let mut canvas = Canvas::new(8, 8, Color::WHITE);
+        canvas.text(0, 0, "I", 0, Color::BLACK);
+        canvas.text(0, 0, "I", -3, Color::BLACK);
+        assert_eq!(painted(&canvas), 0);
+    }
+
+    #[test]
+    fn text_layout() {
+        let mut canvas = Canvas::new(16, 10, Color::WHITE);
+        canvas.text(0, 0, "hi", 2, Color::BLACK);
+        // H 的左上角
+        assert_eq!(canvas.get(0, 0), Some(Color::BLACK));
+        assert_eq!(canvas.get(1, 1), Some(Color::BLACK));
+        assert_eq!(canvas.get(2, 0), Some(Color::WHITE));
+        // 第二個字從 x = 4 * scale 開始，I 的第一列是實心
+        assert_eq!(canvas.get(8, 0), Some(Color::BLACK));
+        assert_eq!(canvas.get(13, 1), Some(Color::BLACK));
+        assert_eq!(canvas.get(14, 0), Some(Color::WHITE));
+        // H 有 11 個點、I 有 9 個點，每個點是 2x2
+        assert_eq!(painted(&canvas), (11 + 9) * 4);
+    }
+
+    #[test]
+    fn script_errors_have_line_numbers() {
+        let cases = [
+            ("move 1", 1, "需要 2 個整數，拿到 1 個"),
+            ("up\nmove 1 two", 2, "\"two\" 不是整數"),
+            ("# comment\n\ncolor 1 2 3 4", 3, "需要 3 個整數，拿到 4 個"),
+            ("move 1 2\njump 1 2", 2, "未知的指令 \"jump\""),
+            ("quit now", 1, "quit 不需要參數"),
+            ("down\nup 1", 2, "up 不需要參數"),
+        ];
+        for (input, line, message) in cases {
+            assert_eq!(
+                input.parse::<Script>(),
+                Err(ScriptError {
+                    line,
+                    message: String::from(message)
+                }),
+                "{:?}",
+                input
+            );
+        }
+        // 顏色的錯誤訊息來自 chapter6_10
+        for (input, line) in [
+            ("color 300 0 0", 1),
+            ("up\ncolor #12", 2),
+            ("color nope", 1),
+        ] {
+            assert_eq!(
+                input.parse::<Script>().unwrap_err().line,
+                line,
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn script_steps() {
+        let script: Script = "\
+  # 註解和空行會略過
+
+move -3 4
+color 255 0 0
+color red
+write  Hello World
+up
+down
+quit
+"
+        .parse()
+        .unwrap();
+        assert_eq!(
+            script.steps,
+            vec![
+                Step::Send(Message::Move { x: -3, y: 4 }),
+                Step::Send(Message::ChangeColor(Color::rgb(255, 0, 0))),
+                Step::Send(Message::ChangeColor(Color::rgb(255, 0, 0))),
+                Step::Send(Message::Write(String::from(" Hello World"))),
+                Step::PenUp,
+                Step::PenDown,
+                Step::Send(Message::Quit),
+            ]
+        );
+    }
+
+    #[test]
+    fn quit_stops_the_turtle() {
+        let script: Script = "move 3 0\nquit\nmove 3 3\nup".parse().unwrap();
+        let mut turtle = Turtle::new(Canvas::new(4, 4, Color::WHITE));
+        assert_eq!(turtle.run(&script), 2);
+        assert!(turtle.is_stopped());
+        assert_eq!(turtle.position(), (3, 0));
+        assert_eq!(painted(&turtle.canvas), 4);
+    }
+
+    #[test]
+    fn lines_are_clipped_at_the_edges() {
+        let black = Color::BLACK;
+        // 水平線穿過整張畫布
+        let mut canvas = Canvas::new(8, 8, Color::WHITE);
+        canvas.line((-10, 5), (20, 5), black);
+        assert_eq!(painted(&canvas), 8);
+        assert!((0..8).all(|x| canvas.get(x, 5) == Some(black)));
+
+        // 對角線只畫畫布內的 8 個點
+        let mut canvas = Canvas::new(8, 8, Color::WHITE);
+        canvas.line((-5, -5), (20, 20), black);
+        assert_eq!(painted(&canvas), 8);
+        assert!((0..8).all(|i| canvas.get(i, i) == Some(black)));
+
+        // 完全在畫布外的線段
+        let mut canvas = Canvas::new(8, 8, Color::WHITE);
+        canvas.line((-5, 0), (-1, 7), black);
+        canvas.line((8, -1), (20, -1), black);
+        canvas.line((-3, 10), (10, 30), black);
+        assert_eq!(painted(&canvas), 0);
+
+        // 極大座標也會很快結束，兩端點在畫布內時都會畫到
+        let mut canvas = Canvas::new(8, 8, Color::WHITE);
+        canvas.line((i32::MIN, 3), (i32::MAX, 3), black);
+        canvas.line((2, i32::MIN), (2, i32::MAX), black);
+        assert_eq!(painted(&canvas), 15);
+        let mut canvas = Canvas::new(8, 8, Color::WHITE);
+        canvas.line((1, 1), (6, 3), black);
+        assert_eq!(canvas.get(1, 1), Some(black));
+        assert_eq!(canvas.get(6, 3), Some(black));
+    }
+
+    #[test]
+    fn ppm_header_and_length() {
+        let mut canvas = Canvas::new(4, 3, Color::WHITE);
+        canvas.set(1, 2, Color::rgb(1, 2, 3));
+        let ppm = canvas.to_ppm();
+        let header = b"P6\n4 3\n255\n";
+        assert!(ppm.starts_with(header));
+        assert_eq!(ppm.len(), header.len() + 4 * 3 * 3);
+        // (1, 2) 是第 2 * 4 + 1 個像素
+        let offset = header.len() + (2 * 4 + 1) * 3;
+        assert_eq!(ppm[offset..offset + 3], [1, 2, 3]);
+        assert_eq!(ppm[header.len()..header.len() + 3], [255, 255, 255]);
+    }
+
+    // 讀出所有 chunk 並檢查 CRC
+    fn png_chunks(png: &[u8]) -> Vec<([u8; 4], Vec<u8>)> {
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        let mut chunks = Vec::new();
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let len = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let body = &rest[4..8 + len];
+            let crc = u32::from_be_bytes(rest[8 + len..12 + len].try_into().unwrap());
+            assert_eq!(crc32(body), crc);
+            chunks.push((body[..4].try_into().unwrap(), body[4..].to_vec()));
+            rest = &rest[12 + len..];
+        }
+        chunks
+    }
+
+    // 只支援 stored block 的 inflate
+    fn inflate_stored(zlib: &[u8]) -> Vec<u8> {
+        assert_eq!(zlib[..2], [0x78, 0x01]);
+        let mut out = Vec::new();
+        let mut i = 2;
+        loop {
+            let last = zlib[i] & 1 == 1;
+            assert_eq!(zlib[i] >> 1, 0, "只應該有 stored block");
+            let len = u16::from_le_bytes([zlib[i + 1], zlib[i + 2]]);
+            let nlen = u16::from_le_bytes([zlib[i + 3], zlib[i + 4]]);
+            assert_eq!(len, !nlen);
+            out.extend_from_slice(&zlib[i + 5..i + 5 + len as usize]);
+            i += 5 + len as usize;
+            if last {
+                break;
+            }
+        }
+        assert_eq!(zlib[i..], adler32(&out).to_be_bytes());
+        out
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        assert_eq!(inflate_stored(&zlib_stored(&[])), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn png_layout() {
+        let mut canvas = Canvas::new(3, 2, Color::WHITE);
+        canvas.set(2, 1, Color::rgb(10, 20, 30));
+        let chunks = png_chunks(&canvas.to_png().unwrap());
+        let kinds: Vec<&[u8; 4]> = chunks.iter().map(|(kind, _)| kind).collect();
+        assert_eq!(kinds, [b"IHDR", b"IDAT", b"IEND"]);
+        assert_eq!(chunks[0].1, [0, 0, 0, 3, 0, 0, 0, 2, 8, 2, 0, 0, 0]);
+        assert_eq!(
+            inflate_stored(&chunks[1].1),
+            [
+                0, 255, 255, 255, 255, 255, 255, 255, 255, 255, //
+                0, 255, 255, 255, 255, 255, 255, 10, 20, 30,
+            ]
+        );
+        assert!(chunks[2].1.is_empty());
+    }
+
+    #[test]
+    fn large_png_uses_several_blocks() {
+        let canvas = Canvas::new(200, 200, Color::rgb(1, 2, 3));
+        let chunks = png_chunks(&canvas.to_png().unwrap());
+        let raw = inflate_stored(&chunks[1].1);
+        assert_eq!(raw.len(), 200 * (1 + 200 * 3));
+        assert!(raw.len() > u16::MAX as usize);
+        for row in raw.chunks(1 + 200 * 3) {
+            assert_eq!(row[0], 0);
+            assert!(row[1..].chunks(3).all(|p| p == [1, 2, 3]));
+        }
+    }
+
+    #[test]
+    fn empty_canvas_is_not_a_png() {
+        assert_eq!(Canvas::new(0, 4, Color::WHITE).to_png(), None);
+        assert_eq!(Canvas::new(4, 0, Color::WHITE).to_png(), None);
+        let path = std::env::temp_dir().join("chapter6_9_empty.png");
+        let err = Canvas::new(0, 0, Color::WHITE)
+            .save_png(path.to_str().unwrap())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -5,15 +5,86 @@ mod chapter10_4;
 mod chapter2;
 mod chapter3;
 mod chapter5;
+mod chapter5_10;
+mod chapter5_11;
+mod chapter5_12;
+mod chapter5_2;
+mod chapter5_3;
+mod chapter5_4;
+mod chapter5_5;
+mod chapter5_6;
+mod chapter5_7;
+mod chapter5_8;
+mod chapter5_9;
 mod chapter6;
+mod chapter6_10;
+mod chapter6_11;
+mod chapter6_12;
+mod chapter6_13;
+mod chapter6_14;
+mod chapter6_2;
+mod chapter6_3;
+mod chapter6_4;
+mod chapter6_5;
+mod chapter6_6;
+mod chapter6_7;
+mod chapter6_8;
+mod chapter6_9;
 
 // !windows 屏蔽 warning : $env:RUSTFLAGS="-Awarnings" cargo r
 // !mac 屏蔽 warning : RUSTFLAGS="-Awarnings" cargo run
 
 pub fn main() {
+    // cargo run -- check rules.txt < addresses.txt
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("check") => match chapter6_5::check_command(&args[1..]) {
+            Ok(0) => return,
+            Ok(_) => std::process::exit(1),
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(2);
+            }
+        },
+        // cargo run -- vend script.txt
+        Some("vend") => {
+            if let Err(err) = chapter6_13::vend_command(&args[1..]) {
+                eprintln!("{}", err);
+                std::process::exit(2);
+            }
+            return;
+        }
+        _ => {}
+    }
+
     // chapter2::main();
     // chapter3::main();
     // chapter5::main();
+    // chapter5_2::main();
+    // chapter5_3::main();
+    // chapter5_4::main();
+    // chapter5_5::main();
+    // chapter5_6::main();
+    // chapter5_7::main();
+    // chapter5_8::main();
+    // chapter5_9::main();
+    // chapter5_10::main();
+    // chapter5_11::main();
+    // chapter5_12::main();
+    // chapter6::main();
+    // chapter6_2::main();
+    // chapter6_3::main();
+    // chapter6_4::main();
+    // chapter6_5::main();
+    // chapter6_6::main();
+    // chapter6_7::main();
+    // chapter6_8::main();
+    // chapter6_9::main();
+    // chapter6_10::main();
+    // chapter6_11::main();
+    // chapter6_12::main();
+    // chapter6_13::main();
+    // chapter6_14::main();
     // chapter10::main();
     // chapter10_2::main();
     // chapter10_3::main();